@@ -0,0 +1,135 @@
+use std::io;
+use futures::Stream;
+use futures::stream;
+use tokio::net::UdpSocket;
+use tokio_util::sync::CancellationToken;
+use crate::event_system::Dispatcher;
+use crate::models::DecodeMode;
+use crate::packets::{decode_with_mode, Packets, MAX_DATAGRAM_SIZE};
+use crate::server::{ReceiveCounters, ReceiveStats, DEFAULT_ADDRESS};
+
+pub struct AsyncServer {
+    pub socket: UdpSocket,
+    address: String,
+    shutdown: CancellationToken,
+    dispatcher: Dispatcher,
+    decode_mode: DecodeMode,
+    counters: ReceiveCounters,
+}
+
+impl AsyncServer {
+    pub async fn new_with_address(address: &str) -> io::Result<AsyncServer> {
+        Ok(AsyncServer {
+            socket: UdpSocket::bind(address).await?,
+            address: address.to_string(),
+            shutdown: CancellationToken::new(),
+            dispatcher: Dispatcher::global().clone(),
+            decode_mode: DecodeMode::Strict,
+            counters: ReceiveCounters::default(),
+        })
+    }
+
+    pub async fn new() -> io::Result<AsyncServer> {
+        Self::new_with_address(DEFAULT_ADDRESS).await
+    }
+
+    /// Replaces the token used to stop the server, so it can be shared with other tasks.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> AsyncServer {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn with_dispatcher(mut self, dispatcher: Dispatcher) -> AsyncServer {
+        self.dispatcher = dispatcher;
+        self
+    }
+
+    pub fn with_decode_mode(mut self, decode_mode: DecodeMode) -> AsyncServer {
+        self.decode_mode = decode_mode;
+        self
+    }
+
+    pub fn dispatcher(&self) -> &Dispatcher {
+        &self.dispatcher
+    }
+
+    /// Returns a handle that stops [`AsyncServer::start`] and ends [`AsyncServer::into_stream`] when cancelled.
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub async fn read_packet(&self) -> io::Result<Packets> {
+        let mut buf = [0; MAX_DATAGRAM_SIZE];
+        let len = self.socket.recv(&mut buf).await?;
+
+        let packet = decode_with_mode(&buf[..len], self.decode_mode);
+        self.counters.count(&packet);
+        Ok(packet?)
+    }
+
+    /// Counters of the datagrams received so far.
+    pub fn stats(&self) -> ReceiveStats {
+        self.counters.snapshot()
+    }
+
+    /// Reads packets until the shutdown token is cancelled, returns `None` once it is.
+    pub async fn next_packet(&self) -> Option<io::Result<Packets>> {
+        tokio::select! {
+            _ = self.shutdown.cancelled() => None,
+            packet = self.read_packet() => Some(packet),
+        }
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = io::Result<Packets>> {
+        stream::unfold(self, |server| async move {
+            let packet = server.next_packet().await?;
+            Some((packet, server))
+        })
+    }
+
+    pub async fn start(&self) {
+        println!("Listening on {}", self.address);
+        while let Some(packet) = self.next_packet().await {
+            match packet {
+                Ok(Packets::Unknown) => println!("Unknown packet"),
+                Ok(packet) => self.dispatcher.dispatch(&packet),
+                Err(e) => println!("Error: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use super::*;
+    use crate::models::MotionPacket;
+    use crate::models::traits::Packet;
+
+    #[tokio::test]
+    async fn streams_packets_until_cancelled() {
+        let server = AsyncServer::new_with_address("127.0.0.1:0").await.unwrap();
+        let target = server.socket.local_addr().unwrap();
+        let shutdown = server.shutdown_token();
+
+        let mut datagram = vec![0; MotionPacket::PACKET_SIZE];
+        datagram[0..2].copy_from_slice(&2022u16.to_le_bytes());
+        datagram[5] = MotionPacket::PACKET_ID;
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.send_to(&datagram, target).await.unwrap();
+
+        let mut packets = Box::pin(server.into_stream());
+        match packets.next().await {
+            Some(Ok(Packets::Motion(packet))) => assert_eq!(packet.header.packet_format, 2022),
+            _ => panic!("Expected a motion packet"),
+        }
+
+        shutdown.cancel();
+        assert!(packets.next().await.is_none());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, ErrorKind};
+use std::net::UdpSocket;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use f1_2022_udp_server::diagnostics::PacketDiagnostics;
+use f1_2022_udp_server::models::DecodeMode;
+use f1_2022_udp_server::packets::{packet_id_by_name, packet_name, PacketFilter, Packets, Selection, PACKET_NAMES};
+use f1_2022_udp_server::recording::{Recorder, Replayer, ReplaySpeed};
+use f1_2022_udp_server::server::{Server, DEFAULT_ADDRESS};
+
+#[derive(Parser)]
+#[command(name = "f1-telemetry", version, about = "Listen to, record and replay F1 telemetry")]
+struct Cli {
+    /// Address to receive telemetry on.
+    #[arg(short, long, global = true, default_value = DEFAULT_ADDRESS)]
+    address: String,
+
+    /// Keep unknown enum values instead of rejecting the packet.
+    #[arg(long, global = true)]
+    lenient: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Pretty-print decoded packets.
+    Listen(Filter),
+    /// Print every decoded packet on a single line.
+    Dump {
+        /// Print JSON lines instead of debug output.
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        filter: Filter,
+    },
+    /// Write received datagrams to a capture file.
+    Record {
+        file: PathBuf,
+        /// Stop after this many seconds.
+        #[arg(long)]
+        duration: Option<u64>,
+    },
+    /// Play a capture file back.
+    Replay {
+        file: PathBuf,
+        /// Playback speed, 0 replays as fast as possible.
+        #[arg(long, default_value_t = 1.0)]
+        speed: f32,
+        /// Send the datagrams to this address instead of printing them.
+        #[arg(long)]
+        target: Option<String>,
+        #[command(flatten)]
+        filter: Filter,
+    },
+    /// Show the packet rates per packet id.
+    Stats {
+        /// Seconds between reports.
+        #[arg(long, default_value_t = 1)]
+        interval: u64,
+    },
+}
+
+#[derive(Args, Clone)]
+struct Filter {
+    /// Only show these packet types, may be repeated.
+    #[arg(short, long = "packet", value_parser = parse_packet_name)]
+    packets: Vec<u8>,
+
+    /// Only show the data of this car index.
+    #[arg(short, long)]
+    car: Option<usize>,
+}
+
+impl Filter {
+    fn packet_filter(&self) -> PacketFilter {
+        PacketFilter {
+            packets: Some(self.packets.clone()).filter(|packets| !packets.is_empty()),
+            car: self.car,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Format {
+    Pretty,
+    Line,
+    Json,
+}
+
+fn parse_packet_name(name: &str) -> Result<u8, String> {
+    packet_id_by_name(name).ok_or_else(|| {
+        let names: Vec<&str> = PACKET_NAMES.iter().map(|(_, name)| *name).collect();
+        format!("unknown packet type, expected one of: {}", names.join(", "))
+    })
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn run(cli: Cli) -> io::Result<()> {
+    let decode_mode = if cli.lenient { DecodeMode::Lenient } else { DecodeMode::Strict };
+
+    match cli.command {
+        Command::Listen(filter) => listen(&server(&cli.address, decode_mode)?, &filter.packet_filter(), Format::Pretty),
+        Command::Dump { json, filter } => {
+            let format = if json { Format::Json } else { Format::Line };
+            listen(&server(&cli.address, decode_mode)?, &filter.packet_filter(), format)
+        },
+        Command::Record { file, duration } => record(&cli.address, decode_mode, file, duration),
+        Command::Replay { file, speed, target, filter } => replay(file, speed, target, &filter.packet_filter(), decode_mode),
+        Command::Stats { interval } => {
+            let server = server(&cli.address, decode_mode)?.with_diagnostics(PacketDiagnostics::new());
+            stats(&server, Duration::from_secs(interval.max(1)))
+        },
+    }
+}
+
+fn server(address: &str, decode_mode: DecodeMode) -> io::Result<Server> {
+    // The server keeps the address for its whole lifetime, which is the lifetime of the process here.
+    let address: &'static str = Box::leak(address.to_owned().into_boxed_str());
+    let server = Server::new_with_address(address)?.with_decode_mode(decode_mode);
+    eprintln!("Listening on {}", address);
+    Ok(server)
+}
+
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+fn listen(server: &Server, filter: &PacketFilter, format: Format) -> io::Result<()> {
+    loop {
+        match server.read_packet() {
+            Ok(packet) => print_packet(&packet, filter, format)?,
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+}
+
+fn record(address: &str, decode_mode: DecodeMode, file: PathBuf, duration: Option<u64>) -> io::Result<()> {
+    // Unbuffered, so an interrupted capture loses at most the record being written, which
+    // replaying drops.
+    let recorder = Recorder::new(File::create(&file)?)?;
+    let mut server = server(address, decode_mode)?.with_recorder(recorder);
+    server.stream.set_read_timeout(Some(Duration::from_millis(200)))?;
+    eprintln!("Recording to {}", file.display());
+
+    let started = Instant::now();
+    let deadline = duration.map(Duration::from_secs);
+    let mut received = 0u64;
+    while deadline.is_none_or(|deadline| started.elapsed() < deadline) {
+        match server.read_packet() {
+            Ok(_) => received += 1,
+            Err(e) if is_timeout(&e) => {},
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+
+    server.stop_recording()?;
+    eprintln!("Recorded {} packets", received);
+    Ok(())
+}
+
+fn replay(file: PathBuf, speed: f32, target: Option<String>, filter: &PacketFilter, decode_mode: DecodeMode) -> io::Result<()> {
+    let mut replayer = Replayer::open(file)?;
+    let speed = match speed {
+        speed if speed <= 0.0 => ReplaySpeed::AsFastAsPossible,
+        1.0 => ReplaySpeed::RealTime,
+        speed => ReplaySpeed::Multiplier(speed),
+    };
+
+    if let Some(target) = target {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        return replayer.send_to(speed, &socket, target);
+    }
+
+    let mut result = Ok(());
+    replayer.play(speed, |record| match record.decode_with_mode(decode_mode) {
+        Ok(packet) if result.is_ok() => result = print_packet(&packet, filter, Format::Pretty),
+        Ok(_) => {},
+        Err(e) => eprintln!("Error: {}", e),
+    });
+    result
+}
+
+fn stats(server: &Server, interval: Duration) -> io::Result<()> {
+    server.stream.set_read_timeout(Some(Duration::from_millis(200)))?;
+
+    let mut counts: BTreeMap<Option<u8>, u64> = BTreeMap::new();
+    let mut errors = 0u64;
+    let mut since = Instant::now();
+    loop {
+        match server.read_packet() {
+            Ok(packet) => *counts.entry(packet.packet_id()).or_default() += 1,
+            Err(e) if is_timeout(&e) => {},
+            Err(_) => errors += 1,
+        }
+
+        let elapsed = since.elapsed();
+        if elapsed >= interval {
+            println!("{:<22} {:>8} {:>10}", "packet", "count", "per second");
+            for (packet_id, count) in &counts {
+                let name = packet_id.and_then(packet_name).unwrap_or("unknown");
+                let id = packet_id.map_or("?".to_owned(), |id| id.to_string());
+                println!("{:<22} {:>8} {:>10.1}", format!("{} ({})", name, id), count, *count as f64 / elapsed.as_secs_f64());
+            }
+            if errors > 0 {
+                println!("{:<22} {:>8} {:>10.1}", "errors", errors, errors as f64 / elapsed.as_secs_f64());
+            }
+            let total = server.stats();
+            println!("total: {} decoded, {} unknown, {} truncated, {} oversized, {} invalid",
+                total.decoded, total.unknown, total.truncated, total.oversized, total.invalid);
+            for stream in server.diagnostics().iter().filter(|stream| stream.lost + stream.reordered + stream.duplicates > 0) {
+                println!("{}", stream);
+            }
+            println!();
+
+            counts.clear();
+            errors = 0;
+            since = Instant::now();
+        }
+    }
+}
+
+fn print_packet(packet: &Packets, filter: &PacketFilter, format: Format) -> io::Result<()> {
+    match filter.select(packet) {
+        Some(Selection::Packet(packet)) => output(packet, format),
+        Some(Selection::Car(data)) => output(&data, format),
+        None => Ok(()),
+    }
+}
+
+fn output<T: Debug + Serialize + ?Sized>(value: &T, format: Format) -> io::Result<()> {
+    match format {
+        Format::Pretty => println!("{:#?}", value),
+        Format::Line => println!("{:?}", value),
+        Format::Json => println!("{}", serde_json::to_string(value)?),
+    }
+    Ok(())
+}
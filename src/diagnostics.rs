@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::models::{EventDetails, PacketEventData, PacketHeader};
+use crate::models::traits::Packet;
+use crate::packets::packet_name;
+
+/// Number of expected datagrams the rolling loss percentage is computed over.
+pub const DEFAULT_WINDOW: usize = 500;
+
+/// Frames a datagram may arrive late before it is taken as the start of a new stream, which
+/// happens after flashbacks whose event was missed.
+const REORDER_FRAMES: u32 = 120;
+
+/// Counters of the datagrams of one packet type in one session.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct StreamStats {
+    pub session_uid: u64,
+    pub packet_id: u8,
+    pub received: u64,
+    /// Datagrams missing from the frame sequence, late arrivals are subtracted again.
+    pub lost: u64,
+    /// Datagrams that arrived after a datagram of a later frame.
+    pub reordered: u64,
+    /// Datagrams of a frame that was already received.
+    pub duplicates: u64,
+    /// Loss over the last expected datagrams of the window, in percent.
+    pub loss_percentage: f32,
+    /// Datagrams per second of session time.
+    pub rate: f32,
+}
+
+impl fmt::Display for StreamStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) of session {:x}: {} received, {} lost ({:.1}%), {} reordered, {} duplicates, {:.1}/s",
+            packet_name(self.packet_id).unwrap_or("unknown"),
+            self.packet_id,
+            self.session_uid,
+            self.received,
+            self.lost,
+            self.loss_percentage,
+            self.reordered,
+            self.duplicates,
+            self.rate,
+        )
+    }
+}
+
+/// Diagnostics of all streams, emitted by [`crate::server::Server::publish_diagnostics`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticsReport {
+    pub streams: Vec<StreamStats>,
+}
+
+#[derive(Debug, Clone)]
+struct Stream {
+    received: u64,
+    lost: u64,
+    reordered: u64,
+    duplicates: u64,
+    /// Smallest step between frames seen so far, the send interval of the packet type.
+    step: Option<u32>,
+    last_frame: u32,
+    first_session_time: f32,
+    last_session_time: f32,
+    recent: VecDeque<(u32, f32)>,
+    /// Whether each of the last expected datagrams arrived.
+    window: VecDeque<bool>,
+    /// The next datagram starts the frame sequence again, set by flashbacks.
+    rebase: bool,
+}
+
+impl Stream {
+    fn new(header: &PacketHeader) -> Stream {
+        Stream {
+            received: 0,
+            lost: 0,
+            reordered: 0,
+            duplicates: 0,
+            step: None,
+            last_frame: header.frame_identifier,
+            first_session_time: header.session_time,
+            last_session_time: header.session_time,
+            recent: VecDeque::new(),
+            window: VecDeque::new(),
+            rebase: false,
+        }
+    }
+
+    fn push(&mut self, arrived: bool, size: usize) {
+        self.window.push_back(arrived);
+        while self.window.len() > size {
+            self.window.pop_front();
+        }
+    }
+
+    fn record(&mut self, header: &PacketHeader, window: usize) {
+        let frame = header.frame_identifier;
+        let key = (frame, header.session_time);
+
+        if self.rebase {
+            self.rebase = false;
+            self.recent.clear();
+            self.last_frame = frame;
+        }
+
+        if self.recent.contains(&key) {
+            self.duplicates += 1;
+            return;
+        }
+        self.recent.push_back(key);
+        if self.recent.len() > 64 {
+            self.recent.pop_front();
+        }
+        self.received += 1;
+
+        if self.received > 1 && frame == self.last_frame {
+            // The frame doesn't advance while the game is in the menus, only the session time.
+            self.last_session_time = self.last_session_time.max(header.session_time);
+            return;
+        }
+
+        if self.received == 1 {
+            self.push(true, window);
+            return;
+        }
+
+        if frame < self.last_frame && self.last_frame - frame <= REORDER_FRAMES {
+            self.reordered += 1;
+            // A late datagram was counted as lost when the gap was seen.
+            if let Some(missing) = self.window.iter_mut().rev().find(|arrived| !**arrived) {
+                *missing = true;
+                self.lost = self.lost.saturating_sub(1);
+            }
+            return;
+        }
+
+        if frame < self.last_frame {
+            // The game went back, the frames continue from there.
+            self.last_frame = frame;
+            self.last_session_time = header.session_time;
+            self.push(true, window);
+            return;
+        }
+
+        let gap = frame - self.last_frame;
+        if gap > 0 {
+            let step = *self.step.get_or_insert(gap);
+            self.step = Some(step.min(gap));
+            // Send intervals that don't divide the frame rate alternate between two steps, so
+            // only whole missing steps count as lost.
+            let missing = (gap / step).saturating_sub(1) as u64;
+            self.lost += missing;
+            for _ in 0..missing {
+                self.push(false, window);
+            }
+        }
+        self.push(true, window);
+        self.last_frame = frame;
+        self.last_session_time = self.last_session_time.max(header.session_time);
+    }
+
+    fn stats(&self, session_uid: u64, packet_id: u8) -> StreamStats {
+        let lost = self.window.iter().filter(|arrived| !**arrived).count();
+        let duration = self.last_session_time - self.first_session_time;
+
+        StreamStats {
+            session_uid,
+            packet_id,
+            received: self.received,
+            lost: self.lost,
+            reordered: self.reordered,
+            duplicates: self.duplicates,
+            loss_percentage: match self.window.len() {
+                0 => 0.0,
+                len => lost as f32 * 100.0 / len as f32,
+            },
+            rate: if duration > 0.0 { self.received as f32 / duration } else { 0.0 },
+        }
+    }
+}
+
+/// Detects lost, reordered and duplicate datagrams per packet type and session.
+///
+/// Datagrams are ordered by their frame identifier. The send interval of each packet type is
+/// learned from the smallest step between frames, gaps of several steps count as lost
+/// datagrams. A datagram with the frame and session time of a recent one is a duplicate, one
+/// that only advances the session time is received without being classified.
+/// Events are sent irregularly and aren't tracked, a flashback event starts the frame sequence
+/// of its session again.
+#[derive(Debug, Clone)]
+pub struct PacketDiagnostics {
+    window: usize,
+    streams: BTreeMap<(u64, u8), Stream>,
+}
+
+impl Default for PacketDiagnostics {
+    fn default() -> PacketDiagnostics {
+        PacketDiagnostics::with_window(DEFAULT_WINDOW)
+    }
+}
+
+impl PacketDiagnostics {
+    pub fn new() -> PacketDiagnostics {
+        PacketDiagnostics::default()
+    }
+
+    /// Computes the rolling loss percentage over the last `window` expected datagrams.
+    pub fn with_window(window: usize) -> PacketDiagnostics {
+        PacketDiagnostics {
+            window: window.max(1),
+            streams: BTreeMap::new(),
+        }
+    }
+
+    /// Creates diagnostics fed by every packet dispatched by `dispatcher`.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<PacketDiagnostics>>, Subscription) {
+        event_system::share(PacketDiagnostics::new(), dispatcher, |diagnostics, packet| {
+            if let Some(header) = packet.header() {
+                diagnostics.record(header);
+                if matches!(packet.event_details(), Some(EventDetails::Flashback(_))) {
+                    diagnostics.flashback(header.session_uid);
+                }
+            }
+        })
+    }
+
+    pub fn record(&mut self, header: &PacketHeader) {
+        if header.packet_id == PacketEventData::PACKET_ID {
+            return;
+        }
+
+        let window = self.window;
+        self.streams.entry((header.session_uid, header.packet_id))
+            .or_insert_with(|| Stream::new(header))
+            .record(header, window);
+    }
+
+    /// Takes the next datagram of every stream of `session_uid` as the new start of its frame
+    /// sequence, the game continues from an earlier frame after a flashback.
+    pub fn flashback(&mut self, session_uid: u64) {
+        self.streams.iter_mut()
+            .filter(|((session, _), _)| *session == session_uid)
+            .for_each(|(_, stream)| stream.rebase = true);
+    }
+
+    pub fn stream(&self, session_uid: u64, packet_id: u8) -> Option<StreamStats> {
+        self.streams.get(&(session_uid, packet_id)).map(|stream| stream.stats(session_uid, packet_id))
+    }
+
+    /// Stats of all streams, sorted by session and packet id.
+    pub fn stats(&self) -> Vec<StreamStats> {
+        self.streams.iter()
+            .map(|((session_uid, packet_id), stream)| stream.stats(*session_uid, *packet_id))
+            .collect()
+    }
+
+    pub fn report(&self) -> DiagnosticsReport {
+        DiagnosticsReport {
+            streams: self.stats(),
+        }
+    }
+
+    /// Forgets the streams of every session but `session_uid`.
+    pub fn retain_session(&mut self, session_uid: u64) {
+        self.streams.retain(|(session, _), _| *session == session_uid);
+    }
+
+    pub fn reset(&mut self) {
+        self.streams.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PacketLapData;
+    use crate::testing;
+
+    fn header(packet_id: u8, frame_identifier: u32) -> PacketHeader {
+        PacketHeader {
+            session_uid: 9,
+            frame_identifier,
+            ..testing::header(packet_id, frame_identifier as f32 / 60.0)
+        }
+    }
+
+    #[test]
+    fn counts_gaps_reordering_and_duplicates() {
+        let mut diagnostics = PacketDiagnostics::new();
+        let lap_data = PacketLapData::PACKET_ID;
+
+        // Sent every second frame, frame 6 and 8 go missing, 8 shows up late and 4 twice.
+        for frame in [0, 2, 4, 10, 8, 4, 12] {
+            diagnostics.record(&header(lap_data, frame));
+        }
+        diagnostics.record(&header(3, 5));
+
+        let stats = diagnostics.stream(9, lap_data).unwrap();
+        assert_eq!(stats.received, 6);
+        assert_eq!(stats.lost, 1);
+        assert_eq!(stats.reordered, 1);
+        assert_eq!(stats.duplicates, 1);
+        assert!((stats.loss_percentage - 100.0 / 7.0).abs() < 0.01);
+        assert_eq!(diagnostics.stats().len(), 1);
+    }
+
+    #[test]
+    fn flashbacks_restart_the_stream() {
+        let mut diagnostics = PacketDiagnostics::new();
+        for frame in [1000, 1001, 1002, 10, 11] {
+            diagnostics.record(&header(6, frame));
+        }
+        diagnostics.flashback(9);
+        for frame in [990, 991, 992] {
+            diagnostics.record(&header(6, frame));
+        }
+
+        let stats = diagnostics.stream(9, 6).unwrap();
+        assert_eq!((stats.received, stats.lost, stats.reordered, stats.duplicates), (8, 0, 0, 0));
+    }
+
+    #[test]
+    fn frames_that_dont_advance_are_not_duplicates() {
+        let mut diagnostics = PacketDiagnostics::new();
+        for session_time in [0.0, 0.5, 1.0, 1.5] {
+            diagnostics.record(&PacketHeader { session_time, ..header(9, 0) });
+        }
+
+        let stats = diagnostics.stream(9, 9).unwrap();
+        assert_eq!((stats.received, stats.duplicates, stats.lost), (4, 0, 0));
+    }
+
+    #[test]
+    fn counts_back_to_back_duplicates() {
+        let mut diagnostics = PacketDiagnostics::new();
+        for frame in [0, 2, 2, 4] {
+            diagnostics.record(&header(6, frame));
+        }
+
+        let stats = diagnostics.stream(9, 6).unwrap();
+        assert_eq!((stats.received, stats.duplicates, stats.lost), (3, 1, 0));
+    }
+}
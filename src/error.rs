@@ -0,0 +1,86 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The datagram ended before the field could be read.
+    UnexpectedEof,
+    Io(io::ErrorKind),
+    /// The byte does not match any variant of the enum stored in the field.
+    InvalidDiscriminant(u8),
+    InvalidUtf8,
+    /// The header announces a game whose format isn't supported.
+    UnsupportedFormat(u16),
+    /// The datagram is shorter than the size of its packet type.
+    Truncated { expected: usize, actual: usize },
+    /// The datagram is longer than the size of its packet type.
+    Oversized { expected: usize, actual: usize },
+}
+
+/// Error returned when a packet could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub packet_id: Option<u8>,
+    /// Byte offset of the field from the start of the packet.
+    pub offset: usize,
+    pub field: &'static str,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(offset: usize, field: &'static str, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            packet_id: None,
+            offset,
+            field,
+            kind,
+        }
+    }
+
+    pub(crate) fn from_io(offset: usize, field: &'static str, error: io::Error) -> ParseError {
+        let kind = match error.kind() {
+            io::ErrorKind::UnexpectedEof => ParseErrorKind::UnexpectedEof,
+            kind => ParseErrorKind::Io(kind),
+        };
+        ParseError::new(offset, field, kind)
+    }
+
+    /// Sets the packet id if it is not known yet.
+    pub fn with_packet_id(mut self, packet_id: u8) -> ParseError {
+        self.packet_id.get_or_insert(packet_id);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::UnexpectedEof => write!(f, "unexpected end of data reading `{}`", self.field)?,
+            ParseErrorKind::Io(kind) => write!(f, "{} reading `{}`", io::Error::from(kind), self.field)?,
+            ParseErrorKind::InvalidDiscriminant(value) => write!(f, "invalid value {} for `{}`", value, self.field)?,
+            ParseErrorKind::InvalidUtf8 => write!(f, "invalid utf-8 in `{}`", self.field)?,
+            ParseErrorKind::UnsupportedFormat(format) => write!(f, "unsupported packet format {}", format)?,
+            ParseErrorKind::Truncated { expected, actual } => write!(f, "truncated datagram of {} bytes, expected {}", actual, expected)?,
+            ParseErrorKind::Oversized { expected, actual } => write!(f, "oversized datagram of {} bytes, expected {}", actual, expected)?,
+        }
+
+        write!(f, " at byte {}", self.offset)?;
+        if let Some(packet_id) = self.packet_id {
+            write!(f, " of packet {}", packet_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(error: ParseError) -> io::Error {
+        let kind = match error.kind {
+            ParseErrorKind::UnexpectedEof => io::ErrorKind::UnexpectedEof,
+            ParseErrorKind::Io(kind) => kind,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, error)
+    }
+}
\ No newline at end of file
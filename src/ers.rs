@@ -0,0 +1,384 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::frames::PacketKind;
+use crate::models::EventDetails;
+use crate::models::enums::ErsMode;
+use crate::packets::Packets;
+use crate::session_state::MAX_CARS;
+use crate::telemetry::{Frame, Status};
+
+/// Length in metres of the lap distance covered by one bin of a deployment profile.
+pub const DEFAULT_PROFILE_BIN: f32 = 50.0;
+
+/// Session time in seconds between two status packets above which the time isn't counted
+/// towards a mode, the game was paused or packets were lost.
+const MAX_SAMPLE_GAP: f32 = 1.0;
+
+/// Energy a car deployed and harvested in one lap, energy is in joules and times in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErsLap {
+    pub car_idx: u8,
+    pub lap: u8,
+    /// Whether the lap was followed from its start.
+    pub complete: bool,
+    pub deployed: f32,
+    pub harvested_mguk: f32,
+    pub harvested_mguh: f32,
+    /// Harvested minus deployed energy.
+    pub net: f32,
+    pub store_start: f32,
+    pub store_end: f32,
+    pub time_in_mode: Vec<(ErsMode, f32)>,
+    /// Energy deployed per bin of lap distance.
+    pub profile: Vec<f32>,
+}
+
+/// The energy balance of a car over its recorded laps, energy is in joules and times in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErsSummary {
+    pub car_idx: u8,
+    pub laps: u32,
+    pub deployed: f32,
+    pub harvested: f32,
+    pub net_balance: f32,
+    /// Time spent in each mode, the current lap included.
+    pub time_in_mode: Vec<(ErsMode, f32)>,
+    pub bin_size: f32,
+    /// Energy deployed per bin of lap distance, averaged over the complete laps.
+    pub average_profile: Vec<f32>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Sample {
+    session_time: f32,
+    mode: ErsMode,
+    store: f32,
+    deployed: f32,
+    harvested_mguk: f32,
+    harvested_mguh: f32,
+}
+
+impl Sample {
+    fn new(status: &Status, session_time: f32) -> Sample {
+        Sample {
+            session_time,
+            mode: status.ers_deploy_mode,
+            store: status.ers_store_energy,
+            deployed: status.ers_deployed_this_lap,
+            harvested_mguk: status.ers_harvested_this_lap_mguk,
+            harvested_mguh: status.ers_harvested_this_lap_mguh,
+        }
+    }
+
+    /// The game sets the lap totals back to zero when a lap starts.
+    fn was_reset(&self, previous: &Sample) -> bool {
+        self.deployed < previous.deployed
+            || self.harvested_mguk < previous.harvested_mguk
+            || self.harvested_mguh < previous.harvested_mguh
+    }
+}
+
+fn add_time(time_in_mode: &mut Vec<(ErsMode, f32)>, mode: ErsMode, time: f32) {
+    match time_in_mode.iter_mut().find(|(entry, _)| *entry == mode) {
+        Some((_, total)) => *total += time,
+        None => time_in_mode.push((mode, time)),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct CurrentLap {
+    lap: u8,
+    complete: bool,
+    store_start: f32,
+    /// The last sample before the totals were reset, when the game reset them before the lap
+    /// number changed.
+    totals: Option<Sample>,
+    time_in_mode: Vec<(ErsMode, f32)>,
+    profile: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarErs {
+    last: Option<Sample>,
+    current: CurrentLap,
+    /// The lap number changed before the totals were reset, the next reset belongs to the lap
+    /// already closed.
+    awaiting_reset: bool,
+    laps: Vec<ErsLap>,
+    time_in_mode: Vec<(ErsMode, f32)>,
+}
+
+impl CarErs {
+    fn record(&mut self, car_idx: u8, sample: Sample, lap: u8, lap_distance: f32, bin_size: f32) -> Option<ErsLap> {
+        let previous = match self.last.replace(sample) {
+            Some(previous) => previous,
+            None => {
+                let fresh = sample.deployed == 0.0 && sample.harvested_mguk == 0.0 && sample.harvested_mguh == 0.0;
+                self.current = CurrentLap { lap, complete: fresh, store_start: sample.store, ..CurrentLap::default() };
+                self.awaiting_reset = false;
+                return None;
+            },
+        };
+
+        let elapsed = sample.session_time - previous.session_time;
+        if elapsed > 0.0 && elapsed <= MAX_SAMPLE_GAP {
+            add_time(&mut self.current.time_in_mode, previous.mode, elapsed);
+            add_time(&mut self.time_in_mode, previous.mode, elapsed);
+        }
+
+        let reset = sample.was_reset(&previous);
+        if reset {
+            if self.awaiting_reset {
+                self.awaiting_reset = false;
+            } else if self.current.totals.is_none() {
+                self.current.totals = Some(previous);
+            }
+        }
+
+        let mut completed = None;
+        if lap != self.current.lap {
+            // Without a reset yet the latest sample holds the totals of the lap.
+            let totals = self.current.totals.unwrap_or(sample);
+            self.awaiting_reset = self.current.totals.is_none()
+                && (totals.deployed > 0.0 || totals.harvested_mguk > 0.0 || totals.harvested_mguh > 0.0);
+
+            let current = std::mem::replace(&mut self.current, CurrentLap {
+                lap,
+                complete: true,
+                store_start: totals.store,
+                ..CurrentLap::default()
+            });
+            let harvested = totals.harvested_mguk + totals.harvested_mguh;
+            completed = Some(ErsLap {
+                car_idx,
+                lap: current.lap,
+                complete: current.complete,
+                deployed: totals.deployed,
+                harvested_mguk: totals.harvested_mguk,
+                harvested_mguh: totals.harvested_mguh,
+                net: harvested - totals.deployed,
+                store_start: current.store_start,
+                store_end: totals.store,
+                time_in_mode: current.time_in_mode,
+                profile: current.profile,
+            });
+        }
+
+        let deployed = if reset { sample.deployed } else { sample.deployed - previous.deployed };
+        if deployed > 0.0 {
+            let bin = (lap_distance.max(0.0) / bin_size) as usize;
+            let profile = &mut self.current.profile;
+            if profile.len() <= bin {
+                profile.resize(bin + 1, 0.0);
+            }
+            profile[bin] += deployed;
+        }
+
+        if let Some(lap) = &completed {
+            self.laps.push(lap.clone());
+        }
+        completed
+    }
+}
+
+/// Records the ERS totals of every lap and where on the lap the energy is deployed.
+///
+/// A lap ends when the lap number of the car changes. The game resets the lap totals of the
+/// car status around the same time, the totals seen just before the reset are recorded as the
+/// lap. A flashback drops the lap in progress.
+#[derive(Debug, Clone)]
+pub struct ErsTracker {
+    bin_size: f32,
+    frame: Option<Frame>,
+    cars: Vec<CarErs>,
+}
+
+impl Default for ErsTracker {
+    fn default() -> ErsTracker {
+        ErsTracker::with_bin_size(DEFAULT_PROFILE_BIN)
+    }
+}
+
+impl ErsTracker {
+    pub fn new() -> ErsTracker {
+        ErsTracker::default()
+    }
+
+    /// Splits the deployment profiles into bins of `bin_size` metres.
+    pub fn with_bin_size(bin_size: f32) -> ErsTracker {
+        ErsTracker {
+            bin_size: bin_size.max(1.0),
+            frame: None,
+            cars: vec![CarErs::default(); MAX_CARS],
+        }
+    }
+
+    /// Creates a tracker fed by every packet dispatched by `dispatcher`, its completed laps are
+    /// emitted back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<ErsTracker>>, Subscription) {
+        ErsTracker::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<ErsTracker>>, Subscription) {
+        event_system::attach(self, dispatcher, ErsTracker::ingest, |lap, dispatcher| dispatcher.emit(lap))
+    }
+
+    /// The recorded laps of `car_idx` in the order they were driven.
+    pub fn laps(&self, car_idx: usize) -> &[ErsLap] {
+        self.cars.get(car_idx).map_or(&[], |car| &car.laps)
+    }
+
+    pub fn summary(&self, car_idx: usize) -> Option<ErsSummary> {
+        let car = self.cars.get(car_idx)?;
+        car.last?;
+
+        let deployed: f32 = car.laps.iter().map(|lap| lap.deployed).sum();
+        let harvested: f32 = car.laps.iter().map(|lap| lap.harvested_mguk + lap.harvested_mguh).sum();
+
+        let complete: Vec<&ErsLap> = car.laps.iter().filter(|lap| lap.complete).collect();
+        let mut average_profile = vec![0.0; complete.iter().map(|lap| lap.profile.len()).max().unwrap_or(0)];
+        for lap in &complete {
+            average_profile.iter_mut().zip(&lap.profile).for_each(|(average, deployed)| *average += deployed / complete.len() as f32);
+        }
+
+        Some(ErsSummary {
+            car_idx: car_idx as u8,
+            laps: car.laps.len() as u32,
+            deployed,
+            harvested,
+            net_balance: harvested - deployed,
+            time_in_mode: car.time_in_mode.clone(),
+            bin_size: self.bin_size,
+            average_profile,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        *self = ErsTracker::with_bin_size(self.bin_size);
+    }
+
+    /// Updates the laps in progress and returns the laps `packet` completed.
+    pub fn ingest(&mut self, packet: &Packets) -> Vec<ErsLap> {
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return vec![],
+        };
+        if self.frame.as_ref().is_some_and(|frame| frame.session_uid != header.session_uid) {
+            self.reset();
+        }
+
+        if matches!(packet.event_details(), Some(EventDetails::Flashback(_))) {
+            // The totals go back with the game, which must not be taken as a new lap.
+            self.cars.iter_mut().for_each(|car| car.last = None);
+            return vec![];
+        }
+
+        let frame = self.frame.get_or_insert_with(|| Frame::new(header));
+        if !frame.update(packet) || PacketKind::of(packet) != Some(PacketKind::CarStatus) {
+            return vec![];
+        }
+
+        let mut laps = vec![];
+        for (car_idx, (car, snapshot)) in self.cars.iter_mut().zip(&frame.cars).enumerate() {
+            if let (Some(status), Some(lap)) = (&snapshot.status, &snapshot.lap) {
+                let sample = Sample::new(status, header.session_time);
+                laps.extend(car.record(car_idx as u8, sample, lap.current_lap_num, lap.lap_distance, self.bin_size));
+            }
+        }
+
+        laps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketCarStatus, PacketLapData};
+    use crate::testing::packet;
+
+    fn lap(session_time: f32, lap_num: u8, lap_distance: f32) -> Packets {
+        let mut packet = packet::<PacketLapData>(session_time);
+        packet.lap_data[0].current_lap_num = lap_num;
+        packet.lap_data[0].lap_distance = lap_distance;
+        Packets::LapData(packet)
+    }
+
+    fn status(session_time: f32, mode: ErsMode, store: f32, harvested: f32, deployed: f32) -> Packets {
+        let mut packet = packet::<PacketCarStatus>(session_time);
+        let status = &mut packet.car_status_data[0];
+        status.ers_store_energy = store;
+        status.ers_deploy_mode = mode;
+        status.ers_harvested_this_lap_mguk = harvested;
+        status.ers_deployed_this_lap = deployed;
+        Packets::CarStatus(packet)
+    }
+
+    fn drive(tracker: &mut ErsTracker, session_time: f32, lap_num: u8, lap_distance: f32, mode: ErsMode, totals: (f32, f32, f32)) -> Vec<ErsLap> {
+        tracker.ingest(&lap(session_time, lap_num, lap_distance));
+        let (store, harvested, deployed) = totals;
+        tracker.ingest(&status(session_time, mode, store, harvested, deployed))
+    }
+
+    #[test]
+    fn records_laps_before_the_reset() {
+        let mut tracker = ErsTracker::with_bin_size(100.0);
+
+        assert!(drive(&mut tracker, 0.0, 1, 0.0, ErsMode::Medium, (4e6, 0.0, 0.0)).is_empty());
+        drive(&mut tracker, 0.5, 1, 150.0, ErsMode::Overtake, (3.9e6, 0.0, 1e5));
+        drive(&mut tracker, 1.0, 1, 250.0, ErsMode::Medium, (3.7e6, 0.0, 3e5));
+        drive(&mut tracker, 1.5, 1, 350.0, ErsMode::Medium, (3.8e6, 2e5, 3e5));
+        let laps = drive(&mut tracker, 2.0, 2, 10.0, ErsMode::Medium, (3.8e6, 0.0, 5e4));
+
+        let lap = &laps[0];
+        assert!(lap.complete);
+        assert_eq!((lap.lap, lap.deployed, lap.harvested_mguk, lap.net), (1, 3e5, 2e5, -1e5));
+        assert_eq!((lap.store_start, lap.store_end), (4e6, 3.8e6));
+        assert_eq!(lap.profile, [0.0, 1e5, 2e5]);
+        assert_eq!(lap.time_in_mode, [(ErsMode::Medium, 1.5), (ErsMode::Overtake, 0.5)]);
+
+        // The energy deployed after the reset belongs to the new lap.
+        drive(&mut tracker, 2.5, 2, 60.0, ErsMode::Medium, (3.8e6, 1e5, 5e4));
+        let laps = drive(&mut tracker, 3.0, 3, 0.0, ErsMode::Medium, (3.9e6, 0.0, 0.0));
+        assert_eq!(laps[0].profile, [5e4]);
+
+        let summary = tracker.summary(0).unwrap();
+        assert_eq!(summary.laps, 2);
+        assert_eq!(summary.net_balance, 3e5 - 3.5e5);
+        assert_eq!(summary.average_profile, [2.5e4, 5e4, 1e5]);
+        assert_eq!(summary.time_in_mode, [(ErsMode::Medium, 2.5), (ErsMode::Overtake, 0.5)]);
+    }
+
+    #[test]
+    fn closes_laps_on_the_lap_number() {
+        let mut tracker = ErsTracker::with_bin_size(100.0);
+        drive(&mut tracker, 0.0, 1, 0.0, ErsMode::Medium, (4e6, 0.0, 0.0));
+        drive(&mut tracker, 0.5, 1, 150.0, ErsMode::Medium, (3.9e6, 0.0, 1e5));
+
+        // The lap number changes a packet before the totals are reset.
+        let laps = drive(&mut tracker, 1.0, 2, 10.0, ErsMode::Medium, (3.9e6, 0.0, 1e5));
+        assert_eq!((laps[0].lap, laps[0].deployed, laps[0].store_end), (1, 1e5, 3.9e6));
+        assert!(drive(&mut tracker, 1.5, 2, 60.0, ErsMode::None, (3.9e6, 0.0, 0.0)).is_empty());
+
+        // A lap without any deployment or harvesting is still recorded.
+        let laps = drive(&mut tracker, 2.0, 3, 0.0, ErsMode::None, (3.9e6, 0.0, 0.0));
+        assert_eq!((laps[0].lap, laps[0].deployed, laps[0].net), (2, 0.0, 0.0));
+        assert!(laps[0].profile.is_empty());
+
+        // The totals are reset a packet before the lap number changes.
+        drive(&mut tracker, 2.5, 3, 250.0, ErsMode::Medium, (3.8e6, 0.0, 2e5));
+        drive(&mut tracker, 3.0, 3, 4990.0, ErsMode::Medium, (3.8e6, 0.0, 0.0));
+        let laps = drive(&mut tracker, 3.5, 4, 5.0, ErsMode::Medium, (3.8e6, 0.0, 0.0));
+        assert_eq!((laps[0].lap, laps[0].deployed, laps[0].profile[2]), (3, 2e5, 2e5));
+        assert_eq!(tracker.laps(0).len(), 3);
+    }
+
+    #[test]
+    fn laps_joined_mid_way_are_incomplete() {
+        let mut tracker = ErsTracker::new();
+        drive(&mut tracker, 0.0, 4, 2000.0, ErsMode::Medium, (2e6, 1e5, 1e5));
+        let laps = drive(&mut tracker, 1.0, 5, 0.0, ErsMode::Medium, (2e6, 0.0, 0.0));
+        assert!(!laps[0].complete);
+        assert!(tracker.summary(0).unwrap().average_profile.is_empty());
+    }
+}
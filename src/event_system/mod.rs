@@ -1,101 +1,181 @@
-pub trait Receiver {
-    type Data;
-    type Transformer;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock, Weak};
+use crate::packets::Packets;
 
-    fn on_emit(&self, data: &Self::Data);
-}
+type Handler = Arc<dyn Fn(&dyn Any) + Send + Sync>;
 
-pub trait Signal {
-    type Data;
-    type RecType: Receiver;
+#[derive(Default)]
+struct Registry {
+    counter: usize,
+    handlers: HashMap<TypeId, Vec<(usize, Handler)>>,
+}
 
-    fn emit(&self, data: Self::Data);
-    fn connect(&mut self, trns: <Self::RecType as Receiver>::Transformer);
-    fn disconnect(&mut self, i: usize);
+/// Routes emitted values to the handlers subscribed to their type.
+///
+/// Cloning a dispatcher is cheap, the clones share the same set of handlers.
+#[derive(Clone, Default)]
+pub struct Dispatcher {
+    registry: Arc<RwLock<Registry>>,
 }
 
-macro_rules! signal {
-    ($sig:ident<$rectype:ident, $data: ident> = [ $($rec:ident = $cls:expr),* ] ) => {
-        pub struct $sig {
-            counter: usize,
-            recs: Vec<$rectype>
-        }
+impl Dispatcher {
+    pub fn new() -> Dispatcher {
+        Dispatcher::default()
+    }
 
-        pub struct $rectype {
-            id: usize,
-            sig: Box<dyn Fn(&$data)>
-        }
+    /// The process wide dispatcher used by `Packet::listen`, `Packet::emit` and `Server::new`.
+    pub fn global() -> &'static Dispatcher {
+        static GLOBAL: OnceLock<Dispatcher> = OnceLock::new();
+        GLOBAL.get_or_init(Dispatcher::new)
+    }
 
-        impl $rectype {
-            fn new(id: usize, cls: Box<dyn Fn(&$data)>) -> Self {
-                Self {
-                    id, sig: cls
-                }
+    /// Registers `handler` for every emitted `T`, it stays registered until the returned
+    /// [`Subscription`] is dropped.
+    pub fn subscribe<T, F>(&self, handler: F) -> Subscription
+        where T: 'static, F: Fn(&T) + Send + Sync + 'static {
+        let handler: Handler = Arc::new(move |data: &dyn Any| {
+            if let Some(data) = data.downcast_ref::<T>() {
+                handler(data)
             }
-        }
+        });
 
-        impl $sig {
-            fn nxt(&mut self) -> usize {
-                self.counter += 1;
-                self.counter
-            }
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        registry.counter += 1;
+        let id = registry.counter;
+        registry.handlers.entry(TypeId::of::<T>()).or_default().push((id, handler));
 
-            const fn new() -> Self {
-                $sig {
-                    recs: Vec::new(),
-                    counter: 0
-                }
-            }
+        Subscription {
+            registry: Arc::downgrade(&self.registry),
+            type_id: TypeId::of::<T>(),
+            id,
         }
+    }
 
-        impl Receiver for $rectype {
-            type Data = $data;
-            type Transformer = Box<dyn Fn(&$data)>;
-
-            fn on_emit(&self, data: &Self::Data) {
-                (self.sig)(data)
+    pub fn emit<T: 'static>(&self, data: &T) {
+        // Handlers are called without holding the lock so they can (un)subscribe themselves.
+        let handlers: Vec<Handler> = {
+            let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+            match registry.handlers.get(&TypeId::of::<T>()) {
+                Some(handlers) => handlers.iter().map(|(_, handler)| handler.clone()).collect(),
+                None => return,
             }
-        }
+        };
 
-        impl Signal for $sig {
-            type Data = $data;
-            type RecType = $rectype;
+        handlers.iter().for_each(|handler| handler(data))
+    }
 
-            fn emit(&self, data: Self::Data) {
-                self.recs.iter().for_each(|rec| rec.on_emit(&data))
-            }
+    /// Emits `packet` itself and then the packet it contains to the handlers of its concrete type.
+    pub fn dispatch(&self, packet: &Packets) {
+        self.emit(packet);
+        packet.emit_to(self)
+    }
 
-            fn connect(&mut self, trns: <Self::RecType as Receiver>::Transformer) {
-                let i: usize = self.nxt();
-                let r = Self::RecType::new(i, trns);
-                self.recs.push(r);
-            }
+    pub fn listener_count<T: 'static>(&self) -> usize {
+        let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+        registry.handlers.get(&TypeId::of::<T>()).map_or(0, Vec::len)
+    }
+}
 
-            fn disconnect(&mut self, i: usize) {
-                let idx = self.recs.iter().position(|r| r.id == i).unwrap();
-                self.recs.remove(idx);
-            }
-        }
+/// Handle to a registered handler, unsubscribes it when dropped.
+#[must_use = "the handler is unsubscribed as soon as the subscription is dropped"]
+pub struct Subscription {
+    registry: Weak<RwLock<Registry>>,
+    type_id: TypeId,
+    id: usize,
+}
 
-        static mut SIGNAL: $sig = $sig::new();
+impl Subscription {
+    /// Keeps the handler registered for the lifetime of its dispatcher.
+    pub fn detach(mut self) {
+        self.registry = Weak::new();
     }
+
+    pub fn unsubscribe(self) {}
 }
 
-macro_rules! signal_fns {
-    () => {
-        fn emit(self) {
-            unsafe {
-                SIGNAL.emit(self)
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(registry) = self.registry.upgrade() {
+            let mut registry = registry.write().unwrap_or_else(|e| e.into_inner());
+            if let Some(handlers) = registry.handlers.get_mut(&self.type_id) {
+                handlers.retain(|(id, _)| *id != self.id);
             }
         }
+    }
+}
 
-        fn listen<F>(callable: F) where F: Fn(&Self) -> () + 'static {
-            unsafe {
-                SIGNAL.connect(Box::new(callable));
-            }
-        }
+/// Shares `state` and feeds it every packet dispatched by `dispatcher`, the values returned by
+/// `ingest` are passed to `emit` together with the dispatcher.
+pub(crate) fn attach<S, R, I, E>(state: S, dispatcher: &Dispatcher, ingest: I, emit: E) -> (Arc<RwLock<S>>, Subscription)
+    where S: Send + Sync + 'static,
+          R: IntoIterator,
+          I: Fn(&mut S, &Packets) -> R + Send + Sync + 'static,
+          E: Fn(&R::Item, &Dispatcher) + Send + Sync + 'static {
+    let state = Arc::new(RwLock::new(state));
+
+    let shared = state.clone();
+    let target = dispatcher.clone();
+    let subscription = dispatcher.subscribe(move |packet: &Packets| {
+        // The lock is released before emitting so handlers can read the state.
+        let values = ingest(&mut shared.write().unwrap_or_else(|e| e.into_inner()), packet);
+        values.into_iter().for_each(|value| emit(&value, &target));
+    });
+
+    (state, subscription)
+}
+
+/// Shares `state` and feeds it every packet dispatched by `dispatcher`.
+pub(crate) fn share<S, I>(state: S, dispatcher: &Dispatcher, ingest: I) -> (Arc<RwLock<S>>, Subscription)
+    where S: Send + Sync + 'static,
+          I: Fn(&mut S, &Packets) + Send + Sync + 'static {
+    let ingest = move |state: &mut S, packet: &Packets| {
+        ingest(state, packet);
+        None::<()>
     };
+    attach(state, dispatcher, ingest, |_, _| {})
 }
 
-pub (crate) use signal;
-pub (crate) use signal_fns;
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::*;
+
+    #[test]
+    fn subscription_unsubscribes_on_drop() {
+        let dispatcher = Dispatcher::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let counter = calls.clone();
+        let subscription = dispatcher.subscribe(move |value: &u32| {
+            counter.fetch_add(*value as usize, Ordering::SeqCst);
+        });
+
+        dispatcher.emit(&2u32);
+        dispatcher.emit(&"not a u32");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(dispatcher.listener_count::<u32>(), 1);
+
+        drop(subscription);
+        dispatcher.emit(&2u32);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(dispatcher.listener_count::<u32>(), 0);
+    }
+
+    #[test]
+    fn dispatchers_do_not_share_handlers() {
+        let first = Dispatcher::new();
+        let second = Dispatcher::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let counter = calls.clone();
+        first.subscribe(move |_: &u8| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }).detach();
+
+        second.emit(&1u8);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        first.clone().emit(&1u8);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,224 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::models::EventDetails;
+use crate::packets::Packets;
+use crate::telemetry::Frame;
+
+/// The packet types that carry car data of a frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum PacketKind {
+    Motion,
+    CarTelemetry,
+    LapData,
+    CarStatus,
+    CarDamage,
+}
+
+impl PacketKind {
+    /// Returns `None` for packets without car data of a frame.
+    pub fn of(packet: &Packets) -> Option<PacketKind> {
+        match packet {
+            Packets::Motion(_) | Packets::Motion2023(_) => Some(PacketKind::Motion),
+            Packets::CarTelemetry(_) => Some(PacketKind::CarTelemetry),
+            Packets::LapData(_) | Packets::LapData2021(_) | Packets::LapData2023(_) => Some(PacketKind::LapData),
+            Packets::CarStatus(_) | Packets::CarStatus2023(_) => Some(PacketKind::CarStatus),
+            Packets::CarDamage(_) | Packets::CarDamage2021(_) => Some(PacketKind::CarDamage),
+            _ => None,
+        }
+    }
+}
+
+/// Packet types a frame needs by default, the game sends them every frame.
+pub const DEFAULT_REQUIRED: [PacketKind; 3] = [PacketKind::Motion, PacketKind::CarTelemetry, PacketKind::LapData];
+
+/// The car data of every car at a single frame.
+///
+/// Packet types that weren't sent for this frame hold their latest known value, `updated` lists
+/// the types that were.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrameSnapshot {
+    pub frame: Frame,
+    pub updated: Vec<PacketKind>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingFrame {
+    frame_identifier: u32,
+    received: Vec<PacketKind>,
+    emitted: bool,
+}
+
+/// Joins the packets sharing a frame identifier into one [`FrameSnapshot`].
+///
+/// A snapshot is emitted as soon as every required packet type of a frame arrived. Packets of
+/// older frames are ignored, frames that never complete are skipped. A flashback restarts the
+/// frame count at the frame the game went back to.
+#[derive(Debug, Clone)]
+pub struct FrameAssembler {
+    required: Vec<PacketKind>,
+    latest: Option<Frame>,
+    pending: Option<PendingFrame>,
+    skipped_frames: u64,
+}
+
+impl Default for FrameAssembler {
+    fn default() -> FrameAssembler {
+        FrameAssembler::with_required(&DEFAULT_REQUIRED)
+    }
+}
+
+impl FrameAssembler {
+    pub fn new() -> FrameAssembler {
+        FrameAssembler::default()
+    }
+
+    /// Creates an assembler that waits for `required` before emitting a frame, an empty list
+    /// emits a snapshot for the first packet of every frame.
+    pub fn with_required(required: &[PacketKind]) -> FrameAssembler {
+        FrameAssembler {
+            required: required.to_vec(),
+            latest: None,
+            pending: None,
+            skipped_frames: 0,
+        }
+    }
+
+    /// Creates an assembler fed by every packet dispatched by `dispatcher`, its snapshots are
+    /// emitted back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<FrameAssembler>>, Subscription) {
+        FrameAssembler::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<FrameAssembler>>, Subscription) {
+        event_system::attach(self, dispatcher, FrameAssembler::ingest, |snapshot, dispatcher| dispatcher.emit(snapshot))
+    }
+
+    pub fn required(&self) -> &[PacketKind] {
+        &self.required
+    }
+
+    /// The car data known so far, including frames that didn't complete.
+    pub fn latest(&self) -> Option<&Frame> {
+        self.latest.as_ref()
+    }
+
+    /// Number of frames that were replaced by a newer frame before they completed.
+    pub fn skipped_frames(&self) -> u64 {
+        self.skipped_frames
+    }
+
+    pub fn reset(&mut self) {
+        self.latest = None;
+        self.pending = None;
+        self.skipped_frames = 0;
+    }
+
+    /// Adds `packet` to its frame and returns the snapshot of the frame once it is complete.
+    pub fn ingest(&mut self, packet: &Packets) -> Option<FrameSnapshot> {
+        let header = packet.header()?;
+        if self.latest.as_ref().is_some_and(|latest| latest.session_uid != header.session_uid) {
+            self.reset();
+        }
+
+        if matches!(packet.event_details(), Some(EventDetails::Flashback(_))) {
+            // The game continues from an earlier frame, which must not be taken as out of order.
+            self.pending = None;
+            return None;
+        }
+
+        let kind = PacketKind::of(packet)?;
+        match &self.pending {
+            Some(pending) if header.frame_identifier < pending.frame_identifier => return None,
+            Some(pending) if header.frame_identifier == pending.frame_identifier => {},
+            pending => {
+                if pending.as_ref().is_some_and(|pending| !pending.emitted) {
+                    self.skipped_frames += 1;
+                }
+                self.pending = Some(PendingFrame {
+                    frame_identifier: header.frame_identifier,
+                    received: vec![],
+                    emitted: false,
+                });
+            },
+        }
+
+        self.latest.get_or_insert_with(|| Frame::new(header)).update(packet);
+        let pending = self.pending.as_mut()?;
+        if !pending.received.contains(&kind) {
+            pending.received.push(kind);
+        }
+
+        if pending.emitted || !self.required.iter().all(|kind| pending.received.contains(kind)) {
+            return None;
+        }
+        pending.emitted = true;
+
+        Some(FrameSnapshot {
+            frame: self.latest.clone()?,
+            updated: pending.received.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CarTelemetryPacket, MotionPacket, PacketCarStatus, PacketHeader, PacketLapData};
+    use crate::models::traits::Packet;
+    use crate::testing;
+
+    fn packet<P: Packet>(frame_identifier: u32) -> Packets {
+        testing::received(&testing::packet_with::<P>(PacketHeader {
+            session_uid: 7,
+            frame_identifier,
+            ..testing::header(P::PACKET_ID, 0.0)
+        }))
+    }
+
+    #[test]
+    fn emits_complete_frames_with_the_latest_values() {
+        let mut assembler = FrameAssembler::new();
+
+        assert!(assembler.ingest(&packet::<PacketCarStatus>(1)).is_none());
+        assert!(assembler.ingest(&packet::<MotionPacket>(1)).is_none());
+        assert!(assembler.ingest(&packet::<CarTelemetryPacket>(1)).is_none());
+        let snapshot = assembler.ingest(&packet::<PacketLapData>(1)).unwrap();
+        assert_eq!(snapshot.frame.frame_identifier, 1);
+        assert_eq!(snapshot.updated.len(), 4);
+
+        // Frame 2 misses its lap data and is skipped, frame 3 keeps the status of frame 1.
+        assembler.ingest(&packet::<MotionPacket>(2));
+        assembler.ingest(&packet::<MotionPacket>(3));
+        assembler.ingest(&packet::<CarTelemetryPacket>(3));
+        assert!(assembler.ingest(&packet::<CarTelemetryPacket>(2)).is_none());
+        let snapshot = assembler.ingest(&packet::<PacketLapData>(3)).unwrap();
+        assert_eq!(snapshot.frame.frame_identifier, 3);
+        assert_eq!(snapshot.updated, [PacketKind::Motion, PacketKind::CarTelemetry, PacketKind::LapData]);
+        assert!(snapshot.frame.player().unwrap().status.is_some());
+        assert_eq!(assembler.skipped_frames(), 1);
+    }
+
+    #[test]
+    fn required_packets_are_configurable() {
+        let mut assembler = FrameAssembler::with_required(&[PacketKind::CarStatus]);
+
+        assert!(assembler.ingest(&packet::<MotionPacket>(1)).is_none());
+        assert!(assembler.ingest(&packet::<PacketCarStatus>(1)).is_some());
+        assert!(assembler.ingest(&packet::<PacketCarStatus>(1)).is_none());
+    }
+
+    #[test]
+    fn new_sessions_reset_the_skipped_frames() {
+        let mut assembler = FrameAssembler::with_required(&[PacketKind::Motion, PacketKind::CarStatus]);
+        assembler.ingest(&packet::<MotionPacket>(1));
+        assembler.ingest(&packet::<MotionPacket>(2));
+        assert_eq!(assembler.skipped_frames(), 1);
+
+        assembler.ingest(&testing::received(&testing::packet_with::<MotionPacket>(PacketHeader {
+            session_uid: 8,
+            ..testing::header(MotionPacket::PACKET_ID, 0.0)
+        })));
+        assert_eq!(assembler.skipped_frames(), 0);
+    }
+}
@@ -0,0 +1,389 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::frames::PacketKind;
+use crate::models::enums::{FuelMix, GameEnum, PitStatus};
+use crate::packets::Packets;
+use crate::session_state::MAX_CARS;
+use crate::telemetry::{CarSnapshot, Frame};
+
+/// Average consumption of the laps driven mostly on one mix, fuel is in kilograms.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct MixConsumption {
+    pub mix: FuelMix,
+    pub laps: u32,
+    pub per_lap: f32,
+}
+
+/// Where the fuel of a car ends up at the flag, fuel is in kilograms.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct FuelProjection {
+    pub car_idx: u8,
+    pub lap: u8,
+    pub mix: FuelMix,
+    pub fuel_in_tank: f32,
+    /// The estimate of the game.
+    pub fuel_remaining_laps: f32,
+    pub laps_remaining: f32,
+    /// Consumption of the current mix, of all laps when it wasn't used for a full lap yet.
+    pub per_lap: Option<f32>,
+    /// Fuel left at the flag, negative when the car runs dry before.
+    pub margin: Option<f32>,
+    pub margin_laps: Option<f32>,
+    /// Fuel to save every lap by lifting and coasting to finish on the current mix.
+    pub saving_per_lap: f32,
+    /// The richest mix that finishes, `None` when the current mix does or no measured mix does.
+    pub suggested_mix: Option<FuelMix>,
+}
+
+/// The projected margin of a car dropped below zero.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct FuelWarning {
+    pub projection: FuelProjection,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum FuelEvent {
+    Projection(FuelProjection),
+    Warning(FuelWarning),
+}
+
+impl FuelEvent {
+    /// Emits the contained event to the handlers of its concrete type.
+    pub fn emit_to(&self, dispatcher: &Dispatcher) {
+        match self {
+            FuelEvent::Projection(event) => dispatcher.emit(event),
+            FuelEvent::Warning(event) => dispatcher.emit(event),
+        }
+    }
+}
+
+/// The race distance from the session packet.
+#[derive(Debug, Copy, Clone, Default)]
+struct Race {
+    total_laps: Option<u8>,
+    track_length: Option<u16>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarFuel {
+    /// Lap number and fuel in the tank at its start.
+    lap_start: Option<(u8, f32)>,
+    /// Status packets per mix during the current lap.
+    mix_samples: Vec<(FuelMix, u32)>,
+    pitted: bool,
+    consumption: Vec<MixConsumption>,
+    short: bool,
+}
+
+impl CarFuel {
+    fn record_lap(&mut self, used: f32) {
+        let mix = match self.mix_samples.iter().max_by_key(|(_, samples)| *samples) {
+            Some((mix, _)) => *mix,
+            None => return,
+        };
+
+        match self.consumption.iter_mut().find(|consumption| consumption.mix == mix) {
+            Some(consumption) => {
+                consumption.per_lap = (consumption.per_lap * consumption.laps as f32 + used) / (consumption.laps + 1) as f32;
+                consumption.laps += 1;
+            },
+            None => self.consumption.push(MixConsumption { mix, laps: 1, per_lap: used }),
+        }
+    }
+
+    fn project(&self, car_idx: usize, snapshot: &CarSnapshot, race: Race) -> Option<FuelProjection> {
+        let (lap, status) = (snapshot.lap?, snapshot.status?);
+
+        let driven = match race.track_length {
+            Some(length) if length > 0 => (lap.lap_distance / length as f32).clamp(0.0, 1.0),
+            _ => 0.0,
+        };
+        let laps_remaining = race.total_laps.map_or(0.0, |total_laps| {
+            (total_laps as f32 - lap.current_lap_num as f32 + 1.0 - driven).max(0.0)
+        });
+
+        let per_lap = self.per_lap(status.fuel_mix).or_else(|| self.average());
+        let margin = per_lap.filter(|_| race.total_laps.is_some())
+            .map(|per_lap| status.fuel_in_tank - per_lap * laps_remaining);
+        let saving_per_lap = match margin {
+            Some(margin) if margin < 0.0 && laps_remaining > 0.0 => -margin / laps_remaining,
+            _ => 0.0,
+        };
+
+        // Unknown mixes can't be told how rich they are, so they are never suggested.
+        let mut mixes: Vec<&MixConsumption> = self.consumption.iter()
+            .filter(|consumption| !matches!(consumption.mix, FuelMix::Unknown(_)))
+            .collect();
+        mixes.sort_by_key(|consumption| std::cmp::Reverse(consumption.mix.to_raw()));
+        let suggested_mix = if saving_per_lap > 0.0 {
+            mixes.iter()
+                .find(|consumption| consumption.per_lap * laps_remaining <= status.fuel_in_tank)
+                .map(|consumption| consumption.mix)
+        } else {
+            None
+        };
+
+        Some(FuelProjection {
+            car_idx: car_idx as u8,
+            lap: lap.current_lap_num,
+            mix: status.fuel_mix,
+            fuel_in_tank: status.fuel_in_tank,
+            fuel_remaining_laps: status.fuel_remaining_laps,
+            laps_remaining,
+            per_lap,
+            margin,
+            margin_laps: margin.zip(per_lap).filter(|(_, per_lap)| *per_lap > 0.0).map(|(margin, per_lap)| margin / per_lap),
+            saving_per_lap,
+            suggested_mix,
+        })
+    }
+
+    fn per_lap(&self, mix: FuelMix) -> Option<f32> {
+        self.consumption.iter().find(|consumption| consumption.mix == mix).map(|consumption| consumption.per_lap)
+    }
+
+    fn average(&self) -> Option<f32> {
+        let laps: u32 = self.consumption.iter().map(|consumption| consumption.laps).sum();
+        let used: f32 = self.consumption.iter().map(|consumption| consumption.per_lap * consumption.laps as f32).sum();
+        (laps > 0).then(|| used / laps as f32)
+    }
+}
+
+/// Measures the fuel consumption per mix and projects the fuel left at the flag.
+///
+/// A lap is put down to the mix used for most of it, laps with a pit stop or a flashback aren't
+/// measured. The race distance comes from the session packet.
+#[derive(Debug, Clone)]
+pub struct FuelCalculator {
+    race: Race,
+    frame: Option<Frame>,
+    cars: Vec<CarFuel>,
+}
+
+impl Default for FuelCalculator {
+    fn default() -> FuelCalculator {
+        FuelCalculator {
+            race: Race::default(),
+            frame: None,
+            cars: vec![CarFuel::default(); MAX_CARS],
+        }
+    }
+}
+
+impl FuelCalculator {
+    pub fn new() -> FuelCalculator {
+        FuelCalculator::default()
+    }
+
+    /// Creates a calculator fed by every packet dispatched by `dispatcher`, its events are
+    /// emitted back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<FuelCalculator>>, Subscription) {
+        FuelCalculator::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<FuelCalculator>>, Subscription) {
+        event_system::attach(self, dispatcher, FuelCalculator::ingest, FuelEvent::emit_to)
+    }
+
+    /// The measured consumption of `car_idx` per mix.
+    pub fn consumption(&self, car_idx: usize) -> &[MixConsumption] {
+        self.cars.get(car_idx).map_or(&[], |car| &car.consumption)
+    }
+
+    pub fn reset(&mut self) {
+        *self = FuelCalculator::new();
+    }
+
+    /// The projection of `car_idx` at its current position, `None` until its lap and status
+    /// are known.
+    pub fn projection(&self, car_idx: usize) -> Option<FuelProjection> {
+        let snapshot = self.frame.as_ref()?.car(car_idx)?;
+        self.cars.get(car_idx)?.project(car_idx, snapshot, self.race)
+    }
+
+    /// Updates the consumption and returns the projections of cars that started a lap and the
+    /// warnings of cars that became short of fuel.
+    pub fn ingest(&mut self, packet: &Packets) -> Vec<FuelEvent> {
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return vec![],
+        };
+        if self.frame.as_ref().is_some_and(|frame| frame.session_uid != header.session_uid) {
+            self.reset();
+        }
+
+        match packet {
+            Packets::Session(packet) => self.race = Race { total_laps: Some(packet.total_laps), track_length: Some(packet.track_length) },
+            Packets::Session2021(packet) => self.race = Race { total_laps: Some(packet.total_laps), track_length: Some(packet.track_length) },
+            Packets::Session2023(packet) => self.race = Race { total_laps: Some(packet.total_laps), track_length: Some(packet.track_length) },
+            _ => {},
+        }
+
+        let frame = self.frame.get_or_insert_with(|| Frame::new(header));
+        if !frame.update(packet) {
+            return vec![];
+        }
+        let kind = PacketKind::of(packet);
+
+        let mut events = vec![];
+        for (car_idx, snapshot) in frame.cars.iter().enumerate() {
+            let (lap, status) = match (snapshot.lap, snapshot.status) {
+                (Some(lap), Some(status)) => (lap, status),
+                _ => continue,
+            };
+            let car = &mut self.cars[car_idx];
+
+            if kind == Some(PacketKind::CarStatus) {
+                match car.mix_samples.iter_mut().find(|(mix, _)| *mix == status.fuel_mix) {
+                    Some((_, samples)) => *samples += 1,
+                    None => car.mix_samples.push((status.fuel_mix, 1)),
+                }
+                continue;
+            }
+            if kind != Some(PacketKind::LapData) {
+                continue;
+            }
+
+            car.pitted |= lap.pit_status != PitStatus::None;
+            match car.lap_start {
+                Some((start_lap, _)) if start_lap == lap.current_lap_num => continue,
+                Some((start_lap, start_fuel)) if lap.current_lap_num == start_lap.wrapping_add(1) => {
+                    let used = start_fuel - status.fuel_in_tank;
+                    if !car.pitted && used >= 0.0 {
+                        car.record_lap(used);
+                    }
+                },
+                _ => {},
+            }
+
+            // A new lap, or the first one seen.
+            let first = car.lap_start.is_none();
+            car.lap_start = Some((lap.current_lap_num, status.fuel_in_tank));
+            car.mix_samples.clear();
+            car.pitted = lap.pit_status != PitStatus::None;
+            if first {
+                continue;
+            }
+
+            if let Some(projection) = car.project(car_idx, snapshot, self.race) {
+                let short = projection.margin.is_some_and(|margin| margin < 0.0);
+                if short && !car.short {
+                    events.push(FuelEvent::Warning(FuelWarning { projection }));
+                }
+                car.short = short;
+                events.push(FuelEvent::Projection(projection));
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketCarStatus, PacketLapData, SessionDataPacket};
+    use crate::testing::packet;
+
+    fn session(total_laps: u8) -> Packets {
+        let mut packet = packet::<SessionDataPacket>(0.0);
+        packet.total_laps = total_laps;
+        packet.track_length = 5000;
+        Packets::Session(packet)
+    }
+
+    fn status(mix: FuelMix, fuel: f32) -> Packets {
+        let mut packet = packet::<PacketCarStatus>(0.0);
+        packet.car_status_data[0].fuel_mix = mix;
+        packet.car_status_data[0].fuel_in_tank = fuel;
+        Packets::CarStatus(packet)
+    }
+
+    fn lap(lap_num: u8) -> Packets {
+        let mut packet = packet::<PacketLapData>(0.0);
+        packet.lap_data[0].current_lap_num = lap_num;
+        Packets::LapData(packet)
+    }
+
+    /// Starts `lap_num` with `fuel` left, the previous lap was driven on `mix`.
+    fn drive(calculator: &mut FuelCalculator, lap_num: u8, mix: FuelMix, fuel: f32) -> Vec<FuelEvent> {
+        calculator.ingest(&status(mix, fuel));
+        calculator.ingest(&lap(lap_num))
+    }
+
+    #[test]
+    fn measures_consumption_per_mix() {
+        let mut calculator = FuelCalculator::new();
+        calculator.ingest(&session(10));
+
+        drive(&mut calculator, 1, FuelMix::Rich, 20.0);
+        drive(&mut calculator, 2, FuelMix::Rich, 18.0);
+        drive(&mut calculator, 3, FuelMix::Rich, 16.0);
+        let events = drive(&mut calculator, 4, FuelMix::Lean, 14.5);
+
+        let consumption = calculator.consumption(0);
+        assert_eq!(consumption.len(), 2);
+        assert_eq!((consumption[0].mix, consumption[0].laps, consumption[0].per_lap), (FuelMix::Rich, 2, 2.0));
+        assert_eq!((consumption[1].mix, consumption[1].per_lap), (FuelMix::Lean, 1.5));
+
+        // 7 laps to go at 1.5 kg leave 4 kg.
+        let projection = match events[..] {
+            [FuelEvent::Projection(projection)] => projection,
+            _ => panic!("unexpected events {:?}", events),
+        };
+        assert_eq!(projection.laps_remaining, 7.0);
+        assert_eq!(projection.margin, Some(4.0));
+        assert_eq!(projection.saving_per_lap, 0.0);
+    }
+
+    #[test]
+    fn warns_when_the_margin_turns_negative() {
+        let mut calculator = FuelCalculator::new();
+        calculator.ingest(&session(10));
+
+        drive(&mut calculator, 1, FuelMix::Lean, 20.0);
+        drive(&mut calculator, 2, FuelMix::Lean, 18.5);
+        drive(&mut calculator, 3, FuelMix::Lean, 17.0);
+        let events = drive(&mut calculator, 4, FuelMix::Rich, 14.5);
+
+        // 7 laps at 2.5 kg need 17.5 kg, on lean 10.5 kg would do.
+        let projection = match events[..] {
+            [FuelEvent::Warning(FuelWarning { projection }), FuelEvent::Projection(_)] => projection,
+            _ => panic!("unexpected events {:?}", events),
+        };
+        assert_eq!(projection.margin, Some(-3.0));
+        assert!((projection.saving_per_lap - 3.0 / 7.0).abs() < 0.001);
+        assert_eq!(projection.suggested_mix, Some(FuelMix::Lean));
+
+        let events = drive(&mut calculator, 5, FuelMix::Rich, 12.0);
+        assert!(matches!(events[..], [FuelEvent::Projection(_)]));
+    }
+
+    #[test]
+    fn never_suggests_an_unknown_mix() {
+        let mut calculator = FuelCalculator::new();
+        calculator.ingest(&session(10));
+
+        drive(&mut calculator, 1, FuelMix::Unknown(9), 20.0);
+        drive(&mut calculator, 2, FuelMix::Unknown(9), 19.0);
+        drive(&mut calculator, 3, FuelMix::Lean, 17.5);
+        let events = drive(&mut calculator, 4, FuelMix::Rich, 14.5);
+
+        let projection = match events[..] {
+            [FuelEvent::Warning(FuelWarning { projection }), FuelEvent::Projection(_)] => projection,
+            _ => panic!("unexpected events {:?}", events),
+        };
+        assert_eq!(projection.suggested_mix, Some(FuelMix::Lean));
+    }
+
+    #[test]
+    fn lap_numbers_wrap_around() {
+        let mut calculator = FuelCalculator::new();
+        calculator.ingest(&session(10));
+
+        drive(&mut calculator, 255, FuelMix::Standard, 20.0);
+        drive(&mut calculator, 0, FuelMix::Standard, 18.0);
+        assert_eq!(calculator.consumption(0)[0].per_lap, 2.0);
+    }
+}
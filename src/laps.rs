@@ -0,0 +1,438 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::models::{f1_2021, f1_2023, LapHistory, PacketLapData};
+use crate::models::enums::Sector;
+use crate::packets::Packets;
+use crate::session_state::MAX_CARS;
+use crate::telemetry::Lap;
+
+/// Session time in seconds a completed lap waits for the session history before it is
+/// emitted with the values known from the lap data alone.
+pub const DEFAULT_HISTORY_TIMEOUT: f32 = 5.0;
+
+/// A car crossed the line, times are in milliseconds.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct LapCompleted {
+    pub car_idx: u8,
+    pub lap_num: u8,
+    pub lap_time: u32,
+    pub sectors: [u32; 3],
+    pub valid: bool,
+}
+
+/// A car left a sector, times are in milliseconds.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct SectorCompleted {
+    pub car_idx: u8,
+    pub lap_num: u8,
+    pub sector: Sector,
+    pub sector_time: u32,
+    pub valid: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum LapEvent {
+    LapCompleted(LapCompleted),
+    SectorCompleted(SectorCompleted),
+}
+
+impl LapEvent {
+    /// Emits the contained event to the handlers of its concrete type.
+    pub fn emit_to(&self, dispatcher: &Dispatcher) {
+        match self {
+            LapEvent::LapCompleted(event) => dispatcher.emit(event),
+            LapEvent::SectorCompleted(event) => dispatcher.emit(event),
+        }
+    }
+}
+
+/// A lap of the session history, times are in milliseconds.
+#[derive(Debug, Copy, Clone)]
+struct HistoryLap {
+    lap_time: u32,
+    sectors: [u32; 3],
+    valid: bool,
+}
+
+impl From<&LapHistory> for HistoryLap {
+    fn from(history: &LapHistory) -> HistoryLap {
+        HistoryLap {
+            lap_time: history.lap_time,
+            sectors: history.sector_times(),
+            valid: history.lap_valid_bit_flags.is_valid_lap(),
+        }
+    }
+}
+
+/// F1 23 sends sector times in minutes and milliseconds, they are kept whole instead of being
+/// capped to the F1 2022 types.
+impl From<&f1_2023::LapHistory> for HistoryLap {
+    fn from(history: &f1_2023::LapHistory) -> HistoryLap {
+        HistoryLap {
+            lap_time: history.lap_time,
+            sectors: history.sector_times(),
+            valid: history.lap_valid_bit_flags.is_valid_lap(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PendingLap {
+    lap: Option<LapCompleted>,
+    deadline: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarLaps {
+    last: Option<Lap>,
+    pending: Vec<PendingLap>,
+    completed: Vec<LapCompleted>,
+}
+
+/// Derives lap and sector completions from successive lap data packets.
+///
+/// Sectors are reported as soon as they are crossed. Completed laps are held back until the
+/// session history of the car confirms their times and validity, or until the history timeout
+/// passed.
+#[derive(Debug, Clone)]
+pub struct LapTracker {
+    session_uid: Option<u64>,
+    history_timeout: f32,
+    cars: Vec<CarLaps>,
+}
+
+impl Default for LapTracker {
+    fn default() -> LapTracker {
+        LapTracker {
+            session_uid: None,
+            history_timeout: DEFAULT_HISTORY_TIMEOUT,
+            cars: vec![CarLaps::default(); MAX_CARS],
+        }
+    }
+}
+
+impl LapTracker {
+    pub fn new() -> LapTracker {
+        LapTracker::default()
+    }
+
+    pub fn with_history_timeout(mut self, seconds: f32) -> LapTracker {
+        self.history_timeout = seconds;
+        self
+    }
+
+    /// Creates a tracker fed by every packet dispatched by `dispatcher`, its events are emitted
+    /// back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<LapTracker>>, Subscription) {
+        LapTracker::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<LapTracker>>, Subscription) {
+        event_system::attach(self, dispatcher, LapTracker::ingest, LapEvent::emit_to)
+    }
+
+    /// Updates the tracker and returns the events caused by `packet`.
+    pub fn ingest(&mut self, packet: &Packets) -> Vec<LapEvent> {
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return vec![],
+        };
+
+        if self.session_uid != Some(header.session_uid) {
+            self.reset();
+            self.session_uid = Some(header.session_uid);
+        }
+
+        let mut events = vec![];
+        match packet {
+            Packets::LapData(PacketLapData { lap_data, .. })
+            | Packets::LapData2021(f1_2021::PacketLapData { lap_data, .. }) => {
+                for (car_idx, lap_data) in lap_data.iter().enumerate().take(MAX_CARS) {
+                    self.update_lap_data(car_idx, &lap_data.into(), header.session_time, &mut events);
+                }
+            },
+            Packets::LapData2023(packet) => {
+                for (car_idx, lap_data) in packet.lap_data.iter().enumerate().take(MAX_CARS) {
+                    self.update_lap_data(car_idx, &lap_data.into(), header.session_time, &mut events);
+                }
+            },
+            Packets::SessionHistory(packet) => {
+                let laps: Vec<HistoryLap> = packet.lap_history_data.iter().map(Into::into).collect();
+                self.update_history(packet.car_idx, packet.num_laps, &laps, &mut events)
+            },
+            Packets::SessionHistory2023(packet) => {
+                let laps: Vec<HistoryLap> = packet.lap_history_data.iter().map(Into::into).collect();
+                self.update_history(packet.car_idx, packet.num_laps, &laps, &mut events)
+            },
+            _ => {},
+        }
+
+        self.expire(header.session_time, &mut events);
+        events
+    }
+
+    fn update_lap_data(&mut self, car_idx: usize, lap_data: &Lap, session_time: f32, events: &mut Vec<LapEvent>) {
+        let history_timeout = self.history_timeout;
+        let car = &mut self.cars[car_idx];
+        let last = match car.last.replace(*lap_data) {
+            Some(last) => last,
+            None => return,
+        };
+
+        if lap_data.current_lap_num == last.current_lap_num.wrapping_add(1) && last.current_lap_num > 0 {
+            let lap_time = lap_data.last_lap_time;
+            let sector3 = lap_time.saturating_sub(last.sector1_time.saturating_add(last.sector2_time));
+            let lap = LapCompleted {
+                car_idx: car_idx as u8,
+                lap_num: last.current_lap_num,
+                lap_time,
+                sectors: [last.sector1_time, last.sector2_time, sector3],
+                valid: !last.current_lap_invalid,
+            };
+
+            events.push(LapEvent::SectorCompleted(SectorCompleted {
+                car_idx: lap.car_idx,
+                lap_num: lap.lap_num,
+                sector: Sector::Sector3,
+                sector_time: lap.sectors[2],
+                valid: lap.valid,
+            }));
+            car.pending.push(PendingLap {
+                lap: Some(lap),
+                deadline: session_time + history_timeout,
+            });
+        } else if lap_data.current_lap_num == last.current_lap_num {
+            let completed = match (last.sector, lap_data.sector) {
+                (Sector::Sector1, Sector::Sector2) => Some((Sector::Sector1, lap_data.sector1_time)),
+                (Sector::Sector2, Sector::Sector3) => Some((Sector::Sector2, lap_data.sector2_time)),
+                _ => None,
+            };
+
+            if let Some((sector, sector_time)) = completed {
+                events.push(LapEvent::SectorCompleted(SectorCompleted {
+                    car_idx: car_idx as u8,
+                    lap_num: lap_data.current_lap_num,
+                    sector,
+                    sector_time,
+                    valid: !lap_data.current_lap_invalid,
+                }));
+            }
+        }
+    }
+
+    fn update_history(&mut self, car_idx: u8, num_laps: u8, history: &[HistoryLap], events: &mut Vec<LapEvent>) {
+        let car = match self.cars.get_mut(car_idx as usize) {
+            Some(car) => car,
+            None => return,
+        };
+
+        for pending in car.pending.iter_mut() {
+            let mut lap = match pending.lap {
+                Some(lap) => lap,
+                None => continue,
+            };
+            let history = match history.get(lap.lap_num as usize - 1) {
+                Some(history) if lap.lap_num <= num_laps && history.lap_time > 0 => history,
+                _ => continue,
+            };
+
+            lap.lap_time = history.lap_time;
+            lap.sectors = history.sectors;
+            lap.valid = history.valid;
+
+            pending.lap = None;
+            car.completed.push(lap);
+            events.push(LapEvent::LapCompleted(lap));
+        }
+        car.pending.retain(|pending| pending.lap.is_some());
+    }
+
+    fn expire(&mut self, session_time: f32, events: &mut Vec<LapEvent>) {
+        for car in self.cars.iter_mut() {
+            for pending in car.pending.iter_mut().filter(|pending| pending.deadline <= session_time) {
+                if let Some(lap) = pending.lap.take() {
+                    car.completed.push(lap);
+                    events.push(LapEvent::LapCompleted(lap));
+                }
+            }
+            car.pending.retain(|pending| pending.lap.is_some());
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = LapTracker {
+            history_timeout: self.history_timeout,
+            ..LapTracker::default()
+        };
+    }
+
+    /// Laps completed by the car so far, in the order they were reported.
+    pub fn laps(&self, car_idx: usize) -> &[LapCompleted] {
+        self.cars.get(car_idx).map_or(&[], |car| &car.completed)
+    }
+
+    pub fn best_lap(&self, car_idx: usize) -> Option<&LapCompleted> {
+        self.laps(car_idx).iter()
+            .filter(|lap| lap.valid && lap.lap_time > 0)
+            .min_by_key(|lap| lap.lap_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketLapData, PacketSessionHistory};
+    use crate::models::{f1_2021, f1_2023};
+    use crate::models::traits::Packet;
+    use crate::testing::{self, packet};
+
+    fn lap_data(session_time: f32, lap_num: u8, sector: Sector, sector1_time: u16, sector2_time: u16, last_lap_time: u32) -> Packets {
+        let mut packet = packet::<PacketLapData>(session_time);
+        let lap_data = &mut packet.lap_data[0];
+        lap_data.current_lap_num = lap_num;
+        lap_data.sector = sector;
+        lap_data.sector1_time = sector1_time;
+        lap_data.sector2_time = sector2_time;
+        lap_data.last_lap_time = last_lap_time;
+        Packets::LapData(packet)
+    }
+
+    fn history(session_time: f32, valid: bool) -> Packets {
+        let mut packet = packet::<PacketSessionHistory>(session_time);
+        packet.num_laps = 2;
+        let lap = &mut packet.lap_history_data[0];
+        lap.lap_time = 90_500;
+        lap.sector_1_time = 30_000;
+        lap.sector_2_time = 30_250;
+        lap.sector_3_time = 30_250;
+        lap.lap_valid_bit_flags.bit_flags = if valid { 0x0F } else { 0x00 };
+        Packets::SessionHistory(packet)
+    }
+
+    fn drive_lap(tracker: &mut LapTracker) -> Vec<LapEvent> {
+        [
+            lap_data(0.0, 1, Sector::Sector1, 0, 0, 0),
+            lap_data(30.0, 1, Sector::Sector2, 30_000, 0, 0),
+            lap_data(60.0, 1, Sector::Sector3, 30_000, 30_250, 0),
+            lap_data(90.5, 2, Sector::Sector1, 0, 0, 90_500),
+        ].iter().flat_map(|packet| tracker.ingest(packet)).collect()
+    }
+
+    #[test]
+    fn reports_sectors_and_waits_for_the_history() {
+        let mut tracker = LapTracker::new();
+
+        let events = drive_lap(&mut tracker);
+        let sectors: Vec<_> = events.iter().map(|event| match event {
+            LapEvent::SectorCompleted(sector) => (sector.sector, sector.sector_time),
+            LapEvent::LapCompleted(_) => panic!("Lap reported before the history arrived"),
+        }).collect();
+        assert_eq!(sectors, vec![(Sector::Sector1, 30_000), (Sector::Sector2, 30_250), (Sector::Sector3, 30_250)]);
+
+        let events = tracker.ingest(&history(91.0, false));
+        assert_eq!(events, vec![LapEvent::LapCompleted(LapCompleted {
+            car_idx: 0,
+            lap_num: 1,
+            lap_time: 90_500,
+            sectors: [30_000, 30_250, 30_250],
+            valid: false,
+        })]);
+        assert_eq!(tracker.laps(0).len(), 1);
+        assert!(tracker.best_lap(0).is_none());
+    }
+
+    #[test]
+    fn reports_unconfirmed_laps_after_the_timeout() {
+        let mut tracker = LapTracker::new().with_history_timeout(1.0);
+        drive_lap(&mut tracker);
+
+        let events = tracker.ingest(&lap_data(92.0, 2, Sector::Sector1, 0, 0, 90_500));
+        match events.as_slice() {
+            [LapEvent::LapCompleted(lap)] => {
+                assert_eq!(lap.lap_num, 1);
+                assert!(lap.valid);
+            },
+            _ => panic!("Expected a completed lap, got {:?}", events),
+        }
+    }
+
+    #[test]
+    fn tracks_f1_2021_lap_data() {
+        // F1 2021 shares the lap data layout, only the packet lacks the time trial indices.
+        let lap_data_2021 = |packets: Packets| {
+            let mut packet_2021 = packet::<f1_2021::PacketLapData>(0.0);
+            match packets {
+                Packets::LapData(packet) => (packet_2021.header, packet_2021.lap_data) = (packet.header, packet.lap_data),
+                _ => unreachable!(),
+            }
+            Packets::LapData2021(packet_2021)
+        };
+
+        let mut tracker = LapTracker::new().with_history_timeout(1.0);
+        tracker.ingest(&lap_data_2021(lap_data(0.0, 1, Sector::Sector3, 30_000, 30_250, 0)));
+        let events = tracker.ingest(&lap_data_2021(lap_data(90.5, 2, Sector::Sector1, 0, 0, 90_500)));
+        assert!(matches!(events[..], [LapEvent::SectorCompleted(SectorCompleted { sector: Sector::Sector3, sector_time: 30_250, .. })]));
+
+        let events = tracker.ingest(&lap_data_2021(lap_data(92.0, 2, Sector::Sector1, 0, 0, 90_500)));
+        assert!(matches!(events[..], [LapEvent::LapCompleted(LapCompleted { lap_num: 1, lap_time: 90_500, .. })]));
+    }
+
+    #[test]
+    fn tracks_f1_23_lap_data_and_history() {
+        fn packet_2023<P: Packet>(session_time: f32) -> P {
+            testing::packet_with(testing::header_2023(P::PACKET_ID, session_time))
+        }
+
+        let lap_data = |session_time: f32, lap_num: u8, sector: Sector, last_lap_time: u32| {
+            let mut packet = packet_2023::<f1_2023::PacketLapData>(session_time);
+            let lap_data = &mut packet.lap_data[0];
+            lap_data.current_lap_num = lap_num;
+            lap_data.sector = sector;
+            // Sector times longer than a minute are split in minutes and milliseconds, 70 s
+            // doesn't fit the F1 2022 types.
+            (lap_data.sector1_time_minutes, lap_data.sector1_time_ms_part) = (1, 10_000);
+            (lap_data.sector2_time_minutes, lap_data.sector2_time_ms_part) = (0, 30_250);
+            lap_data.last_lap_time = last_lap_time;
+            Packets::LapData2023(packet)
+        };
+
+        let mut tracker = LapTracker::new();
+        tracker.ingest(&lap_data(0.0, 1, Sector::Sector3, 0));
+        let events = tracker.ingest(&lap_data(125.5, 2, Sector::Sector1, 125_500));
+        assert!(matches!(events[..], [LapEvent::SectorCompleted(SectorCompleted { sector: Sector::Sector3, sector_time: 25_250, .. })]));
+
+        let mut history = packet_2023::<f1_2023::PacketSessionHistory>(126.0);
+        history.num_laps = 2;
+        let lap = &mut history.lap_history_data[0];
+        lap.lap_time = 125_500;
+        (lap.sector_1_time_minutes, lap.sector_1_time_ms_part) = (1, 10_000);
+        lap.sector_2_time_ms_part = 30_250;
+        lap.sector_3_time_ms_part = 25_250;
+        lap.lap_valid_bit_flags.bit_flags = 0x0F;
+
+        let events = tracker.ingest(&Packets::SessionHistory2023(history));
+        assert_eq!(events, vec![LapEvent::LapCompleted(LapCompleted {
+            car_idx: 0,
+            lap_num: 1,
+            lap_time: 125_500,
+            sectors: [70_000, 30_250, 25_250],
+            valid: true,
+        })]);
+    }
+
+    #[test]
+    fn emits_events_to_the_dispatcher() {
+        let dispatcher = Dispatcher::new();
+        let (_tracker, _subscription) = LapTracker::attach(&dispatcher);
+
+        let sectors = Arc::new(RwLock::new(vec![]));
+        let received = sectors.clone();
+        let _sectors = dispatcher.subscribe(move |event: &SectorCompleted| received.write().unwrap().push(event.sector));
+
+        dispatcher.dispatch(&lap_data(0.0, 1, Sector::Sector1, 0, 0, 0));
+        dispatcher.dispatch(&lap_data(30.0, 1, Sector::Sector2, 30_000, 0, 0));
+
+        assert_eq!(*sectors.read().unwrap(), vec![Sector::Sector1]);
+    }
+}
@@ -1,11 +1,30 @@
-extern crate num_derive;
 extern crate core;
 
+pub mod error;
 pub mod models;
 pub mod server;
+#[cfg(feature = "tokio")]
+pub mod async_server;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 pub mod packets;
-#[macro_use]
+pub mod recording;
+pub mod relay;
+pub mod session_state;
+pub mod laps;
+pub mod telemetry;
+pub mod frames;
+pub mod diagnostics;
+pub mod sessions;
+pub mod pits;
+pub mod overtakes;
+pub mod timing;
+pub mod tyres;
+pub mod fuel;
+pub mod ers;
 pub mod event_system;
+#[cfg(test)]
+mod testing;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
@@ -1,12 +1,11 @@
-use std::io::Read;
-use crate::models::{PacketHeader, WheelsVector};
-use byteorder::ReadBytesExt;
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader, WheelsVector};
+use crate::error::ParseError;
+use byteorder::WriteBytesExt;
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct CarDamage {
     pub tyre_wear: WheelsVector<f32>,
     pub tyre_damage: WheelsVector<u8>,
@@ -32,46 +31,70 @@ pub struct CarDamage {
 }
 
 impl CarDamage {
-    pub fn new<R: Read>(reader: &mut R) -> Result<CarDamage, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<CarDamage, ParseError> {
         Ok(CarDamage {
-            tyre_wear: WheelsVector::<f32>::read_from(reader)?,
-            tyre_damage: WheelsVector::<u8>::read_from(reader)?,
-            brakes_damage: WheelsVector::<u8>::read_from(reader)?,
-            front_left_wing_damage: reader.read_u8()?,
-            front_right_wing_damage: reader.read_u8()?,
-            rear_wing_damage: reader.read_u8()?,
-            floor_damage: reader.read_u8()?,
-            diffuser_damage: reader.read_u8()?,
-            sidepod_damage: reader.read_u8()?,
-            drs_fault: reader.read_u8()? != 0,
-            ers_fault: reader.read_u8()? != 0,
-            gear_box_damage: reader.read_u8()?,
-            engine_damage: reader.read_u8()?,
-            engine_mguh_wear: reader.read_u8()?,
-            engine_es_wear: reader.read_u8()?,
-            engine_ce_wear: reader.read_u8()?,
-            engine_ice_wear: reader.read_u8()?,
-            engine_mguk_wear: reader.read_u8()?,
-            engine_tc_wear: reader.read_u8()?,
-            engine_blown: reader.read_u8()? != 0,
-            engine_seized: reader.read_u8()? != 0,
+            tyre_wear: WheelsVector::<f32>::read_from(reader, "tyre_wear")?,
+            tyre_damage: WheelsVector::<u8>::read_from(reader, "tyre_damage")?,
+            brakes_damage: WheelsVector::<u8>::read_from(reader, "brakes_damage")?,
+            front_left_wing_damage: reader.read_u8("front_left_wing_damage")?,
+            front_right_wing_damage: reader.read_u8("front_right_wing_damage")?,
+            rear_wing_damage: reader.read_u8("rear_wing_damage")?,
+            floor_damage: reader.read_u8("floor_damage")?,
+            diffuser_damage: reader.read_u8("diffuser_damage")?,
+            sidepod_damage: reader.read_u8("sidepod_damage")?,
+            drs_fault: reader.read_bool("drs_fault")?,
+            ers_fault: reader.read_bool("ers_fault")?,
+            gear_box_damage: reader.read_u8("gear_box_damage")?,
+            engine_damage: reader.read_u8("engine_damage")?,
+            engine_mguh_wear: reader.read_u8("engine_mguh_wear")?,
+            engine_es_wear: reader.read_u8("engine_es_wear")?,
+            engine_ce_wear: reader.read_u8("engine_ce_wear")?,
+            engine_ice_wear: reader.read_u8("engine_ice_wear")?,
+            engine_mguk_wear: reader.read_u8("engine_mguk_wear")?,
+            engine_tc_wear: reader.read_u8("engine_tc_wear")?,
+            engine_blown: reader.read_bool("engine_blown")?,
+            engine_seized: reader.read_bool("engine_seized")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.tyre_wear.write_to(writer)?;
+        self.tyre_damage.write_to(writer)?;
+        self.brakes_damage.write_to(writer)?;
+        writer.write_u8(self.front_left_wing_damage)?;
+        writer.write_u8(self.front_right_wing_damage)?;
+        writer.write_u8(self.rear_wing_damage)?;
+        writer.write_u8(self.floor_damage)?;
+        writer.write_u8(self.diffuser_damage)?;
+        writer.write_u8(self.sidepod_damage)?;
+        writer.write_u8(self.drs_fault as u8)?;
+        writer.write_u8(self.ers_fault as u8)?;
+        writer.write_u8(self.gear_box_damage)?;
+        writer.write_u8(self.engine_damage)?;
+        writer.write_u8(self.engine_mguh_wear)?;
+        writer.write_u8(self.engine_es_wear)?;
+        writer.write_u8(self.engine_ce_wear)?;
+        writer.write_u8(self.engine_ice_wear)?;
+        writer.write_u8(self.engine_mguk_wear)?;
+        writer.write_u8(self.engine_tc_wear)?;
+        writer.write_u8(self.engine_blown as u8)?;
+        writer.write_u8(self.engine_seized as u8)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PacketCarDamage {
     pub header: PacketHeader,
     pub car_damage_data: Vec<CarDamage>,
 }
 
-event_system::signal!(CarDamageSignal<CarDamageReceiver, PacketCarDamage> = []);
-
 impl Packet for PacketCarDamage {
     const PACKET_ID: u8 = 10;
     const PACKET_SIZE: usize = 948;
 
-    fn new<R: Read>(reader: &mut R) -> Result<PacketCarDamage, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketCarDamage, ParseError> {
         let header = PacketHeader::new(reader)?;
         let mut car_damage_data = Vec::new();
         for _ in 0..22 {
@@ -83,5 +106,12 @@ impl Packet for PacketCarDamage {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for car_damage in &self.car_damage_data {
+            car_damage.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,12 +1,11 @@
-use std::io::{Error, Read};
-use crate::models::{PacketHeader, WheelsVector};
-use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader, WheelsVector};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct CarSetup {
     pub front_wing: u8,
     pub rear_wing: u8,
@@ -30,44 +29,66 @@ pub struct CarSetup {
 }
 
 impl CarSetup {
-    pub fn read_from<R: Read>(reader: &mut R) -> Result<CarSetup, Error> {
+    pub fn read_from<R: Read>(reader: &mut PacketReader<R>) -> Result<CarSetup, ParseError> {
         Ok(CarSetup {
-            front_wing: reader.read_u8()?,
-            rear_wing: reader.read_u8()?,
-            on_throttle: reader.read_u8()?,
-            off_throttle: reader.read_u8()?,
-            front_camber: reader.read_f32::<LittleEndian>()?,
-            rear_camber: reader.read_f32::<LittleEndian>()?,
-            front_toe: reader.read_f32::<LittleEndian>()?,
-            rear_toe: reader.read_f32::<LittleEndian>()?,
-            front_suspension: reader.read_u8()?,
-            rear_suspension: reader.read_u8()?,
-            front_anti_roll_bar: reader.read_u8()?,
-            rear_anti_roll_bar: reader.read_u8()?,
-            front_suspension_height: reader.read_u8()?,
-            rear_suspension_height: reader.read_u8()?,
-            brake_pressure: reader.read_u8()?,
-            brake_bias: reader.read_u8()?,
-            tyre_pressure: WheelsVector::<f32>::read_from(reader)?,
-            ballast: reader.read_u8()?,
-            fuel_load: reader.read_f32::<LittleEndian>()?,
+            front_wing: reader.read_u8("front_wing")?,
+            rear_wing: reader.read_u8("rear_wing")?,
+            on_throttle: reader.read_u8("on_throttle")?,
+            off_throttle: reader.read_u8("off_throttle")?,
+            front_camber: reader.read_f32("front_camber")?,
+            rear_camber: reader.read_f32("rear_camber")?,
+            front_toe: reader.read_f32("front_toe")?,
+            rear_toe: reader.read_f32("rear_toe")?,
+            front_suspension: reader.read_u8("front_suspension")?,
+            rear_suspension: reader.read_u8("rear_suspension")?,
+            front_anti_roll_bar: reader.read_u8("front_anti_roll_bar")?,
+            rear_anti_roll_bar: reader.read_u8("rear_anti_roll_bar")?,
+            front_suspension_height: reader.read_u8("front_suspension_height")?,
+            rear_suspension_height: reader.read_u8("rear_suspension_height")?,
+            brake_pressure: reader.read_u8("brake_pressure")?,
+            brake_bias: reader.read_u8("brake_bias")?,
+            tyre_pressure: WheelsVector::<f32>::read_from(reader, "tyre_pressure")?,
+            ballast: reader.read_u8("ballast")?,
+            fuel_load: reader.read_f32("fuel_load")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.front_wing)?;
+        writer.write_u8(self.rear_wing)?;
+        writer.write_u8(self.on_throttle)?;
+        writer.write_u8(self.off_throttle)?;
+        writer.write_f32::<LittleEndian>(self.front_camber)?;
+        writer.write_f32::<LittleEndian>(self.rear_camber)?;
+        writer.write_f32::<LittleEndian>(self.front_toe)?;
+        writer.write_f32::<LittleEndian>(self.rear_toe)?;
+        writer.write_u8(self.front_suspension)?;
+        writer.write_u8(self.rear_suspension)?;
+        writer.write_u8(self.front_anti_roll_bar)?;
+        writer.write_u8(self.rear_anti_roll_bar)?;
+        writer.write_u8(self.front_suspension_height)?;
+        writer.write_u8(self.rear_suspension_height)?;
+        writer.write_u8(self.brake_pressure)?;
+        writer.write_u8(self.brake_bias)?;
+        self.tyre_pressure.write_to(writer)?;
+        writer.write_u8(self.ballast)?;
+        writer.write_f32::<LittleEndian>(self.fuel_load)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct CarSetupPacket {
     pub header: PacketHeader,
     pub car_setups: Vec<CarSetup>,
 }
 
-event_system::signal!(CarSetupSignal<CarSetupReceiver, CarSetupPacket> = []);
-
 impl Packet for CarSetupPacket {
     const PACKET_ID: u8 = 5;
     const PACKET_SIZE: usize = 1102;
 
-    fn new<R: Read>(reader: &mut R) -> Result<CarSetupPacket, Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<CarSetupPacket, ParseError> {
         let header = PacketHeader::new(reader)?;
         let mut car_setups = Vec::with_capacity(22);
         for _ in 0..22 {
@@ -79,5 +100,12 @@ impl Packet for CarSetupPacket {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for car_setup in &self.car_setups {
+            car_setup.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
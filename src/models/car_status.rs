@@ -1,14 +1,12 @@
-use std::io::Read;
-use crate::models::enums::{ActualTyreCompound, ErsMode, FuelMix, TractionControlStatus, VehicleFiaFlags, VisualTyreCompound};
-use byteorder::{ReadBytesExt, LittleEndian};
-use num_traits::FromPrimitive;
-use crate::models::PacketHeader;
+use std::io::{self, Read, Write};
+use crate::models::enums::{ActualTyreCompound, ErsMode, FuelMix, GameEnum, TractionControlStatus, VehicleFiaFlags, VisualTyreCompound};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct CarStatusData {
     pub traction_control: TractionControlStatus,
     pub anti_lock_brakes: bool,
@@ -36,48 +34,74 @@ pub struct CarStatusData {
 }
 
 impl CarStatusData {
-    pub fn new<R: Read>(reader: &mut R) -> Result<CarStatusData, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<CarStatusData, ParseError> {
         Ok(CarStatusData {
-            traction_control: TractionControlStatus::from_u8(reader.read_u8()?).unwrap(),
-            anti_lock_brakes: reader.read_u8()? != 0,
-            fuel_mix: FuelMix::from_u8(reader.read_u8()?).unwrap(),
-            front_brake_bias: reader.read_u8()?,
-            pit_limiter_status: reader.read_u8()? != 0,
-            fuel_in_tank: reader.read_f32::<LittleEndian>()?,
-            fuel_capacity: reader.read_f32::<LittleEndian>()?,
-            fuel_remaining_laps: reader.read_f32::<LittleEndian>()?,
-            max_rpm: reader.read_u16::<LittleEndian>()?,
-            idle_rpm: reader.read_u16::<LittleEndian>()?,
-            max_gears: reader.read_u8()?,
-            drs_allowed: reader.read_u8()? != 0,
-            drs_activation_distance: reader.read_u16::<LittleEndian>()?,
-            actual_tyre_compound: ActualTyreCompound::from_u8(reader.read_u8()?).unwrap(),
-            visual_tyre_compound: VisualTyreCompound::from_u8(reader.read_u8()?).unwrap(),
-            tyres_age_laps: reader.read_u8()?,
-            vehicle_fia_flags: VehicleFiaFlags::from_u8(reader.read_u8()?).unwrap(),
-            ers_store_energy: reader.read_f32::<LittleEndian>()?,
-            ers_deploy_mode: ErsMode::from_u8(reader.read_u8()?).unwrap(),
-            ers_harvested_this_lap_mguk: reader.read_f32::<LittleEndian>()?,
-            ers_harvested_this_lap_mguh: reader.read_f32::<LittleEndian>()?,
-            ers_deployed_this_lap: reader.read_f32::<LittleEndian>()?,
-            network_paused: reader.read_u8()? != 0,
+            traction_control: reader.read_enum("traction_control")?,
+            anti_lock_brakes: reader.read_bool("anti_lock_brakes")?,
+            fuel_mix: reader.read_enum("fuel_mix")?,
+            front_brake_bias: reader.read_u8("front_brake_bias")?,
+            pit_limiter_status: reader.read_bool("pit_limiter_status")?,
+            fuel_in_tank: reader.read_f32("fuel_in_tank")?,
+            fuel_capacity: reader.read_f32("fuel_capacity")?,
+            fuel_remaining_laps: reader.read_f32("fuel_remaining_laps")?,
+            max_rpm: reader.read_u16("max_rpm")?,
+            idle_rpm: reader.read_u16("idle_rpm")?,
+            max_gears: reader.read_u8("max_gears")?,
+            drs_allowed: reader.read_bool("drs_allowed")?,
+            drs_activation_distance: reader.read_u16("drs_activation_distance")?,
+            actual_tyre_compound: reader.read_enum("actual_tyre_compound")?,
+            visual_tyre_compound: reader.read_enum("visual_tyre_compound")?,
+            tyres_age_laps: reader.read_u8("tyres_age_laps")?,
+            vehicle_fia_flags: reader.read_enum("vehicle_fia_flags")?,
+            ers_store_energy: reader.read_f32("ers_store_energy")?,
+            ers_deploy_mode: reader.read_enum("ers_deploy_mode")?,
+            ers_harvested_this_lap_mguk: reader.read_f32("ers_harvested_this_lap_mguk")?,
+            ers_harvested_this_lap_mguh: reader.read_f32("ers_harvested_this_lap_mguh")?,
+            ers_deployed_this_lap: reader.read_f32("ers_deployed_this_lap")?,
+            network_paused: reader.read_bool("network_paused")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.traction_control.to_raw())?;
+        writer.write_u8(self.anti_lock_brakes as u8)?;
+        writer.write_u8(self.fuel_mix.to_raw())?;
+        writer.write_u8(self.front_brake_bias)?;
+        writer.write_u8(self.pit_limiter_status as u8)?;
+        writer.write_f32::<LittleEndian>(self.fuel_in_tank)?;
+        writer.write_f32::<LittleEndian>(self.fuel_capacity)?;
+        writer.write_f32::<LittleEndian>(self.fuel_remaining_laps)?;
+        writer.write_u16::<LittleEndian>(self.max_rpm)?;
+        writer.write_u16::<LittleEndian>(self.idle_rpm)?;
+        writer.write_u8(self.max_gears)?;
+        writer.write_u8(self.drs_allowed as u8)?;
+        writer.write_u16::<LittleEndian>(self.drs_activation_distance)?;
+        writer.write_u8(self.actual_tyre_compound.to_raw())?;
+        writer.write_u8(self.visual_tyre_compound.to_raw())?;
+        writer.write_u8(self.tyres_age_laps)?;
+        writer.write_u8(self.vehicle_fia_flags.to_raw())?;
+        writer.write_f32::<LittleEndian>(self.ers_store_energy)?;
+        writer.write_u8(self.ers_deploy_mode.to_raw())?;
+        writer.write_f32::<LittleEndian>(self.ers_harvested_this_lap_mguk)?;
+        writer.write_f32::<LittleEndian>(self.ers_harvested_this_lap_mguh)?;
+        writer.write_f32::<LittleEndian>(self.ers_deployed_this_lap)?;
+        writer.write_u8(self.network_paused as u8)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PacketCarStatus {
     pub header: PacketHeader,
     pub car_status_data: Vec<CarStatusData>,
 }
 
-event_system::signal!(CarStatusSignal<CarStatusReceiver, PacketCarStatus> = []);
-
 impl Packet for PacketCarStatus {
     const PACKET_ID: u8 = 7;
     const PACKET_SIZE: usize = 1058;
 
-    fn new<R: Read>(reader: &mut R) -> Result<PacketCarStatus, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketCarStatus, ParseError> {
         let header = PacketHeader::new(reader)?;
         let mut car_status_data = Vec::new();
         for _ in 0..22 {
@@ -89,5 +113,12 @@ impl Packet for PacketCarStatus {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for car_status in &self.car_status_data {
+            car_status.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,14 +1,12 @@
-use std::io::Read;
-use crate::models::enums::{MdfPanel, SurfaceType};
-use crate::models::{PacketHeader, WheelsVector};
-use byteorder::{ReadBytesExt, LittleEndian};
-use num_traits::FromPrimitive;
+use std::io::{self, Read, Write};
+use crate::models::enums::{GameEnum, MdfPanel, SurfaceType};
+use crate::models::{PacketHeader, PacketReader, WheelsVector};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct CarTelemetryData {
     pub speed: u16,
     pub throttle: f32,
@@ -29,23 +27,23 @@ pub struct CarTelemetryData {
 }
 
 impl CarTelemetryData {
-    pub fn new<R: Read>(reader: &mut R) -> Result<CarTelemetryData, std::io::Error> {
-        let speed = reader.read_u16::<LittleEndian>()?;
-        let throttle = reader.read_f32::<LittleEndian>()?;
-        let steer = reader.read_f32::<LittleEndian>()?;
-        let brake = reader.read_f32::<LittleEndian>()?;
-        let clutch = reader.read_u8()?;
-        let gear = reader.read_i8()?;
-        let engine_rpm = reader.read_u16::<LittleEndian>()?;
-        let drs = reader.read_u8()? != 0;
-        let rev_lights_percent = reader.read_u8()?;
-        let rev_lights_bitfield = reader.read_u16::<LittleEndian>()?;
-        let brakes_temperature = WheelsVector::<u16>::read_from(reader)?;
-        let tyres_surface_temperature = WheelsVector::<u8>::read_from(reader)?;
-        let tyres_inner_temperature = WheelsVector::<u8>::read_from(reader)?;
-        let engine_temperature = reader.read_u16::<LittleEndian>()?;
-        let tyres_pressure = WheelsVector::<f32>::read_from(reader)?;
-        let surface_type = WheelsVector::<SurfaceType>::read_from(reader)?;
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<CarTelemetryData, ParseError> {
+        let speed = reader.read_u16("speed")?;
+        let throttle = reader.read_f32("throttle")?;
+        let steer = reader.read_f32("steer")?;
+        let brake = reader.read_f32("brake")?;
+        let clutch = reader.read_u8("clutch")?;
+        let gear = reader.read_i8("gear")?;
+        let engine_rpm = reader.read_u16("engine_rpm")?;
+        let drs = reader.read_bool("drs")?;
+        let rev_lights_percent = reader.read_u8("rev_lights_percent")?;
+        let rev_lights_bitfield = reader.read_u16("rev_lights_bitfield")?;
+        let brakes_temperature = WheelsVector::<u16>::read_from(reader, "brakes_temperature")?;
+        let tyres_surface_temperature = WheelsVector::<u8>::read_from(reader, "tyres_surface_temperature")?;
+        let tyres_inner_temperature = WheelsVector::<u8>::read_from(reader, "tyres_inner_temperature")?;
+        let engine_temperature = reader.read_u16("engine_temperature")?;
+        let tyres_pressure = WheelsVector::<f32>::read_from(reader, "tyres_pressure")?;
+        let surface_type = WheelsVector::<SurfaceType>::read_from(reader, "surface_type")?;
 
         Ok(CarTelemetryData {
             speed,
@@ -66,9 +64,30 @@ impl CarTelemetryData {
             surface_type,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u16::<LittleEndian>(self.speed)?;
+        writer.write_f32::<LittleEndian>(self.throttle)?;
+        writer.write_f32::<LittleEndian>(self.steer)?;
+        writer.write_f32::<LittleEndian>(self.brake)?;
+        writer.write_u8(self.clutch)?;
+        writer.write_i8(self.gear)?;
+        writer.write_u16::<LittleEndian>(self.engine_rpm)?;
+        writer.write_u8(self.drs as u8)?;
+        writer.write_u8(self.rev_lights_percent)?;
+        writer.write_u16::<LittleEndian>(self.rev_lights_bitfield)?;
+        self.brakes_temperature.write_to(writer)?;
+        self.tyres_surface_temperature.write_to(writer)?;
+        self.tyres_inner_temperature.write_to(writer)?;
+        writer.write_u16::<LittleEndian>(self.engine_temperature)?;
+        self.tyres_pressure.write_to(writer)?;
+        self.surface_type.write_to(writer)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct CarTelemetryPacket {
     pub header: PacketHeader,
     pub car_telemetry_data: Vec<CarTelemetryData>,
@@ -77,22 +96,20 @@ pub struct CarTelemetryPacket {
     pub suggested_gear: i8,
 }
 
-event_system::signal!(CarTelemetrySignal<CarTelemetryReceiver, CarTelemetryPacket> = []);
-
 impl Packet for CarTelemetryPacket {
     const PACKET_ID: u8 = 6;
     const PACKET_SIZE: usize = 1347;
 
-    fn new<R: Read>(reader: &mut R) -> Result<CarTelemetryPacket, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<CarTelemetryPacket, ParseError> {
         let header = PacketHeader::new(reader)?;
         let mut car_telemetry_data = Vec::new();
         for _ in 0..22 {
             car_telemetry_data.push(CarTelemetryData::new(reader)?);
         }
-        let mdf_panel_index = MdfPanel::from_u8(reader.read_u8()?).unwrap();
+        let mdf_panel_index = reader.read_enum("mdf_panel_index")?;
 
-        let mdf_panel_index_secondary_player = MdfPanel::from_u8(reader.read_u8()?).unwrap();
-        let suggested_gear = reader.read_i8()?;
+        let mdf_panel_index_secondary_player = reader.read_enum("mdf_panel_index_secondary_player")?;
+        let suggested_gear = reader.read_i8("suggested_gear")?;
         Ok(CarTelemetryPacket {
             header,
             car_telemetry_data,
@@ -102,5 +119,15 @@ impl Packet for CarTelemetryPacket {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for car_telemetry in &self.car_telemetry_data {
+            car_telemetry.write_to(writer)?;
+        }
+        writer.write_u8(self.mdf_panel_index.to_raw())?;
+        writer.write_u8(self.mdf_panel_index_secondary_player.to_raw())?;
+        writer.write_i8(self.suggested_gear)?;
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,269 @@
+game_enum! {
+    pub enum Driver: u8 {
+        CarlosSainz = 0,
+        DaniilKvyat = 1,
+        DanielRicciardo = 2,
+        FernandoAlonso = 3,
+        FelipeMassa = 4,
+        KimiRaikkonen = 6,
+        LewisHamilton = 7,
+        MaxVerstappen = 9,
+        NicoHulkenberg = 10,
+        KevinMagnussen = 11,
+        RomainGrosjean = 12,
+        SebastianVettel = 13,
+        SergioPerez = 14,
+        ValtteriBottas = 15,
+        EstebanOcon = 17,
+        LanceStroll = 19,
+        ArronBarnes = 20,
+        MartinGiles = 21,
+        AlexMurray = 22,
+        LucasRoth = 23,
+        IgorCorreia = 24,
+        SophieLevasseur = 25,
+        JonasSchiffer = 26,
+        AlainForest = 27,
+        JayLetourneau = 28,
+        EstoSaari = 29,
+        YasarAtiyeh = 30,
+        CallistoCalabresi = 31,
+        NaotaIzum = 32,
+        HowardClarke = 33,
+        WilheimKaufmann = 34,
+        MarieLaursen = 35,
+        FlavioNieves = 36,
+        PeterBelousov = 37,
+        KlimekMichalski = 38,
+        SantiagoMoreno = 39,
+        BenjaminCoppens = 40,
+        NoahVisser = 41,
+        GertWaldmuller = 42,
+        JulianQuesada = 43,
+        DanielJones = 44,
+        ArtemMarkelov = 45,
+        TadasukeMakino = 46,
+        SeanGelael = 47,
+        NyckDeVries = 48,
+        JackAitken = 49,
+        GeorgeRussell = 50,
+        MaximilianGunther = 51,
+        NireiFukuzumi = 52,
+        LucaGhiotto = 53,
+        LandoNorris = 54,
+        SergioSetteCamara = 55,
+        LouisDeletraz = 56,
+        AntonioFuoco = 57,
+        CharlesLeclerc = 58,
+        PierreGasly = 59,
+        AlexanderAlbon = 62,
+        NicholasLatifi = 63,
+        DorianBoccolacci = 64,
+        NikoKari = 65,
+        RobertoMerhi = 66,
+        ArjunMaini = 67,
+        AlessioLorandi = 68,
+        RubenMeijer = 69,
+        RashidNair = 70,
+        JackTremblay = 71,
+        DevonButler = 72,
+        LukasWeber = 73,
+        AntonioGiovinazzi = 74,
+        RobertKubica = 75,
+        AlainProst = 76,
+        AyrtonSenna = 77,
+        NobuharuMatsushita = 78,
+        NikitaMazepin = 79,
+        GuanyaZhou = 80,
+        MickSchumacher = 81,
+        CallumIlott = 82,
+        JuanManuelCorrea = 83,
+        JordanKing = 84,
+        MahaveerRaghunathan = 85,
+        TatianaCalderon = 86,
+        AnthoineHubert = 87,
+        GuilianoAlesi = 88,
+        RalphBoschung = 89,
+        MichaelSchumacher = 90,
+        DanTicktum = 91,
+        MarcusArmstrong = 92,
+        ChristianLundgaard = 93,
+        YukiTsunoda = 94,
+        JehanDaruvala = 95,
+        GulhermeSamaia = 96,
+        PedroPiquet = 97,
+        FelipeDrugovich = 98,
+        RobertSchwartzman = 99,
+        RoyNissany = 100,
+        MarinoSato = 101,
+        AidanJackson = 102,
+        CasperAkkerman = 103,
+        JensonButton = 109,
+        DavidCoulthard = 110,
+        NicoRosberg = 111,
+        OscarPiastri = 112,
+        LiamLawson = 113,
+        JuriVips = 114,
+        TheoPourchaire = 115,
+        RichardVerschoor = 116,
+        LirimZendeli = 117,
+        DavidBeckmann = 118,
+        AlessioDeledda = 121,
+        BentViscaal = 122,
+        EnzoFittipaldi = 123,
+        MarkWebber = 125,
+        JacquesVilleneuve = 126,
+        JakeHughes = 127,
+        FrederikVesti = 128,
+        OlliCaldwell = 129,
+        LoganSargeant = 130,
+        CemBolukbasi = 131,
+        AyumaIwasa = 132,
+        ClementNovolak = 133,
+        DennisHauger = 134,
+        CalanWilliams = 135,
+        JackDoohan = 136,
+        AmauryCordeel = 137,
+        MikaHakkinen = 138,
+        NetworkHuman = 255,
+    }
+}
+
+impl Driver {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Driver::CarlosSainz => "Carlos Sainz",
+            Driver::DaniilKvyat => "Daniil Kvyat",
+            Driver::DanielRicciardo => "Daniel Ricciardo",
+            Driver::FernandoAlonso => "Fernando Alonso",
+            Driver::FelipeMassa => "Felipe Massa",
+            Driver::KimiRaikkonen => "Kimi Räikkönen",
+            Driver::LewisHamilton => "Lewis Hamilton",
+            Driver::MaxVerstappen => "Max Verstappen",
+            Driver::NicoHulkenberg => "Nico Hulkenberg",
+            Driver::KevinMagnussen => "Kevin Magnussen",
+            Driver::RomainGrosjean => "Romain Grosjean",
+            Driver::SebastianVettel => "Sebastian Vettel",
+            Driver::SergioPerez => "Sergio Perez",
+            Driver::ValtteriBottas => "Valtteri Bottas",
+            Driver::EstebanOcon => "Esteban Ocon",
+            Driver::LanceStroll => "Lance Stroll",
+            Driver::ArronBarnes => "Arron Barnes",
+            Driver::MartinGiles => "Martin Giles",
+            Driver::AlexMurray => "Alex Murray",
+            Driver::LucasRoth => "Lucas Roth",
+            Driver::IgorCorreia => "Igor Correia",
+            Driver::SophieLevasseur => "Sophie Levasseur",
+            Driver::JonasSchiffer => "Jonas Schiffer",
+            Driver::AlainForest => "Alain Forest",
+            Driver::JayLetourneau => "Jay Letourneau",
+            Driver::EstoSaari => "Esto Saari",
+            Driver::YasarAtiyeh => "Yasar Atiyeh",
+            Driver::CallistoCalabresi => "Callisto Calabresi",
+            Driver::NaotaIzum => "Naota Izum",
+            Driver::HowardClarke => "Howard Clarke",
+            Driver::WilheimKaufmann => "Wilheim Kaufmann",
+            Driver::MarieLaursen => "Marie Laursen",
+            Driver::FlavioNieves => "Flavio Nieves",
+            Driver::PeterBelousov => "Peter Belousov",
+            Driver::KlimekMichalski => "Klimek Michalski",
+            Driver::SantiagoMoreno => "Santiago Moreno",
+            Driver::BenjaminCoppens => "Benjamin Coppens",
+            Driver::NoahVisser => "Noah Visser",
+            Driver::GertWaldmuller => "Gert Waldmuller",
+            Driver::JulianQuesada => "Julian Quesada",
+            Driver::DanielJones => "Daniel Jones",
+            Driver::ArtemMarkelov => "Artem Markelov",
+            Driver::TadasukeMakino => "Tadasuke Makino",
+            Driver::SeanGelael => "Sean Gelael",
+            Driver::NyckDeVries => "Nyck De Vries",
+            Driver::JackAitken => "Jack Aitken",
+            Driver::GeorgeRussell => "George Russell",
+            Driver::MaximilianGunther => "Maximilian Günther",
+            Driver::NireiFukuzumi => "Nirei Fukuzumi",
+            Driver::LucaGhiotto => "Luca Ghiotto",
+            Driver::LandoNorris => "Lando Norris",
+            Driver::SergioSetteCamara => "Sérgio Sette Câmara",
+            Driver::LouisDeletraz => "Louis Delétraz",
+            Driver::AntonioFuoco => "Antonio Fuoco",
+            Driver::CharlesLeclerc => "Charles Leclerc",
+            Driver::PierreGasly => "Pierre Gasly",
+            Driver::AlexanderAlbon => "Alexander Albon",
+            Driver::NicholasLatifi => "Nicholas Latifi",
+            Driver::DorianBoccolacci => "Dorian Boccolacci",
+            Driver::NikoKari => "Niko Kari",
+            Driver::RobertoMerhi => "Roberto Merhi",
+            Driver::ArjunMaini => "Arjun Maini",
+            Driver::AlessioLorandi => "Alessio Lorandi",
+            Driver::RubenMeijer => "Ruben Meijer",
+            Driver::RashidNair => "Rashid Nair",
+            Driver::JackTremblay => "Jack Tremblay",
+            Driver::DevonButler => "Devon Butler",
+            Driver::LukasWeber => "Lukas Weber",
+            Driver::AntonioGiovinazzi => "Antonio Giovinazzi",
+            Driver::RobertKubica => "Robert Kubica",
+            Driver::AlainProst => "Alain Prost",
+            Driver::AyrtonSenna => "Ayrton Senna",
+            Driver::NobuharuMatsushita => "Nobuharu Matsushita",
+            Driver::NikitaMazepin => "Nikita Mazepin",
+            Driver::GuanyaZhou => "Guanya Zhou",
+            Driver::MickSchumacher => "Mick Schumacher",
+            Driver::CallumIlott => "Callum Ilott",
+            Driver::JuanManuelCorrea => "Juan Manuel Correa",
+            Driver::JordanKing => "Jordan King",
+            Driver::MahaveerRaghunathan => "Mahaveer Raghunathan",
+            Driver::TatianaCalderon => "Tatiana Calderon",
+            Driver::AnthoineHubert => "Anthoine Hubert",
+            Driver::GuilianoAlesi => "Guiliano Alesi",
+            Driver::RalphBoschung => "Ralph Boschung",
+            Driver::MichaelSchumacher => "Michael Schumacher",
+            Driver::DanTicktum => "Dan Ticktum",
+            Driver::MarcusArmstrong => "Marcus Armstrong",
+            Driver::ChristianLundgaard => "Christian Lundgaard",
+            Driver::YukiTsunoda => "Yuki Tsunoda",
+            Driver::JehanDaruvala => "Jehan Daruvala",
+            Driver::GulhermeSamaia => "Gulherme Samaia",
+            Driver::PedroPiquet => "Pedro Piquet",
+            Driver::FelipeDrugovich => "Felipe Drugovich",
+            Driver::RobertSchwartzman => "Robert Schwartzman",
+            Driver::RoyNissany => "Roy Nissany",
+            Driver::MarinoSato => "Marino Sato",
+            Driver::AidanJackson => "Aidan Jackson",
+            Driver::CasperAkkerman => "Casper Akkerman",
+            Driver::JensonButton => "Jenson Button",
+            Driver::DavidCoulthard => "David Coulthard",
+            Driver::NicoRosberg => "Nico Rosberg",
+            Driver::OscarPiastri => "Oscar Piastri",
+            Driver::LiamLawson => "Liam Lawson",
+            Driver::JuriVips => "Juri Vips",
+            Driver::TheoPourchaire => "Theo Pourchaire",
+            Driver::RichardVerschoor => "Richard Verschoor",
+            Driver::LirimZendeli => "Lirim Zendeli",
+            Driver::DavidBeckmann => "David Beckmann",
+            Driver::AlessioDeledda => "Alessio Deledda",
+            Driver::BentViscaal => "Bent Viscaal",
+            Driver::EnzoFittipaldi => "Enzo Fittipaldi",
+            Driver::MarkWebber => "Mark Webber",
+            Driver::JacquesVilleneuve => "Jacques Villeneuve",
+            Driver::JakeHughes => "Jake Hughes",
+            Driver::FrederikVesti => "Frederik Vesti",
+            Driver::OlliCaldwell => "Olli Caldwell",
+            Driver::LoganSargeant => "Logan Sargeant",
+            Driver::CemBolukbasi => "Cem Bolukbasi",
+            Driver::AyumaIwasa => "Ayuma Iwasa",
+            Driver::ClementNovolak => "Clement Novolak",
+            Driver::DennisHauger => "Dennis Hauger",
+            Driver::CalanWilliams => "Calan Williams",
+            Driver::JackDoohan => "Jack Doohan",
+            Driver::AmauryCordeel => "Amaury Cordeel",
+            Driver::MikaHakkinen => "Mika Hakkinen",
+            Driver::NetworkHuman => "Network Human",
+            Driver::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Human players in online sessions aren't identified by a driver id.
+    pub fn is_network_human(&self) -> bool {
+        *self == Driver::NetworkHuman
+    }
+}
\ No newline at end of file
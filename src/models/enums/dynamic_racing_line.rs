@@ -1,15 +1,14 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum DynamicRacingLine {
-    None = 0,
-    Corners = 1,
-    All = 2,
+game_enum! {
+    pub enum DynamicRacingLine: u8 {
+        None = 0,
+        Corners = 1,
+        All = 2,
+    }
 }
 
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum DynamicRacingLineType {
-    L2D = 0,
-    L3D = 1,
+game_enum! {
+    pub enum DynamicRacingLineType: u8 {
+        L2D = 0,
+        L3D = 1,
+    }
 }
\ No newline at end of file
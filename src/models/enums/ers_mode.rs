@@ -1,10 +1,8 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum ErsMode {
-    None = 0,
-    Low = 1,
-    Medium = 2,
-    Overtake = 3,
+game_enum! {
+    pub enum ErsMode: u8 {
+        None = 0,
+        Low = 1,
+        Medium = 2,
+        Overtake = 3,
+    }
 }
\ No newline at end of file
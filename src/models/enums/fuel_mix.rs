@@ -1,10 +1,8 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum FuelMix {
-    Lean = 0,
-    Standard = 1,
-    Rich = 2,
-    Max = 3,
+game_enum! {
+    pub enum FuelMix: u8 {
+        Lean = 0,
+        Standard = 1,
+        Rich = 2,
+        Max = 3,
+    }
 }
\ No newline at end of file
@@ -1,9 +1,7 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum GearboxAssist {
-    NoAssist = 1,
-    SuggestedShift = 2,
-    AutoShift = 3,
+game_enum! {
+    pub enum GearboxAssist: u8 {
+        NoAssist = 1,
+        SuggestedShift = 2,
+        AutoShift = 3,
+    }
 }
\ No newline at end of file
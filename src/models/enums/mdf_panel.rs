@@ -1,12 +1,10 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum MdfPanel {
-    CarSetup = 0,
-    Pits = 1,
-    Damage = 2,
-    Engine = 3,
-    Temperatures = 4,
-    Unknown = 255,
+game_enum! {
+    pub enum MdfPanel: u8 {
+        CarSetup = 0,
+        Pits = 1,
+        Damage = 2,
+        Engine = 3,
+        Temperatures = 4,
+    }
+    unknown = [255]
 }
\ No newline at end of file
@@ -1,3 +1,62 @@
+/// Conversion between the raw byte sent by the game and an enum.
+pub trait GameEnum: Sized {
+    fn from_raw(raw: u8) -> Option<Self>;
+
+    /// Wraps a byte that doesn't match any known variant.
+    fn unknown(raw: u8) -> Self;
+
+    fn to_raw(&self) -> u8;
+}
+
+/// Declares an enum with the given discriminants and an `Unknown` variant holding any other value.
+///
+/// Values listed in `unknown` are accepted in strict mode but still decoded into `Unknown`.
+macro_rules! game_enum {
+    (
+        pub enum $name:ident: $repr:ty {
+            $($variant:ident = $value:literal,)*
+        }
+        $(unknown = [$($unknown:literal),*])?
+    ) => {
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, ::serde::Serialize)]
+        pub enum $name {
+            $($variant,)*
+            Unknown($repr),
+        }
+
+        impl $name {
+            pub fn from_value(value: $repr) -> Option<$name> {
+                match value {
+                    $($value => Some($name::$variant),)*
+                    $($($unknown)|* => Some($name::Unknown(value)),)?
+                    _ => None,
+                }
+            }
+
+            pub fn value(&self) -> $repr {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => *value,
+                }
+            }
+        }
+
+        impl $crate::models::enums::GameEnum for $name {
+            fn from_raw(raw: u8) -> Option<$name> {
+                $name::from_value(raw as $repr)
+            }
+
+            fn unknown(raw: u8) -> $name {
+                $name::Unknown(raw as $repr)
+            }
+
+            fn to_raw(&self) -> u8 {
+                self.value() as u8
+            }
+        }
+    };
+}
+
 mod gamemode;
 mod ruleset;
 mod session_length;
@@ -23,6 +82,9 @@ mod tyre_compound;
 mod vehicle_fia_flags;
 mod ers_mode;
 mod ready_status;
+mod track;
+mod nationality;
+mod driver;
 
 pub use gamemode::*;
 pub use ruleset::*;
@@ -48,4 +110,7 @@ pub use fuel_mix::*;
 pub use tyre_compound::*;
 pub use vehicle_fia_flags::*;
 pub use ers_mode::*;
-pub use ready_status::*;
\ No newline at end of file
+pub use ready_status::*;
+pub use track::*;
+pub use nationality::*;
+pub use driver::*;
\ No newline at end of file
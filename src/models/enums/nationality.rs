@@ -0,0 +1,281 @@
+game_enum! {
+    pub enum Nationality: u8 {
+        American = 1,
+        Argentinean = 2,
+        Australian = 3,
+        Austrian = 4,
+        Azerbaijani = 5,
+        Bahraini = 6,
+        Belgian = 7,
+        Bolivian = 8,
+        Brazilian = 9,
+        British = 10,
+        Bulgarian = 11,
+        Cameroonian = 12,
+        Canadian = 13,
+        Chilean = 14,
+        Chinese = 15,
+        Colombian = 16,
+        CostaRican = 17,
+        Croatian = 18,
+        Cypriot = 19,
+        Czech = 20,
+        Danish = 21,
+        Dutch = 22,
+        Ecuadorian = 23,
+        English = 24,
+        Emirian = 25,
+        Estonian = 26,
+        Finnish = 27,
+        French = 28,
+        German = 29,
+        Ghanaian = 30,
+        Greek = 31,
+        Guatemalan = 32,
+        Honduran = 33,
+        HongKonger = 34,
+        Hungarian = 35,
+        Icelander = 36,
+        Indian = 37,
+        Indonesian = 38,
+        Irish = 39,
+        Israeli = 40,
+        Italian = 41,
+        Jamaican = 42,
+        Japanese = 43,
+        Jordanian = 44,
+        Kuwaiti = 45,
+        Latvian = 46,
+        Lebanese = 47,
+        Lithuanian = 48,
+        Luxembourger = 49,
+        Malaysian = 50,
+        Maltese = 51,
+        Mexican = 52,
+        Monegasque = 53,
+        NewZealander = 54,
+        Nicaraguan = 55,
+        NorthernIrish = 56,
+        Norwegian = 57,
+        Omani = 58,
+        Pakistani = 59,
+        Panamanian = 60,
+        Paraguayan = 61,
+        Peruvian = 62,
+        Polish = 63,
+        Portuguese = 64,
+        Qatari = 65,
+        Romanian = 66,
+        Russian = 67,
+        Salvadoran = 68,
+        Saudi = 69,
+        Scottish = 70,
+        Serbian = 71,
+        Singaporean = 72,
+        Slovakian = 73,
+        Slovenian = 74,
+        SouthKorean = 75,
+        SouthAfrican = 76,
+        Spanish = 77,
+        Swedish = 78,
+        Swiss = 79,
+        Thai = 80,
+        Turkish = 81,
+        Uruguayan = 82,
+        Ukrainian = 83,
+        Venezuelan = 84,
+        Barbadian = 85,
+        Welsh = 86,
+        Vietnamese = 87,
+    }
+    unknown = [0]
+}
+
+impl Nationality {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Nationality::American => "American",
+            Nationality::Argentinean => "Argentinean",
+            Nationality::Australian => "Australian",
+            Nationality::Austrian => "Austrian",
+            Nationality::Azerbaijani => "Azerbaijani",
+            Nationality::Bahraini => "Bahraini",
+            Nationality::Belgian => "Belgian",
+            Nationality::Bolivian => "Bolivian",
+            Nationality::Brazilian => "Brazilian",
+            Nationality::British => "British",
+            Nationality::Bulgarian => "Bulgarian",
+            Nationality::Cameroonian => "Cameroonian",
+            Nationality::Canadian => "Canadian",
+            Nationality::Chilean => "Chilean",
+            Nationality::Chinese => "Chinese",
+            Nationality::Colombian => "Colombian",
+            Nationality::CostaRican => "Costa Rican",
+            Nationality::Croatian => "Croatian",
+            Nationality::Cypriot => "Cypriot",
+            Nationality::Czech => "Czech",
+            Nationality::Danish => "Danish",
+            Nationality::Dutch => "Dutch",
+            Nationality::Ecuadorian => "Ecuadorian",
+            Nationality::English => "English",
+            Nationality::Emirian => "Emirian",
+            Nationality::Estonian => "Estonian",
+            Nationality::Finnish => "Finnish",
+            Nationality::French => "French",
+            Nationality::German => "German",
+            Nationality::Ghanaian => "Ghanaian",
+            Nationality::Greek => "Greek",
+            Nationality::Guatemalan => "Guatemalan",
+            Nationality::Honduran => "Honduran",
+            Nationality::HongKonger => "Hong Konger",
+            Nationality::Hungarian => "Hungarian",
+            Nationality::Icelander => "Icelander",
+            Nationality::Indian => "Indian",
+            Nationality::Indonesian => "Indonesian",
+            Nationality::Irish => "Irish",
+            Nationality::Israeli => "Israeli",
+            Nationality::Italian => "Italian",
+            Nationality::Jamaican => "Jamaican",
+            Nationality::Japanese => "Japanese",
+            Nationality::Jordanian => "Jordanian",
+            Nationality::Kuwaiti => "Kuwaiti",
+            Nationality::Latvian => "Latvian",
+            Nationality::Lebanese => "Lebanese",
+            Nationality::Lithuanian => "Lithuanian",
+            Nationality::Luxembourger => "Luxembourger",
+            Nationality::Malaysian => "Malaysian",
+            Nationality::Maltese => "Maltese",
+            Nationality::Mexican => "Mexican",
+            Nationality::Monegasque => "Monegasque",
+            Nationality::NewZealander => "New Zealander",
+            Nationality::Nicaraguan => "Nicaraguan",
+            Nationality::NorthernIrish => "Northern Irish",
+            Nationality::Norwegian => "Norwegian",
+            Nationality::Omani => "Omani",
+            Nationality::Pakistani => "Pakistani",
+            Nationality::Panamanian => "Panamanian",
+            Nationality::Paraguayan => "Paraguayan",
+            Nationality::Peruvian => "Peruvian",
+            Nationality::Polish => "Polish",
+            Nationality::Portuguese => "Portuguese",
+            Nationality::Qatari => "Qatari",
+            Nationality::Romanian => "Romanian",
+            Nationality::Russian => "Russian",
+            Nationality::Salvadoran => "Salvadoran",
+            Nationality::Saudi => "Saudi",
+            Nationality::Scottish => "Scottish",
+            Nationality::Serbian => "Serbian",
+            Nationality::Singaporean => "Singaporean",
+            Nationality::Slovakian => "Slovakian",
+            Nationality::Slovenian => "Slovenian",
+            Nationality::SouthKorean => "South Korean",
+            Nationality::SouthAfrican => "South African",
+            Nationality::Spanish => "Spanish",
+            Nationality::Swedish => "Swedish",
+            Nationality::Swiss => "Swiss",
+            Nationality::Thai => "Thai",
+            Nationality::Turkish => "Turkish",
+            Nationality::Uruguayan => "Uruguayan",
+            Nationality::Ukrainian => "Ukrainian",
+            Nationality::Venezuelan => "Venezuelan",
+            Nationality::Barbadian => "Barbadian",
+            Nationality::Welsh => "Welsh",
+            Nationality::Vietnamese => "Vietnamese",
+            Nationality::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// ISO 3166-1 alpha-2 code, the home nations of the United Kingdom use their ISO 3166-2 code.
+    pub fn iso_code(&self) -> Option<&'static str> {
+        Some(match self {
+            Nationality::American => "US",
+            Nationality::Argentinean => "AR",
+            Nationality::Australian => "AU",
+            Nationality::Austrian => "AT",
+            Nationality::Azerbaijani => "AZ",
+            Nationality::Bahraini => "BH",
+            Nationality::Belgian => "BE",
+            Nationality::Bolivian => "BO",
+            Nationality::Brazilian => "BR",
+            Nationality::British => "GB",
+            Nationality::Bulgarian => "BG",
+            Nationality::Cameroonian => "CM",
+            Nationality::Canadian => "CA",
+            Nationality::Chilean => "CL",
+            Nationality::Chinese => "CN",
+            Nationality::Colombian => "CO",
+            Nationality::CostaRican => "CR",
+            Nationality::Croatian => "HR",
+            Nationality::Cypriot => "CY",
+            Nationality::Czech => "CZ",
+            Nationality::Danish => "DK",
+            Nationality::Dutch => "NL",
+            Nationality::Ecuadorian => "EC",
+            Nationality::English => "GB-ENG",
+            Nationality::Emirian => "AE",
+            Nationality::Estonian => "EE",
+            Nationality::Finnish => "FI",
+            Nationality::French => "FR",
+            Nationality::German => "DE",
+            Nationality::Ghanaian => "GH",
+            Nationality::Greek => "GR",
+            Nationality::Guatemalan => "GT",
+            Nationality::Honduran => "HN",
+            Nationality::HongKonger => "HK",
+            Nationality::Hungarian => "HU",
+            Nationality::Icelander => "IS",
+            Nationality::Indian => "IN",
+            Nationality::Indonesian => "ID",
+            Nationality::Irish => "IE",
+            Nationality::Israeli => "IL",
+            Nationality::Italian => "IT",
+            Nationality::Jamaican => "JM",
+            Nationality::Japanese => "JP",
+            Nationality::Jordanian => "JO",
+            Nationality::Kuwaiti => "KW",
+            Nationality::Latvian => "LV",
+            Nationality::Lebanese => "LB",
+            Nationality::Lithuanian => "LT",
+            Nationality::Luxembourger => "LU",
+            Nationality::Malaysian => "MY",
+            Nationality::Maltese => "MT",
+            Nationality::Mexican => "MX",
+            Nationality::Monegasque => "MC",
+            Nationality::NewZealander => "NZ",
+            Nationality::Nicaraguan => "NI",
+            Nationality::NorthernIrish => "GB-NIR",
+            Nationality::Norwegian => "NO",
+            Nationality::Omani => "OM",
+            Nationality::Pakistani => "PK",
+            Nationality::Panamanian => "PA",
+            Nationality::Paraguayan => "PY",
+            Nationality::Peruvian => "PE",
+            Nationality::Polish => "PL",
+            Nationality::Portuguese => "PT",
+            Nationality::Qatari => "QA",
+            Nationality::Romanian => "RO",
+            Nationality::Russian => "RU",
+            Nationality::Salvadoran => "SV",
+            Nationality::Saudi => "SA",
+            Nationality::Scottish => "GB-SCT",
+            Nationality::Serbian => "RS",
+            Nationality::Singaporean => "SG",
+            Nationality::Slovakian => "SK",
+            Nationality::Slovenian => "SI",
+            Nationality::SouthKorean => "KR",
+            Nationality::SouthAfrican => "ZA",
+            Nationality::Spanish => "ES",
+            Nationality::Swedish => "SE",
+            Nationality::Swiss => "CH",
+            Nationality::Thai => "TH",
+            Nationality::Turkish => "TR",
+            Nationality::Uruguayan => "UY",
+            Nationality::Ukrainian => "UA",
+            Nationality::Venezuelan => "VE",
+            Nationality::Barbadian => "BB",
+            Nationality::Welsh => "GB-WLS",
+            Nationality::Vietnamese => "VN",
+            Nationality::Unknown(_) => return None,
+        })
+    }
+}
\ No newline at end of file
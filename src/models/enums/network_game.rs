@@ -1,8 +1,6 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum NetworkGame {
-    Offline = 0,
-    Online = 1,
+game_enum! {
+    pub enum NetworkGame: u8 {
+        Offline = 0,
+        Online = 1,
+    }
 }
\ No newline at end of file
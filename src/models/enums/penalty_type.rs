@@ -1,24 +1,22 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum PenaltyType {
-    DriveThrough = 0,
-    StopGo = 1,
-    GridPenalty = 2,
-    PenaltyReminder = 3,
-    TimePenalty = 4,
-    Warning = 5,
-    Disqualified = 6,
-    RemovedFromFormationLap = 7,
-    ParkTooLong = 8,
-    TyreRegulations = 9,
-    ThisLapInvalidated = 10,
-    ThisAndNextLapInvalidated = 11,
-    ThisLapInvalidatedWithoutReason = 12,
-    ThisAndNextLapInvalidatedWithoutReason = 13,
-    ThisAndPreviousLapInvalidated = 14,
-    ThisAndPreviousLapInvalidatedWithoutReason = 15,
-    Retired = 16,
-    BlackFlag = 17,
+game_enum! {
+    pub enum PenaltyType: u8 {
+        DriveThrough = 0,
+        StopGo = 1,
+        GridPenalty = 2,
+        PenaltyReminder = 3,
+        TimePenalty = 4,
+        Warning = 5,
+        Disqualified = 6,
+        RemovedFromFormationLap = 7,
+        ParkTooLong = 8,
+        TyreRegulations = 9,
+        ThisLapInvalidated = 10,
+        ThisAndNextLapInvalidated = 11,
+        ThisLapInvalidatedWithoutReason = 12,
+        ThisAndNextLapInvalidatedWithoutReason = 13,
+        ThisAndPreviousLapInvalidated = 14,
+        ThisAndPreviousLapInvalidatedWithoutReason = 15,
+        Retired = 16,
+        BlackFlag = 17,
+    }
 }
\ No newline at end of file
@@ -1,9 +1,7 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum PitStatus {
-    None = 0,
-    Pitting = 1,
-    InPitArea = 2,
+game_enum! {
+    pub enum PitStatus: u8 {
+        None = 0,
+        Pitting = 1,
+        InPitArea = 2,
+    }
 }
\ No newline at end of file
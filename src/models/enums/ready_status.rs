@@ -1,9 +1,7 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum ReadyStatus {
-    NotReady = 0,
-    Ready = 1,
-    Spectating = 2,
+game_enum! {
+    pub enum ReadyStatus: u8 {
+        NotReady = 0,
+        Ready = 1,
+        Spectating = 2,
+    }
 }
\ No newline at end of file
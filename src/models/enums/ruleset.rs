@@ -1,16 +1,13 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum RuleSet {
-    PracticeAndQualifying = 0,
-    Race = 1,
-    TimeTrial = 2,
-    TimeAttack = 4,
-    CheckPointChallenge = 6,
-    Autocross = 8,
-    Drift = 9,
-    AverageSpeedZone = 10,
-    RivalDuel = 11,
-    Unknown,
+game_enum! {
+    pub enum RuleSet: u8 {
+        PracticeAndQualifying = 0,
+        Race = 1,
+        TimeTrial = 2,
+        TimeAttack = 4,
+        CheckPointChallenge = 6,
+        Autocross = 8,
+        Drift = 9,
+        AverageSpeedZone = 10,
+        RivalDuel = 11,
+    }
 }
\ No newline at end of file
@@ -1,10 +1,8 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum SafetyCarStatus {
-    NoSafetyCar = 0,
-    FullSafetyCar = 1,
-    VirtualSafetyCar = 2,
-    FormationLap = 3,
+game_enum! {
+    pub enum SafetyCarStatus: u8 {
+        NoSafetyCar = 0,
+        FullSafetyCar = 1,
+        VirtualSafetyCar = 2,
+        FormationLap = 3,
+    }
 }
\ No newline at end of file
@@ -1,14 +1,11 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum SessionLength {
-    None = 0,
-    VeryShort = 2,
-    Short = 3,
-    Medium = 4,
-    MediumLong = 5,
-    Long = 6,
-    Full = 7,
-    Unknown,
+game_enum! {
+    pub enum SessionLength: u8 {
+        None = 0,
+        VeryShort = 2,
+        Short = 3,
+        Medium = 4,
+        MediumLong = 5,
+        Long = 6,
+        Full = 7,
+    }
 }
\ No newline at end of file
@@ -1,18 +1,16 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum SurfaceType {
-    Tarmac = 0,
-    RumbleStrip = 1,
-    Concrete = 2,
-    Rock = 3,
-    Gravel = 4,
-    Mud = 5,
-    Sand = 6,
-    Grass = 7,
-    Water = 8,
-    Cobblestone = 9,
-    Metal = 10,
-    Ridged = 11,
+game_enum! {
+    pub enum SurfaceType: u8 {
+        Tarmac = 0,
+        RumbleStrip = 1,
+        Concrete = 2,
+        Rock = 3,
+        Gravel = 4,
+        Mud = 5,
+        Sand = 6,
+        Grass = 7,
+        Water = 8,
+        Cobblestone = 9,
+        Metal = 10,
+        Ridged = 11,
+    }
 }
\ No newline at end of file
@@ -1,49 +1,81 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum Team {
-    Mercedes = 0,
-    Ferrari = 1,
-    RedBullRacing = 2,
-    Williams = 3,
-    AstonMartin = 4,
-    Alpine = 5,
-    AlphaTauri = 6,
-    Haas = 7,
-    McLaren = 8,
-    AlfaRomeo = 9,
-    Mercedes2020 = 85,
-    Ferrari2020 = 86,
-    RedBullRacing2020 = 87,
-    Williams2020 = 88,
-    RacingPoint2020 = 89,
-    Renault2020 = 90,
-    AlphaTauri2020 = 91,
-    Haas2020 = 92,
-    McLaren2020 = 93,
-    AlfaRomeo2020 = 94,
-    AstonMartinDB11V12 = 95,
-    AstonMartinVantageF1 = 96,
-    AstonMartinVantageSafety = 97,
-    FerrariF8Tributo = 98,
-    FerrariRoma = 99,
-    McLaren720S = 100,
-    McLarenArtura = 101,
-    MercedesAMGGTSafety = 102,
-    MercedesAMGGTR = 103,
-    F1CustomTeam = 104,
-    Prema21 = 106,
-    UniVirtuosi21 = 107,
-    Carlin21 = 108,
-    Hitech21 = 109,
-    ArtGP21 = 110,
-    MPMotorsport21 = 111,
-    Charouz21 = 112,
-    Dams21 = 113,
-    Campos21 = 114,
-    BWT21 = 115,
-    Trident21 = 116,
-    MercedesAMGGT = 117,
-    Unknown = 255,
+game_enum! {
+    pub enum Team: u8 {
+        Mercedes = 0,
+        Ferrari = 1,
+        RedBullRacing = 2,
+        Williams = 3,
+        AstonMartin = 4,
+        Alpine = 5,
+        AlphaTauri = 6,
+        Haas = 7,
+        McLaren = 8,
+        AlfaRomeo = 9,
+        Mercedes2020 = 85,
+        Ferrari2020 = 86,
+        RedBullRacing2020 = 87,
+        Williams2020 = 88,
+        RacingPoint2020 = 89,
+        Renault2020 = 90,
+        AlphaTauri2020 = 91,
+        Haas2020 = 92,
+        McLaren2020 = 93,
+        AlfaRomeo2020 = 94,
+        AstonMartinDB11V12 = 95,
+        AstonMartinVantageF1 = 96,
+        AstonMartinVantageSafety = 97,
+        FerrariF8Tributo = 98,
+        FerrariRoma = 99,
+        McLaren720S = 100,
+        McLarenArtura = 101,
+        MercedesAMGGTSafety = 102,
+        MercedesAMGGTR = 103,
+        F1CustomTeam = 104,
+        Prema21 = 106,
+        UniVirtuosi21 = 107,
+        Carlin21 = 108,
+        Hitech21 = 109,
+        ArtGP21 = 110,
+        MPMotorsport21 = 111,
+        Charouz21 = 112,
+        Dams21 = 113,
+        Campos21 = 114,
+        BWT21 = 115,
+        Trident21 = 116,
+        MercedesAMGGT = 117,
+        Mercedes22 = 118,
+        Ferrari22 = 119,
+        RedBullRacing22 = 120,
+        Williams22 = 121,
+        AstonMartin22 = 122,
+        Alpine22 = 123,
+        AlphaTauri22 = 124,
+        Haas22 = 125,
+        McLaren22 = 126,
+        AlfaRomeo22 = 127,
+        Konnersport22 = 128,
+        Konnersport = 129,
+        Prema22 = 130,
+        Virtuosi22 = 131,
+        Carlin22 = 132,
+        MPMotorsport22 = 133,
+        Charouz22 = 134,
+        Dams22 = 135,
+        Campos22 = 136,
+        VanAmersfoortRacing22 = 137,
+        Trident22 = 138,
+        Hitech22 = 139,
+        ArtGP22 = 140,
+        ArtGP23 = 143,
+        Campos23 = 144,
+        Carlin23 = 145,
+        PHM23 = 146,
+        Dams23 = 147,
+        Hitech23 = 148,
+        MPMotorsport23 = 149,
+        Prema23 = 150,
+        Trident23 = 151,
+        VanAmersfoortRacing23 = 152,
+        Virtuosi23 = 153,
+    }
+    unknown = [255]
 }
\ No newline at end of file
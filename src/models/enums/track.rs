@@ -0,0 +1,224 @@
+use serde::Serialize;
+use crate::models::LapData;
+use crate::models::enums::Sector;
+
+game_enum! {
+    pub enum Track: i8 {
+        Melbourne = 0,
+        PaulRicard = 1,
+        Shanghai = 2,
+        Sakhir = 3,
+        Catalunya = 4,
+        Monaco = 5,
+        Montreal = 6,
+        Silverstone = 7,
+        Hockenheim = 8,
+        Hungaroring = 9,
+        Spa = 10,
+        Monza = 11,
+        Singapore = 12,
+        Suzuka = 13,
+        AbuDhabi = 14,
+        Texas = 15,
+        Brazil = 16,
+        Austria = 17,
+        Sochi = 18,
+        Mexico = 19,
+        Baku = 20,
+        SakhirShort = 21,
+        SilverstoneShort = 22,
+        TexasShort = 23,
+        SuzukaShort = 24,
+        Hanoi = 25,
+        Zandvoort = 26,
+        Imola = 27,
+        Portimao = 28,
+        Jeddah = 29,
+        Miami = 30,
+        LasVegas = 31,
+        Losail = 32,
+    }
+    unknown = [-1]
+}
+
+/// Static information about a track layout.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct TrackInfo {
+    pub name: &'static str,
+    pub country: &'static str,
+    /// Length of the real world layout in metres, `None` where the game uses its own layout.
+    ///
+    /// The in game length is sent in [`crate::models::SessionDataPacket::track_length`].
+    pub length: Option<u16>,
+    pub sectors: u8,
+    /// Approximate lap distance in metres at which sector 2 and sector 3 start, `None` where
+    /// the game uses its own layout.
+    pub sector_starts: Option<[u16; 2]>,
+}
+
+const fn info(name: &'static str, country: &'static str, length: Option<u16>, sector_starts: Option<[u16; 2]>) -> TrackInfo {
+    TrackInfo {
+        name,
+        country,
+        length,
+        sectors: 3,
+        sector_starts,
+    }
+}
+
+/// Indexed by the track id, the games up to F1 23 have no reverse layouts.
+const TRACKS: [TrackInfo; 33] = [
+    info("Melbourne", "Australia", Some(5278), Some([1560, 3580])),
+    info("Paul Ricard", "France", Some(5842), Some([2090, 4010])),
+    info("Shanghai", "China", Some(5451), Some([1550, 3630])),
+    info("Sakhir (Bahrain)", "Bahrain", Some(5412), Some([1500, 3840])),
+    info("Catalunya", "Spain", Some(4675), Some([1850, 3150])),
+    info("Monaco", "Monaco", Some(3337), Some([1090, 2250])),
+    info("Montreal", "Canada", Some(4361), Some([1230, 2930])),
+    info("Silverstone", "United Kingdom", Some(5891), Some([1880, 4220])),
+    info("Hockenheim", "Germany", Some(4574), Some([1420, 3040])),
+    info("Hungaroring", "Hungary", Some(4381), Some([1490, 3010])),
+    info("Spa", "Belgium", Some(7004), Some([2220, 5210])),
+    info("Monza", "Italy", Some(5793), Some([1930, 3970])),
+    info("Singapore", "Singapore", Some(5063), Some([1700, 3440])),
+    info("Suzuka", "Japan", Some(5807), Some([2030, 4090])),
+    info("Abu Dhabi", "United Arab Emirates", Some(5281), Some([1780, 3630])),
+    info("Texas", "United States", Some(5513), Some([1270, 3810])),
+    info("Brazil", "Brazil", Some(4309), Some([910, 3190])),
+    info("Austria", "Austria", Some(4318), Some([1340, 2760])),
+    info("Sochi", "Russia", Some(5848), Some([1830, 3930])),
+    info("Mexico", "Mexico", Some(4304), Some([1860, 3290])),
+    info("Baku (Azerbaijan)", "Azerbaijan", Some(6003), Some([2030, 4200])),
+    info("Sakhir Short", "Bahrain", Some(3543), Some([1190, 2390])),
+    info("Silverstone Short", "United Kingdom", None, None),
+    info("Texas Short", "United States", None, None),
+    info("Suzuka Short", "Japan", Some(2243), Some([760, 1500])),
+    info("Hanoi", "Vietnam", Some(5607), Some([1870, 3740])),
+    info("Zandvoort", "Netherlands", Some(4259), Some([1240, 2920])),
+    info("Imola", "Italy", Some(4909), Some([1790, 3580])),
+    info("Portimão", "Portugal", Some(4653), Some([1670, 3290])),
+    info("Jeddah", "Saudi Arabia", Some(6174), Some([2110, 4290])),
+    info("Miami", "United States", Some(5412), Some([1530, 3920])),
+    info("Las Vegas", "United States", Some(6201), Some([1990, 4220])),
+    info("Losail", "Qatar", Some(5419), Some([1880, 3720])),
+];
+
+impl Track {
+    /// Returns `None` for unknown tracks.
+    pub fn info(&self) -> Option<&'static TrackInfo> {
+        match self {
+            Track::Unknown(_) => None,
+            track => TRACKS.get(track.value() as usize),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.info().map(|info| info.name).unwrap_or("Unknown")
+    }
+}
+
+/// Sector boundaries of a track measured as lap distance in metres.
+///
+/// The game doesn't send where sectors start. Boundaries can start from the approximate
+/// distances of [`TrackInfo::sector_starts`] and are refined from the lap data of any car
+/// crossing into the next sector.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize)]
+pub struct SectorBoundaries {
+    boundaries: [Option<f32>; 2],
+    last_sector: Option<Sector>,
+}
+
+impl SectorBoundaries {
+    pub fn new() -> SectorBoundaries {
+        SectorBoundaries::default()
+    }
+
+    /// Starts from the approximate sector starts of `track`, if it has any.
+    pub fn for_track(track: Track) -> SectorBoundaries {
+        let starts = track.info().and_then(|info| info.sector_starts);
+        SectorBoundaries {
+            boundaries: starts.map_or([None; 2], |starts| starts.map(|start| Some(start as f32))),
+            last_sector: None,
+        }
+    }
+
+    /// Feeds the lap data of a single car, call it for one car only.
+    pub fn observe(&mut self, lap_data: &LapData) {
+        let boundary = match (self.last_sector, lap_data.sector) {
+            (Some(Sector::Sector1), Sector::Sector2) => Some(0),
+            (Some(Sector::Sector2), Sector::Sector3) => Some(1),
+            _ => None,
+        };
+        if let Some(boundary) = boundary {
+            if lap_data.lap_distance > 0.0 {
+                self.boundaries[boundary] = Some(lap_data.lap_distance);
+            }
+        }
+        self.last_sector = Some(lap_data.sector);
+    }
+
+    /// Lap distance at which sector 2 and sector 3 start, once crossed at least once or taken
+    /// from the track info.
+    pub fn boundaries(&self) -> [Option<f32>; 2] {
+        self.boundaries
+    }
+
+    /// Sector the given lap distance belongs to, `None` until both boundaries are known.
+    pub fn sector_at(&self, lap_distance: f32) -> Option<Sector> {
+        match self.boundaries {
+            [Some(sector2), Some(sector3)] => Some(if lap_distance < sector2 {
+                Sector::Sector1
+            } else if lap_distance < sector3 {
+                Sector::Sector2
+            } else {
+                Sector::Sector3
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DecodeMode, PacketReader};
+
+    fn lap_data(sector: u8, lap_distance: f32) -> LapData {
+        let mut bytes = [0; 43];
+        bytes[12..16].copy_from_slice(&lap_distance.to_le_bytes());
+        bytes[28] = sector;
+        LapData::new(&mut PacketReader::with_mode(&bytes[..], DecodeMode::Lenient)).unwrap()
+    }
+
+    #[test]
+    fn looks_up_track_info() {
+        assert_eq!(Track::from_value(26), Some(Track::Zandvoort));
+        assert_eq!(Track::Zandvoort.info().unwrap().country, "Netherlands");
+        assert_eq!(Track::Miami.name(), "Miami");
+        assert_eq!(Track::from_value(-1).unwrap().info(), None);
+    }
+
+    #[test]
+    fn learns_sector_boundaries() {
+        let mut boundaries = SectorBoundaries::new();
+        for (sector, lap_distance) in [(0, 100.0), (0, 1500.0), (1, 1510.0), (1, 3000.0), (2, 3020.0)] {
+            boundaries.observe(&lap_data(sector, lap_distance));
+        }
+
+        assert_eq!(boundaries.boundaries(), [Some(1510.0), Some(3020.0)]);
+        assert_eq!(boundaries.sector_at(2000.0), Some(Sector::Sector2));
+    }
+
+    #[test]
+    fn starts_from_approximate_sector_starts() {
+        let mut boundaries = SectorBoundaries::for_track(Track::Monza);
+        assert_eq!(boundaries.boundaries(), [Some(1930.0), Some(3970.0)]);
+        assert_eq!(boundaries.sector_at(4000.0), Some(Sector::Sector3));
+
+        boundaries.observe(&lap_data(0, 1900.0));
+        boundaries.observe(&lap_data(1, 1915.0));
+        assert_eq!(boundaries.boundaries(), [Some(1915.0), Some(3970.0)]);
+
+        assert_eq!(SectorBoundaries::for_track(Track::TexasShort).boundaries(), [None, None]);
+    }
+}
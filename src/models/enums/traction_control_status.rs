@@ -1,9 +1,7 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum TractionControlStatus {
-    Off = 0,
-    Medium = 1,
-    Full = 2,
+game_enum! {
+    pub enum TractionControlStatus: u8 {
+        Off = 0,
+        Medium = 1,
+        Full = 2,
+    }
 }
\ No newline at end of file
@@ -1,12 +1,10 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum Weather {
-    Clear = 0,
-    LightCloud = 1,
-    Overcast = 2,
-    LightRain = 3,
-    HeavyRain = 4,
-    Storm = 5,
+game_enum! {
+    pub enum Weather: u8 {
+        Clear = 0,
+        LightCloud = 1,
+        Overcast = 2,
+        LightRain = 3,
+        HeavyRain = 4,
+        Storm = 5,
+    }
 }
\ No newline at end of file
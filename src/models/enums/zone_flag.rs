@@ -1,12 +1,10 @@
-use num_derive::FromPrimitive;
-use serde::Serialize;
-
-#[derive(FromPrimitive, Copy, Clone, Debug, Serialize)]
-pub enum ZoneFlag {
-    InvalidUnknown = -1,
-    None = 0,
-    Green = 1,
-    Blue = 2,
-    Yellow = 3,
-    Red = 4,
+game_enum! {
+    pub enum ZoneFlag: i8 {
+        InvalidUnknown = -1,
+        None = 0,
+        Green = 1,
+        Blue = 2,
+        Yellow = 3,
+        Red = 4,
+    }
 }
\ No newline at end of file
@@ -1,43 +1,53 @@
-use std::fmt::Error;
-use std::io::Read;
-use crate::models::PacketHeader;
-use byteorder::{ReadBytesExt, LittleEndian};
-use crate::models::enums::{InfringementType, PenaltyType};
-use num_traits::FromPrimitive;
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::enums::{GameEnum, InfringementType, PenaltyType};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct FastestLap {
     pub vehicle_index: u8,
     pub lap_time: f32,
 }
 
 impl FastestLap {
-    pub fn new<R: Read>(reader: &mut R) -> Result<FastestLap, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<FastestLap, ParseError> {
         Ok(FastestLap {
-            vehicle_index: reader.read_u8()?,
-            lap_time: reader.read_f32::<LittleEndian>()?,
+            vehicle_index: reader.read_u8("vehicle_index")?,
+            lap_time: reader.read_f32("lap_time")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.vehicle_index)?;
+        writer.write_f32::<LittleEndian>(self.lap_time)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct OnlyIndex {
     pub vehicle_index: u8,
 }
 
 impl OnlyIndex {
-    pub fn new<R: Read>(reader: &mut R) -> Result<OnlyIndex, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<OnlyIndex, ParseError> {
         Ok(OnlyIndex {
-            vehicle_index: reader.read_u8()?,
+            vehicle_index: reader.read_u8("vehicle_index")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.vehicle_index)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct Penalty {
     pub penalty_type: PenaltyType,
     pub infringement_type: InfringementType,
@@ -49,20 +59,32 @@ pub struct Penalty {
 }
 
 impl Penalty {
-    pub fn new<R: Read>(reader: &mut R) -> Result<Penalty, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<Penalty, ParseError> {
         Ok(Penalty {
-            penalty_type: PenaltyType::from_u8(reader.read_u8()?).unwrap(),
-            infringement_type: InfringementType::from_u8(reader.read_u8()?).unwrap(),
-            vehicle_index: reader.read_u8()?,
-            other_vehicle_index: reader.read_u8()?,
-            time: reader.read_u8()?,
-            lap_num: reader.read_u8()?,
-            places_gained: reader.read_u8()?,
+            penalty_type: reader.read_enum("penalty_type")?,
+            infringement_type: reader.read_enum("infringement_type")?,
+            vehicle_index: reader.read_u8("vehicle_index")?,
+            other_vehicle_index: reader.read_u8("other_vehicle_index")?,
+            time: reader.read_u8("time")?,
+            lap_num: reader.read_u8("lap_num")?,
+            places_gained: reader.read_u8("places_gained")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.penalty_type.to_raw())?;
+        writer.write_u8(self.infringement_type.to_raw())?;
+        writer.write_u8(self.vehicle_index)?;
+        writer.write_u8(self.other_vehicle_index)?;
+        writer.write_u8(self.time)?;
+        writer.write_u8(self.lap_num)?;
+        writer.write_u8(self.places_gained)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct SpeedTrap {
     pub vehicle_index: u8,
     pub speed: f32,
@@ -73,60 +95,90 @@ pub struct SpeedTrap {
 }
 
 impl SpeedTrap {
-    pub fn new<R: Read>(reader: &mut R) -> Result<SpeedTrap, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<SpeedTrap, ParseError> {
         Ok(SpeedTrap {
-            vehicle_index: reader.read_u8()?,
-            speed: reader.read_f32::<LittleEndian>()?,
-            is_overall_fastest_in_session: reader.read_u8()? != 0,
-            is_driver_fastest_in_session: reader.read_u8()? != 0,
-            fastest_vehicle_idx_in_session: reader.read_u8()?,
-            fastest_speed_in_session: reader.read_f32::<LittleEndian>()?,
+            vehicle_index: reader.read_u8("vehicle_index")?,
+            speed: reader.read_f32("speed")?,
+            is_overall_fastest_in_session: reader.read_bool("is_overall_fastest_in_session")?,
+            is_driver_fastest_in_session: reader.read_bool("is_driver_fastest_in_session")?,
+            fastest_vehicle_idx_in_session: reader.read_u8("fastest_vehicle_idx_in_session")?,
+            fastest_speed_in_session: reader.read_f32("fastest_speed_in_session")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.vehicle_index)?;
+        writer.write_f32::<LittleEndian>(self.speed)?;
+        writer.write_u8(self.is_overall_fastest_in_session as u8)?;
+        writer.write_u8(self.is_driver_fastest_in_session as u8)?;
+        writer.write_u8(self.fastest_vehicle_idx_in_session)?;
+        writer.write_f32::<LittleEndian>(self.fastest_speed_in_session)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct StartLights {
     pub num_lights: u8,
 }
 
 impl StartLights {
-    pub fn new<R: Read>(reader: &mut R) -> Result<StartLights, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<StartLights, ParseError> {
         Ok(StartLights {
-            num_lights: reader.read_u8()?,
+            num_lights: reader.read_u8("num_lights")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.num_lights)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct Flashback {
     pub frame_identifier: u32,
     pub session_time: f32,
 }
 
 impl Flashback {
-    pub fn new<R: Read>(reader: &mut R) -> Result<Flashback, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<Flashback, ParseError> {
         Ok(Flashback {
-            frame_identifier: reader.read_u32::<LittleEndian>()?,
-            session_time: reader.read_f32::<LittleEndian>()?,
+            frame_identifier: reader.read_u32("frame_identifier")?,
+            session_time: reader.read_f32("session_time")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u32::<LittleEndian>(self.frame_identifier)?;
+        writer.write_f32::<LittleEndian>(self.session_time)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct Buttons {
     pub button_status: u32,
 }
 
 impl Buttons {
-    pub fn new<R: Read>(reader: &mut R) -> Result<Buttons, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<Buttons, ParseError> {
         Ok(Buttons {
-            button_status: reader.read_u32::<LittleEndian>()?,
+            button_status: reader.read_u32("button_status")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u32::<LittleEndian>(self.button_status)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub enum EventDetails {
     SessionStarted,
     SessionEnded,
@@ -148,31 +200,72 @@ pub enum EventDetails {
 }
 
 impl EventDetails {
-    pub fn from_str<R: Read>(value: &str, reader: &mut R) -> Result<Self, Error> {
-        Ok(match value {
+    /// Reads the details belonging to the event code `value`, returns `None` for unknown codes.
+    pub fn from_str<R: Read>(value: &str, reader: &mut PacketReader<R>) -> Result<Option<Self>, ParseError> {
+        Ok(Some(match value {
             "SSTA" => EventDetails::SessionStarted,
             "SEND" => EventDetails::SessionEnded,
-            "FTLP" => EventDetails::FastestLap(FastestLap::new(reader).unwrap()),
-            "RTMT" => EventDetails::Retirement(OnlyIndex::new(reader).unwrap()),
+            "FTLP" => EventDetails::FastestLap(FastestLap::new(reader)?),
+            "RTMT" => EventDetails::Retirement(OnlyIndex::new(reader)?),
             "DRSE" => EventDetails::DRSEnabled,
             "DRSD" => EventDetails::DRSDisabled,
-            "TMPT" => EventDetails::TeamMateInPits(OnlyIndex::new(reader).unwrap()),
+            "TMPT" => EventDetails::TeamMateInPits(OnlyIndex::new(reader)?),
             "CHQF" => EventDetails::ChequeredFlag,
-            "RCWN" => EventDetails::RaceWinner(OnlyIndex::new(reader).unwrap()),
-            "PENA" => EventDetails::Penalty(Penalty::new(reader).unwrap()),
-            "SPTP" => EventDetails::SpeedTrap(SpeedTrap::new(reader).unwrap()),
-            "STLG" => EventDetails::StartLights(StartLights::new(reader).unwrap()),
+            "RCWN" => EventDetails::RaceWinner(OnlyIndex::new(reader)?),
+            "PENA" => EventDetails::Penalty(Penalty::new(reader)?),
+            "SPTP" => EventDetails::SpeedTrap(SpeedTrap::new(reader)?),
+            "STLG" => EventDetails::StartLights(StartLights::new(reader)?),
             "LGOT" => EventDetails::LightsOut,
-            "DTSV" => EventDetails::DriveThroughPenaltyServed(OnlyIndex::new(reader).unwrap()),
-            "SGSV" => EventDetails::StopGoPenaltyServed(OnlyIndex::new(reader).unwrap()),
-            "FLBK" => EventDetails::Flashback(Flashback::new(reader).unwrap()),
-            "BUTN" => EventDetails::ButtonStatus(Buttons::new(reader).unwrap()),
-            _ => return Err(Error::default()),
-        })
+            "DTSV" => EventDetails::DriveThroughPenaltyServed(OnlyIndex::new(reader)?),
+            "SGSV" => EventDetails::StopGoPenaltyServed(OnlyIndex::new(reader)?),
+            "FLBK" => EventDetails::Flashback(Flashback::new(reader)?),
+            "BUTN" => EventDetails::ButtonStatus(Buttons::new(reader)?),
+            _ => return Ok(None),
+        }))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EventDetails::SessionStarted => "SSTA",
+            EventDetails::SessionEnded => "SEND",
+            EventDetails::FastestLap(_) => "FTLP",
+            EventDetails::Retirement(_) => "RTMT",
+            EventDetails::DRSEnabled => "DRSE",
+            EventDetails::DRSDisabled => "DRSD",
+            EventDetails::TeamMateInPits(_) => "TMPT",
+            EventDetails::ChequeredFlag => "CHQF",
+            EventDetails::RaceWinner(_) => "RCWN",
+            EventDetails::Penalty(_) => "PENA",
+            EventDetails::SpeedTrap(_) => "SPTP",
+            EventDetails::StartLights(_) => "STLG",
+            EventDetails::LightsOut => "LGOT",
+            EventDetails::DriveThroughPenaltyServed(_) => "DTSV",
+            EventDetails::StopGoPenaltyServed(_) => "SGSV",
+            EventDetails::Flashback(_) => "FLBK",
+            EventDetails::ButtonStatus(_) => "BUTN",
+        }
+    }
+
+    /// Writes the details without the event code.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            EventDetails::FastestLap(details) => details.write_to(writer),
+            EventDetails::Retirement(details)
+            | EventDetails::TeamMateInPits(details)
+            | EventDetails::RaceWinner(details)
+            | EventDetails::DriveThroughPenaltyServed(details)
+            | EventDetails::StopGoPenaltyServed(details) => details.write_to(writer),
+            EventDetails::Penalty(details) => details.write_to(writer),
+            EventDetails::SpeedTrap(details) => details.write_to(writer),
+            EventDetails::StartLights(details) => details.write_to(writer),
+            EventDetails::Flashback(details) => details.write_to(writer),
+            EventDetails::ButtonStatus(details) => details.write_to(writer),
+            _ => Ok(()),
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PacketEventData {
     pub header: PacketHeader,
     pub event_string_code: [u8; 4],
@@ -180,28 +273,36 @@ pub struct PacketEventData {
     pub test: Vec<u8>,
 }
 
-event_system::signal!(EventSignal<EventReceiver, PacketEventData> = []);
-
 impl Packet for PacketEventData {
     const PACKET_ID: u8 = 3;
     const PACKET_SIZE: usize = 40;
 
-    fn new<R: Read>(reader: &mut R) -> Result<PacketEventData, std::io::Error> {
-        let mut event_string_code = [0; 4];
-
-        reader.read_exact(&mut event_string_code)?;
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketEventData, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let event_string_code = reader.read_bytes::<4>("event_string_code")?;
         let event_details = EventDetails::from_str(
             &String::from_utf8_lossy(&event_string_code),
             reader,
-        ).ok();
+        )?;
 
         Ok(PacketEventData {
-            header: PacketHeader::new(reader)?,
+            header,
             event_string_code,
             event_details,
             test: vec![],
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_all(&self.event_string_code)?;
+
+        // The details are a union of 12 bytes, unused bytes are sent as zeros.
+        let mut details = Vec::with_capacity(12);
+        if let Some(event_details) = &self.event_details {
+            event_details.write_to(&mut details)?;
+        }
+        details.resize(12, 0);
+        writer.write_all(&details)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,137 @@
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader, WheelsVector};
+use crate::error::ParseError;
+use byteorder::WriteBytesExt;
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 2021 car damage, it lacks the ERS fault and whether the engine blew up or seized.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct CarDamage {
+    pub tyre_wear: WheelsVector<f32>,
+    pub tyre_damage: WheelsVector<u8>,
+    pub brakes_damage: WheelsVector<u8>,
+    pub front_left_wing_damage: u8,
+    pub front_right_wing_damage: u8,
+    pub rear_wing_damage: u8,
+    pub floor_damage: u8,
+    pub diffuser_damage: u8,
+    pub sidepod_damage: u8,
+    pub drs_fault: bool,
+    pub gear_box_damage: u8,
+    pub engine_damage: u8,
+    pub engine_mguh_wear: u8,
+    pub engine_es_wear: u8,
+    pub engine_ce_wear: u8,
+    pub engine_ice_wear: u8,
+    pub engine_mguk_wear: u8,
+    pub engine_tc_wear: u8,
+}
+
+impl CarDamage {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<CarDamage, ParseError> {
+        Ok(CarDamage {
+            tyre_wear: WheelsVector::<f32>::read_from(reader, "tyre_wear")?,
+            tyre_damage: WheelsVector::<u8>::read_from(reader, "tyre_damage")?,
+            brakes_damage: WheelsVector::<u8>::read_from(reader, "brakes_damage")?,
+            front_left_wing_damage: reader.read_u8("front_left_wing_damage")?,
+            front_right_wing_damage: reader.read_u8("front_right_wing_damage")?,
+            rear_wing_damage: reader.read_u8("rear_wing_damage")?,
+            floor_damage: reader.read_u8("floor_damage")?,
+            diffuser_damage: reader.read_u8("diffuser_damage")?,
+            sidepod_damage: reader.read_u8("sidepod_damage")?,
+            drs_fault: reader.read_bool("drs_fault")?,
+            gear_box_damage: reader.read_u8("gear_box_damage")?,
+            engine_damage: reader.read_u8("engine_damage")?,
+            engine_mguh_wear: reader.read_u8("engine_mguh_wear")?,
+            engine_es_wear: reader.read_u8("engine_es_wear")?,
+            engine_ce_wear: reader.read_u8("engine_ce_wear")?,
+            engine_ice_wear: reader.read_u8("engine_ice_wear")?,
+            engine_mguk_wear: reader.read_u8("engine_mguk_wear")?,
+            engine_tc_wear: reader.read_u8("engine_tc_wear")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.tyre_wear.write_to(writer)?;
+        self.tyre_damage.write_to(writer)?;
+        self.brakes_damage.write_to(writer)?;
+        writer.write_u8(self.front_left_wing_damage)?;
+        writer.write_u8(self.front_right_wing_damage)?;
+        writer.write_u8(self.rear_wing_damage)?;
+        writer.write_u8(self.floor_damage)?;
+        writer.write_u8(self.diffuser_damage)?;
+        writer.write_u8(self.sidepod_damage)?;
+        writer.write_u8(self.drs_fault as u8)?;
+        writer.write_u8(self.gear_box_damage)?;
+        writer.write_u8(self.engine_damage)?;
+        writer.write_u8(self.engine_mguh_wear)?;
+        writer.write_u8(self.engine_es_wear)?;
+        writer.write_u8(self.engine_ce_wear)?;
+        writer.write_u8(self.engine_ice_wear)?;
+        writer.write_u8(self.engine_mguk_wear)?;
+        writer.write_u8(self.engine_tc_wear)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketCarDamage {
+    pub header: PacketHeader,
+    pub car_damage_data: Vec<CarDamage>,
+}
+
+impl Packet for PacketCarDamage {
+    const PACKET_ID: u8 = 10;
+    const PACKET_SIZE: usize = 882;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketCarDamage, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let mut car_damage_data = Vec::new();
+        for _ in 0..22 {
+            car_damage_data.push(CarDamage::new(reader)?);
+        }
+        Ok(PacketCarDamage {
+            header,
+            car_damage_data,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for car_damage in &self.car_damage_data {
+            car_damage.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
+/// The F1 2022 car damage, F1 2021 doesn't report ERS faults or blown and seized engines.
+impl From<&CarDamage> for crate::models::CarDamage {
+    fn from(data: &CarDamage) -> crate::models::CarDamage {
+        crate::models::CarDamage {
+            tyre_wear: data.tyre_wear,
+            tyre_damage: data.tyre_damage,
+            brakes_damage: data.brakes_damage,
+            front_left_wing_damage: data.front_left_wing_damage,
+            front_right_wing_damage: data.front_right_wing_damage,
+            rear_wing_damage: data.rear_wing_damage,
+            floor_damage: data.floor_damage,
+            diffuser_damage: data.diffuser_damage,
+            sidepod_damage: data.sidepod_damage,
+            drs_fault: data.drs_fault,
+            ers_fault: false,
+            gear_box_damage: data.gear_box_damage,
+            engine_damage: data.engine_damage,
+            engine_mguh_wear: data.engine_mguh_wear,
+            engine_es_wear: data.engine_es_wear,
+            engine_ce_wear: data.engine_ce_wear,
+            engine_ice_wear: data.engine_ice_wear,
+            engine_mguk_wear: data.engine_mguk_wear,
+            engine_tc_wear: data.engine_tc_wear,
+            engine_blown: false,
+            engine_seized: false,
+        }
+    }
+}
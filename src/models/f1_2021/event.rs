@@ -0,0 +1,107 @@
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 2021 speed trap, without the fastest speed of the session.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct SpeedTrap {
+    pub vehicle_index: u8,
+    pub speed: f32,
+    pub is_overall_fastest_in_session: bool,
+    pub is_driver_fastest_in_session: bool,
+}
+
+impl SpeedTrap {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<SpeedTrap, ParseError> {
+        Ok(SpeedTrap {
+            vehicle_index: reader.read_u8("vehicle_index")?,
+            speed: reader.read_f32("speed")?,
+            is_overall_fastest_in_session: reader.read_bool("is_overall_fastest_in_session")?,
+            is_driver_fastest_in_session: reader.read_bool("is_driver_fastest_in_session")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.vehicle_index)?;
+        writer.write_f32::<LittleEndian>(self.speed)?;
+        writer.write_u8(self.is_overall_fastest_in_session as u8)?;
+        writer.write_u8(self.is_driver_fastest_in_session as u8)?;
+
+        Ok(())
+    }
+}
+
+/// All F1 2021 events share their layout with F1 2022, except for the speed trap.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum EventDetails {
+    SpeedTrap(SpeedTrap),
+    Other(crate::models::EventDetails),
+}
+
+impl EventDetails {
+    /// Reads the details belonging to the event code `value`, returns `None` for unknown codes.
+    pub fn from_str<R: Read>(value: &str, reader: &mut PacketReader<R>) -> Result<Option<Self>, ParseError> {
+        match value {
+            "SPTP" => Ok(Some(EventDetails::SpeedTrap(SpeedTrap::new(reader)?))),
+            _ => Ok(crate::models::EventDetails::from_str(value, reader)?.map(EventDetails::Other)),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EventDetails::SpeedTrap(_) => "SPTP",
+            EventDetails::Other(details) => details.code(),
+        }
+    }
+
+    /// Writes the details without the event code.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            EventDetails::SpeedTrap(details) => details.write_to(writer),
+            EventDetails::Other(details) => details.write_to(writer),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketEventData {
+    pub header: PacketHeader,
+    pub event_string_code: [u8; 4],
+    pub event_details: Option<EventDetails>,
+}
+
+impl Packet for PacketEventData {
+    const PACKET_ID: u8 = 3;
+    const PACKET_SIZE: usize = 36;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketEventData, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let event_string_code = reader.read_bytes::<4>("event_string_code")?;
+        let event_details = EventDetails::from_str(
+            &String::from_utf8_lossy(&event_string_code),
+            reader,
+        )?;
+
+        Ok(PacketEventData {
+            header,
+            event_string_code,
+            event_details,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_all(&self.event_string_code)?;
+
+        // The details are a union of 8 bytes, unused bytes are sent as zeros.
+        let mut details = Vec::with_capacity(8);
+        if let Some(event_details) = &self.event_details {
+            event_details.write_to(&mut details)?;
+        }
+        details.resize(8, 0);
+        writer.write_all(&details)
+    }
+}
@@ -0,0 +1,38 @@
+use std::io::{self, Read, Write};
+use crate::models::{LapData, PacketHeader, PacketReader};
+use crate::error::ParseError;
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 2021 lap data packet, the entries match F1 2022 but the time trial indices are missing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketLapData {
+    pub header: PacketHeader,
+    pub lap_data: Vec<LapData>,
+}
+
+impl Packet for PacketLapData {
+    const PACKET_ID: u8 = 2;
+    const PACKET_SIZE: usize = 970;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketLapData, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let mut lap_data = vec![];
+        for _ in 0..22 {
+            lap_data.push(LapData::new(reader)?);
+        }
+        Ok(PacketLapData {
+            header,
+            lap_data,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for lap_data in &self.lap_data {
+            lap_data.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+//! Packets of the F1 2021 format that differ from F1 2022.
+//!
+//! The motion, participants, car setups, car telemetry, car status, lobby info and session
+//! history packets of F1 2021 share their layout with F1 2022 and decode into the same types.
+
+mod session;
+mod lap_data;
+mod event;
+mod final_classification;
+mod car_damage;
+
+pub use session::*;
+pub use lap_data::*;
+pub use event::*;
+pub use final_classification::*;
+pub use car_damage::*;
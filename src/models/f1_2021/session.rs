@@ -0,0 +1,275 @@
+use std::io::{self, Read, Write};
+use crate::models::enums::{DynamicRacingLine, DynamicRacingLineType, GameEnum, GameMode, GearboxAssist, NetworkGame, RuleSet, SafetyCarStatus, SessionLength, SessionType, Track};
+use crate::models::{MarshalZone, PacketHeader, PacketReader, WeatherForecastSample};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// F1 2021 has no third race, its time trial uses the id of the 2022 third race.
+fn from_2021(session_type: SessionType) -> SessionType {
+    match session_type {
+        SessionType::R3 => SessionType::TimeTrial,
+        SessionType::TimeTrial => SessionType::Unknown(SessionType::TimeTrial.value()),
+        session_type => session_type,
+    }
+}
+
+fn to_2021(session_type: SessionType) -> SessionType {
+    match session_type {
+        SessionType::TimeTrial => SessionType::R3,
+        session_type => session_type,
+    }
+}
+
+/// The F1 2021 session packet, it lacks the game mode, rule set, time of day and session length.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionDataPacket {
+    pub header: PacketHeader,
+    pub weather: u8,
+    pub track_temperature: i8,
+    pub air_temperature: i8,
+    pub total_laps: u8,
+    pub track_length: u16,
+    pub session_type: SessionType,
+
+    pub track_id: i8,
+    pub formula: u8,
+
+    pub session_time_left: u16,
+    pub session_duration: u16,
+    pub pit_speed_limit: u8,
+    pub game_paused: u8,
+    pub is_spectating: u8,
+    pub spectator_car_index: u8,
+    pub sli_pro_native_support: u8,
+    pub num_marshal_zones: u8,
+    pub marshal_zones: Vec<MarshalZone>,
+    pub safety_car_status: SafetyCarStatus,
+    pub network_game: NetworkGame,
+    pub num_weather_forecast_samples: u8,
+    pub weather_forecast_samples: Vec<WeatherForecastSample>,
+    pub forecast_accuracy: u8,
+    pub ai_difficulty: u8,
+    pub season_link_identifier: u32,
+    pub weekend_link_identifier: u32,
+    pub session_link_identifier: u32,
+    pub pit_stop_window_ideal_lap: u8,
+    pub pit_stop_window_latest_lap: u8,
+    pub pit_stop_rejoin_position: u8,
+    pub steering_assist: bool,
+    pub braking_assist: bool,
+    pub gearbox_assist: GearboxAssist,
+    pub pit_assist: bool,
+    pub pit_release_assist: bool,
+    pub ers_assist: bool,
+    pub drs_assist: bool,
+    pub dynamic_racing_line: DynamicRacingLine,
+    pub dynamic_racing_line_type: DynamicRacingLineType,
+}
+
+impl SessionDataPacket {
+    pub fn track(&self) -> Track {
+        Track::from_value(self.track_id).unwrap_or(Track::Unknown(self.track_id))
+    }
+}
+
+impl Packet for SessionDataPacket {
+    const PACKET_ID: u8 = 1;
+    const PACKET_SIZE: usize = 625;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<SessionDataPacket, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let weather = reader.read_u8("weather")?;
+        let track_temperature = reader.read_i8("track_temperature")?;
+        let air_temperature = reader.read_i8("air_temperature")?;
+        let total_laps = reader.read_u8("total_laps")?;
+        let track_length = reader.read_u16("track_length")?;
+        let session_type = from_2021(reader.read_enum("session_type")?);
+        let track_id = reader.read_i8("track_id")?;
+        let formula = reader.read_u8("formula")?;
+        let session_time_left = reader.read_u16("session_time_left")?;
+        let session_duration = reader.read_u16("session_duration")?;
+        let pit_speed_limit = reader.read_u8("pit_speed_limit")?;
+        let game_paused = reader.read_u8("game_paused")?;
+        let is_spectating = reader.read_u8("is_spectating")?;
+        let spectator_car_index = reader.read_u8("spectator_car_index")?;
+        let sli_pro_native_support = reader.read_u8("sli_pro_native_support")?;
+        let num_marshal_zones = reader.read_u8("num_marshal_zones")?;
+        let mut marshal_zones = Vec::new();
+        for _ in 0..21 {
+            marshal_zones.push(MarshalZone::new(reader)?);
+        }
+        let safety_car_status = reader.read_enum("safety_car_status")?;
+        let network_game = reader.read_enum("network_game")?;
+        let num_weather_forecast_samples = reader.read_u8("num_weather_forecast_samples")?;
+        let mut weather_forecast_samples = Vec::new();
+        for _ in 0..56 {
+            let mut sample = WeatherForecastSample::new(reader)?;
+            sample.session_type = from_2021(sample.session_type);
+            weather_forecast_samples.push(sample);
+        }
+        let forecast_accuracy = reader.read_u8("forecast_accuracy")?;
+        let ai_difficulty = reader.read_u8("ai_difficulty")?;
+        let season_link_identifier = reader.read_u32("season_link_identifier")?;
+        let weekend_link_identifier = reader.read_u32("weekend_link_identifier")?;
+        let session_link_identifier = reader.read_u32("session_link_identifier")?;
+        let pit_stop_window_ideal_lap = reader.read_u8("pit_stop_window_ideal_lap")?;
+        let pit_stop_window_latest_lap = reader.read_u8("pit_stop_window_latest_lap")?;
+        let pit_stop_rejoin_position = reader.read_u8("pit_stop_rejoin_position")?;
+        let steering_assist = reader.read_bool("steering_assist")?;
+        let braking_assist = reader.read_bool("braking_assist")?;
+        let gearbox_assist = reader.read_enum("gearbox_assist")?;
+        let pit_assist = reader.read_bool("pit_assist")?;
+        let pit_release_assist = reader.read_bool("pit_release_assist")?;
+        let ers_assist = reader.read_bool("ers_assist")?;
+        let drs_assist = reader.read_bool("drs_assist")?;
+        let dynamic_racing_line = reader.read_enum("dynamic_racing_line")?;
+        let dynamic_racing_line_type = reader.read_enum("dynamic_racing_line_type")?;
+
+        Ok(SessionDataPacket {
+            header,
+            weather,
+            track_temperature,
+            air_temperature,
+            total_laps,
+            track_length,
+            session_type,
+            track_id,
+            formula,
+            session_time_left,
+            session_duration,
+            pit_speed_limit,
+            game_paused,
+            is_spectating,
+            spectator_car_index,
+            sli_pro_native_support,
+            num_marshal_zones,
+            marshal_zones,
+            safety_car_status,
+            network_game,
+            num_weather_forecast_samples,
+            weather_forecast_samples,
+            forecast_accuracy,
+            ai_difficulty,
+            season_link_identifier,
+            weekend_link_identifier,
+            session_link_identifier,
+            pit_stop_window_ideal_lap,
+            pit_stop_window_latest_lap,
+            pit_stop_rejoin_position,
+            steering_assist,
+            braking_assist,
+            gearbox_assist,
+            pit_assist,
+            pit_release_assist,
+            ers_assist,
+            drs_assist,
+            dynamic_racing_line,
+            dynamic_racing_line_type,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.weather)?;
+        writer.write_i8(self.track_temperature)?;
+        writer.write_i8(self.air_temperature)?;
+        writer.write_u8(self.total_laps)?;
+        writer.write_u16::<LittleEndian>(self.track_length)?;
+        writer.write_u8(to_2021(self.session_type).to_raw())?;
+        writer.write_i8(self.track_id)?;
+        writer.write_u8(self.formula)?;
+        writer.write_u16::<LittleEndian>(self.session_time_left)?;
+        writer.write_u16::<LittleEndian>(self.session_duration)?;
+        writer.write_u8(self.pit_speed_limit)?;
+        writer.write_u8(self.game_paused)?;
+        writer.write_u8(self.is_spectating)?;
+        writer.write_u8(self.spectator_car_index)?;
+        writer.write_u8(self.sli_pro_native_support)?;
+        writer.write_u8(self.num_marshal_zones)?;
+        for marshal_zone in &self.marshal_zones {
+            marshal_zone.write_to(writer)?;
+        }
+        writer.write_u8(self.safety_car_status.to_raw())?;
+        writer.write_u8(self.network_game.to_raw())?;
+        writer.write_u8(self.num_weather_forecast_samples)?;
+        for sample in &self.weather_forecast_samples {
+            WeatherForecastSample {
+                session_type: to_2021(sample.session_type),
+                ..sample.clone()
+            }.write_to(writer)?;
+        }
+        writer.write_u8(self.forecast_accuracy)?;
+        writer.write_u8(self.ai_difficulty)?;
+        writer.write_u32::<LittleEndian>(self.season_link_identifier)?;
+        writer.write_u32::<LittleEndian>(self.weekend_link_identifier)?;
+        writer.write_u32::<LittleEndian>(self.session_link_identifier)?;
+        writer.write_u8(self.pit_stop_window_ideal_lap)?;
+        writer.write_u8(self.pit_stop_window_latest_lap)?;
+        writer.write_u8(self.pit_stop_rejoin_position)?;
+        writer.write_u8(self.steering_assist as u8)?;
+        writer.write_u8(self.braking_assist as u8)?;
+        writer.write_u8(self.gearbox_assist.to_raw())?;
+        writer.write_u8(self.pit_assist as u8)?;
+        writer.write_u8(self.pit_release_assist as u8)?;
+        writer.write_u8(self.ers_assist as u8)?;
+        writer.write_u8(self.drs_assist as u8)?;
+        writer.write_u8(self.dynamic_racing_line.to_raw())?;
+        writer.write_u8(self.dynamic_racing_line_type.to_raw())?;
+
+        Ok(())
+    }
+}
+
+/// The F1 2022 session, F1 2021 doesn't send the game mode, rule set, time of day and session
+/// length so they are left unknown and zero.
+impl From<&SessionDataPacket> for crate::models::SessionDataPacket {
+    fn from(packet: &SessionDataPacket) -> crate::models::SessionDataPacket {
+        crate::models::SessionDataPacket {
+            header: packet.header,
+            weather: packet.weather,
+            track_temperature: packet.track_temperature,
+            air_temperature: packet.air_temperature,
+            total_laps: packet.total_laps,
+            track_length: packet.track_length,
+            session_type: packet.session_type,
+            track_id: packet.track_id,
+            formula: packet.formula,
+            session_time_left: packet.session_time_left,
+            session_duration: packet.session_duration,
+            pit_speed_limit: packet.pit_speed_limit,
+            game_paused: packet.game_paused,
+            is_spectating: packet.is_spectating,
+            spectator_car_index: packet.spectator_car_index,
+            sli_pro_native_support: packet.sli_pro_native_support,
+            num_marshal_zones: packet.num_marshal_zones,
+            marshal_zones: packet.marshal_zones.clone(),
+            safety_car_status: packet.safety_car_status,
+            network_game: packet.network_game,
+            num_weather_forecast_samples: packet.num_weather_forecast_samples,
+            weather_forecast_samples: packet.weather_forecast_samples.clone(),
+            forecast_accuracy: packet.forecast_accuracy,
+            ai_difficulty: packet.ai_difficulty,
+            season_link_identifier: packet.season_link_identifier,
+            weekend_link_identifier: packet.weekend_link_identifier,
+            session_link_identifier: packet.session_link_identifier,
+            pit_stop_window_ideal_lap: packet.pit_stop_window_ideal_lap,
+            pit_stop_window_latest_lap: packet.pit_stop_window_latest_lap,
+            pit_stop_rejoin_position: packet.pit_stop_rejoin_position,
+            steering_assist: packet.steering_assist,
+            braking_assist: packet.braking_assist,
+            gearbox_assist: packet.gearbox_assist,
+            pit_assist: packet.pit_assist,
+            pit_release_assist: packet.pit_release_assist,
+            ers_assist: packet.ers_assist,
+            drs_assist: packet.drs_assist,
+            dynamic_racing_line: packet.dynamic_racing_line,
+            dynamic_racing_line_type: packet.dynamic_racing_line_type,
+            game_mode: GameMode::unknown(0),
+            rule_set: RuleSet::unknown(0),
+            time_of_day: 0,
+            session_length: SessionLength::unknown(0),
+        }
+    }
+}
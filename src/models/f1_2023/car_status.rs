@@ -0,0 +1,162 @@
+use std::io::{self, Read, Write};
+use crate::models::enums::{ActualTyreCompound, ErsMode, FuelMix, GameEnum, TractionControlStatus, VehicleFiaFlags, VisualTyreCompound};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 23 car status, with the power of the engine and the MGU-K.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct CarStatusData {
+    pub traction_control: TractionControlStatus,
+    pub anti_lock_brakes: bool,
+    pub fuel_mix: FuelMix,
+    pub front_brake_bias: u8,
+    pub pit_limiter_status: bool,
+    pub fuel_in_tank: f32,
+    pub fuel_capacity: f32,
+    pub fuel_remaining_laps: f32,
+    pub max_rpm: u16,
+    pub idle_rpm: u16,
+    pub max_gears: u8,
+    pub drs_allowed: bool,
+    pub drs_activation_distance: u16,
+    pub actual_tyre_compound: ActualTyreCompound,
+    pub visual_tyre_compound: VisualTyreCompound,
+    pub tyres_age_laps: u8,
+    pub vehicle_fia_flags: VehicleFiaFlags,
+    pub engine_power_ice: f32,
+    pub engine_power_mguk: f32,
+    pub ers_store_energy: f32,
+    pub ers_deploy_mode: ErsMode,
+    pub ers_harvested_this_lap_mguk: f32,
+    pub ers_harvested_this_lap_mguh: f32,
+    pub ers_deployed_this_lap: f32,
+    pub network_paused: bool,
+}
+
+impl CarStatusData {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<CarStatusData, ParseError> {
+        Ok(CarStatusData {
+            traction_control: reader.read_enum("traction_control")?,
+            anti_lock_brakes: reader.read_bool("anti_lock_brakes")?,
+            fuel_mix: reader.read_enum("fuel_mix")?,
+            front_brake_bias: reader.read_u8("front_brake_bias")?,
+            pit_limiter_status: reader.read_bool("pit_limiter_status")?,
+            fuel_in_tank: reader.read_f32("fuel_in_tank")?,
+            fuel_capacity: reader.read_f32("fuel_capacity")?,
+            fuel_remaining_laps: reader.read_f32("fuel_remaining_laps")?,
+            max_rpm: reader.read_u16("max_rpm")?,
+            idle_rpm: reader.read_u16("idle_rpm")?,
+            max_gears: reader.read_u8("max_gears")?,
+            drs_allowed: reader.read_bool("drs_allowed")?,
+            drs_activation_distance: reader.read_u16("drs_activation_distance")?,
+            actual_tyre_compound: reader.read_enum("actual_tyre_compound")?,
+            visual_tyre_compound: reader.read_enum("visual_tyre_compound")?,
+            tyres_age_laps: reader.read_u8("tyres_age_laps")?,
+            vehicle_fia_flags: reader.read_enum("vehicle_fia_flags")?,
+            engine_power_ice: reader.read_f32("engine_power_ice")?,
+            engine_power_mguk: reader.read_f32("engine_power_mguk")?,
+            ers_store_energy: reader.read_f32("ers_store_energy")?,
+            ers_deploy_mode: reader.read_enum("ers_deploy_mode")?,
+            ers_harvested_this_lap_mguk: reader.read_f32("ers_harvested_this_lap_mguk")?,
+            ers_harvested_this_lap_mguh: reader.read_f32("ers_harvested_this_lap_mguh")?,
+            ers_deployed_this_lap: reader.read_f32("ers_deployed_this_lap")?,
+            network_paused: reader.read_bool("network_paused")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.traction_control.to_raw())?;
+        writer.write_u8(self.anti_lock_brakes as u8)?;
+        writer.write_u8(self.fuel_mix.to_raw())?;
+        writer.write_u8(self.front_brake_bias)?;
+        writer.write_u8(self.pit_limiter_status as u8)?;
+        writer.write_f32::<LittleEndian>(self.fuel_in_tank)?;
+        writer.write_f32::<LittleEndian>(self.fuel_capacity)?;
+        writer.write_f32::<LittleEndian>(self.fuel_remaining_laps)?;
+        writer.write_u16::<LittleEndian>(self.max_rpm)?;
+        writer.write_u16::<LittleEndian>(self.idle_rpm)?;
+        writer.write_u8(self.max_gears)?;
+        writer.write_u8(self.drs_allowed as u8)?;
+        writer.write_u16::<LittleEndian>(self.drs_activation_distance)?;
+        writer.write_u8(self.actual_tyre_compound.to_raw())?;
+        writer.write_u8(self.visual_tyre_compound.to_raw())?;
+        writer.write_u8(self.tyres_age_laps)?;
+        writer.write_u8(self.vehicle_fia_flags.to_raw())?;
+        writer.write_f32::<LittleEndian>(self.engine_power_ice)?;
+        writer.write_f32::<LittleEndian>(self.engine_power_mguk)?;
+        writer.write_f32::<LittleEndian>(self.ers_store_energy)?;
+        writer.write_u8(self.ers_deploy_mode.to_raw())?;
+        writer.write_f32::<LittleEndian>(self.ers_harvested_this_lap_mguk)?;
+        writer.write_f32::<LittleEndian>(self.ers_harvested_this_lap_mguh)?;
+        writer.write_f32::<LittleEndian>(self.ers_deployed_this_lap)?;
+        writer.write_u8(self.network_paused as u8)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketCarStatus {
+    pub header: PacketHeader,
+    pub car_status_data: Vec<CarStatusData>,
+}
+
+impl Packet for PacketCarStatus {
+    const PACKET_ID: u8 = 7;
+    const PACKET_SIZE: usize = 1239;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketCarStatus, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let mut car_status_data = Vec::new();
+        for _ in 0..22 {
+            car_status_data.push(CarStatusData::new(reader)?);
+        }
+        Ok(PacketCarStatus {
+            header,
+            car_status_data,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for car_status in &self.car_status_data {
+            car_status.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The F1 2022 car status, without the engine power.
+impl From<&CarStatusData> for crate::models::CarStatusData {
+    fn from(data: &CarStatusData) -> crate::models::CarStatusData {
+        crate::models::CarStatusData {
+            traction_control: data.traction_control,
+            anti_lock_brakes: data.anti_lock_brakes,
+            fuel_mix: data.fuel_mix,
+            front_brake_bias: data.front_brake_bias,
+            pit_limiter_status: data.pit_limiter_status,
+            fuel_in_tank: data.fuel_in_tank,
+            fuel_capacity: data.fuel_capacity,
+            fuel_remaining_laps: data.fuel_remaining_laps,
+            max_rpm: data.max_rpm,
+            idle_rpm: data.idle_rpm,
+            max_gears: data.max_gears,
+            drs_allowed: data.drs_allowed,
+            drs_activation_distance: data.drs_activation_distance,
+            actual_tyre_compound: data.actual_tyre_compound,
+            visual_tyre_compound: data.visual_tyre_compound,
+            tyres_age_laps: data.tyres_age_laps,
+            vehicle_fia_flags: data.vehicle_fia_flags,
+            ers_store_energy: data.ers_store_energy,
+            ers_deploy_mode: data.ers_deploy_mode,
+            ers_harvested_this_lap_mguk: data.ers_harvested_this_lap_mguk,
+            ers_harvested_this_lap_mguh: data.ers_harvested_this_lap_mguh,
+            ers_deployed_this_lap: data.ers_deployed_this_lap,
+            network_paused: data.network_paused,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::WriteBytesExt;
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Overtake {
+    pub overtaking_vehicle_index: u8,
+    pub being_overtaken_vehicle_index: u8,
+}
+
+impl Overtake {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<Overtake, ParseError> {
+        Ok(Overtake {
+            overtaking_vehicle_index: reader.read_u8("overtaking_vehicle_index")?,
+            being_overtaken_vehicle_index: reader.read_u8("being_overtaken_vehicle_index")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.overtaking_vehicle_index)?;
+        writer.write_u8(self.being_overtaken_vehicle_index)?;
+
+        Ok(())
+    }
+}
+
+/// F1 23 keeps the F1 2022 events and adds red flags and overtakes.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum EventDetails {
+    RedFlag,
+    Overtake(Overtake),
+    Other(crate::models::EventDetails),
+}
+
+impl EventDetails {
+    /// Reads the details belonging to the event code `value`, returns `None` for unknown codes.
+    pub fn from_str<R: Read>(value: &str, reader: &mut PacketReader<R>) -> Result<Option<Self>, ParseError> {
+        match value {
+            "RDFL" => Ok(Some(EventDetails::RedFlag)),
+            "OVTK" => Ok(Some(EventDetails::Overtake(Overtake::new(reader)?))),
+            _ => Ok(crate::models::EventDetails::from_str(value, reader)?.map(EventDetails::Other)),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EventDetails::RedFlag => "RDFL",
+            EventDetails::Overtake(_) => "OVTK",
+            EventDetails::Other(details) => details.code(),
+        }
+    }
+
+    /// Writes the details without the event code.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            EventDetails::RedFlag => Ok(()),
+            EventDetails::Overtake(details) => details.write_to(writer),
+            EventDetails::Other(details) => details.write_to(writer),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketEventData {
+    pub header: PacketHeader,
+    pub event_string_code: [u8; 4],
+    pub event_details: Option<EventDetails>,
+}
+
+impl Packet for PacketEventData {
+    const PACKET_ID: u8 = 3;
+    const PACKET_SIZE: usize = 45;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketEventData, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let event_string_code = reader.read_bytes::<4>("event_string_code")?;
+        let event_details = EventDetails::from_str(
+            &String::from_utf8_lossy(&event_string_code),
+            reader,
+        )?;
+
+        Ok(PacketEventData {
+            header,
+            event_string_code,
+            event_details,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_all(&self.event_string_code)?;
+
+        // The details are a union of 12 bytes, unused bytes are sent as zeros.
+        let mut details = Vec::with_capacity(12);
+        if let Some(event_details) = &self.event_details {
+            event_details.write_to(&mut details)?;
+        }
+        details.resize(12, 0);
+        writer.write_all(&details)
+    }
+}
@@ -0,0 +1,195 @@
+use std::io::{self, Read, Write};
+use crate::models::enums::{DriverStatus, GameEnum, PitStatus, ResultStatus, Sector};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 23 lap data, sector times are split in minutes and milliseconds and the deltas to the
+/// car in front and the leader are added.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct LapData {
+    pub last_lap_time: u32,
+    pub current_lap_time: u32,
+    pub sector1_time_ms_part: u16,
+    pub sector1_time_minutes: u8,
+    pub sector2_time_ms_part: u16,
+    pub sector2_time_minutes: u8,
+    pub delta_to_car_in_front: u16,
+    pub delta_to_race_leader: u16,
+    pub lap_distance: f32,
+    pub total_distance: f32,
+    pub safety_car_delta: f32,
+    pub car_position: u8,
+    pub current_lap_num: u8,
+    pub pit_status: PitStatus,
+    pub num_pit_stops: u8,
+    pub sector: Sector,
+    pub current_lap_invalid: bool,
+    pub penalties: u8,
+    /// All warnings, corner cutting included.
+    pub warnings: u8,
+    pub corner_cutting_warnings: u8,
+    pub num_unserved_drive_through_penalties: u8,
+    pub num_unserved_stop_go_penalties: u8,
+    pub grid_position: u8,
+    pub driver_status: DriverStatus,
+    pub result_status: ResultStatus,
+    pub pit_lane_timer_active: bool,
+    pub pit_lane_time_in_lane: u16,
+    pub pit_stop_timer: u16,
+    pub pit_stop_should_serve_penalty: bool,
+}
+
+impl LapData {
+    /// Time of the first sector in milliseconds.
+    pub fn sector1_time(&self) -> u32 {
+        self.sector1_time_minutes as u32 * 60_000 + self.sector1_time_ms_part as u32
+    }
+
+    /// Time of the second sector in milliseconds.
+    pub fn sector2_time(&self) -> u32 {
+        self.sector2_time_minutes as u32 * 60_000 + self.sector2_time_ms_part as u32
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<LapData, ParseError> {
+        Ok(LapData {
+            last_lap_time: reader.read_u32("last_lap_time")?,
+            current_lap_time: reader.read_u32("current_lap_time")?,
+            sector1_time_ms_part: reader.read_u16("sector1_time_ms_part")?,
+            sector1_time_minutes: reader.read_u8("sector1_time_minutes")?,
+            sector2_time_ms_part: reader.read_u16("sector2_time_ms_part")?,
+            sector2_time_minutes: reader.read_u8("sector2_time_minutes")?,
+            delta_to_car_in_front: reader.read_u16("delta_to_car_in_front")?,
+            delta_to_race_leader: reader.read_u16("delta_to_race_leader")?,
+            lap_distance: reader.read_f32("lap_distance")?,
+            total_distance: reader.read_f32("total_distance")?,
+            safety_car_delta: reader.read_f32("safety_car_delta")?,
+            car_position: reader.read_u8("car_position")?,
+            current_lap_num: reader.read_u8("current_lap_num")?,
+            pit_status: reader.read_enum("pit_status")?,
+            num_pit_stops: reader.read_u8("num_pit_stops")?,
+            sector: reader.read_enum("sector")?,
+            current_lap_invalid: reader.read_bool("current_lap_invalid")?,
+            penalties: reader.read_u8("penalties")?,
+            warnings: reader.read_u8("warnings")?,
+            corner_cutting_warnings: reader.read_u8("corner_cutting_warnings")?,
+            num_unserved_drive_through_penalties: reader.read_u8("num_unserved_drive_through_penalties")?,
+            num_unserved_stop_go_penalties: reader.read_u8("num_unserved_stop_go_penalties")?,
+            grid_position: reader.read_u8("grid_position")?,
+            driver_status: reader.read_enum("driver_status")?,
+            result_status: reader.read_enum("result_status")?,
+            pit_lane_timer_active: reader.read_bool("pit_lane_timer_active")?,
+            pit_lane_time_in_lane: reader.read_u16("pit_lane_time_in_lane")?,
+            pit_stop_timer: reader.read_u16("pit_stop_timer")?,
+            pit_stop_should_serve_penalty: reader.read_bool("pit_stop_should_serve_penalty")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u32::<LittleEndian>(self.last_lap_time)?;
+        writer.write_u32::<LittleEndian>(self.current_lap_time)?;
+        writer.write_u16::<LittleEndian>(self.sector1_time_ms_part)?;
+        writer.write_u8(self.sector1_time_minutes)?;
+        writer.write_u16::<LittleEndian>(self.sector2_time_ms_part)?;
+        writer.write_u8(self.sector2_time_minutes)?;
+        writer.write_u16::<LittleEndian>(self.delta_to_car_in_front)?;
+        writer.write_u16::<LittleEndian>(self.delta_to_race_leader)?;
+        writer.write_f32::<LittleEndian>(self.lap_distance)?;
+        writer.write_f32::<LittleEndian>(self.total_distance)?;
+        writer.write_f32::<LittleEndian>(self.safety_car_delta)?;
+        writer.write_u8(self.car_position)?;
+        writer.write_u8(self.current_lap_num)?;
+        writer.write_u8(self.pit_status.to_raw())?;
+        writer.write_u8(self.num_pit_stops)?;
+        writer.write_u8(self.sector.to_raw())?;
+        writer.write_u8(self.current_lap_invalid as u8)?;
+        writer.write_u8(self.penalties)?;
+        writer.write_u8(self.warnings)?;
+        writer.write_u8(self.corner_cutting_warnings)?;
+        writer.write_u8(self.num_unserved_drive_through_penalties)?;
+        writer.write_u8(self.num_unserved_stop_go_penalties)?;
+        writer.write_u8(self.grid_position)?;
+        writer.write_u8(self.driver_status.to_raw())?;
+        writer.write_u8(self.result_status.to_raw())?;
+        writer.write_u8(self.pit_lane_timer_active as u8)?;
+        writer.write_u16::<LittleEndian>(self.pit_lane_time_in_lane)?;
+        writer.write_u16::<LittleEndian>(self.pit_stop_timer)?;
+        writer.write_u8(self.pit_stop_should_serve_penalty as u8)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketLapData {
+    pub header: PacketHeader,
+    pub lap_data: Vec<LapData>,
+    pub time_trial_pb_car_idx: u8,
+    pub time_trial_rival_car_idx: u8,
+}
+
+impl Packet for PacketLapData {
+    const PACKET_ID: u8 = 2;
+    const PACKET_SIZE: usize = 1131;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketLapData, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let mut lap_data = vec![];
+        for _ in 0..22 {
+            lap_data.push(LapData::new(reader)?);
+        }
+        let time_trial_pb_car_idx = reader.read_u8("time_trial_pb_car_idx")?;
+        let time_trial_rival_car_idx = reader.read_u8("time_trial_rival_car_idx")?;
+        Ok(PacketLapData {
+            header,
+            lap_data,
+            time_trial_pb_car_idx,
+            time_trial_rival_car_idx,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for lap_data in &self.lap_data {
+            lap_data.write_to(writer)?;
+        }
+        writer.write_u8(self.time_trial_pb_car_idx)?;
+        writer.write_u8(self.time_trial_rival_car_idx)?;
+
+        Ok(())
+    }
+}
+
+/// The F1 2022 lap data, sector times too long for it are capped.
+impl From<&LapData> for crate::models::LapData {
+    fn from(data: &LapData) -> crate::models::LapData {
+        crate::models::LapData {
+            last_lap_time: data.last_lap_time,
+            current_lap_time: data.current_lap_time,
+            sector1_time: data.sector1_time().min(u16::MAX as u32) as u16,
+            sector2_time: data.sector2_time().min(u16::MAX as u32) as u16,
+            lap_distance: data.lap_distance,
+            total_distance: data.total_distance,
+            safety_car_delta: data.safety_car_delta,
+            car_position: data.car_position,
+            current_lap_num: data.current_lap_num,
+            pit_status: data.pit_status,
+            num_pit_stops: data.num_pit_stops,
+            sector: data.sector,
+            current_lap_invalid: data.current_lap_invalid,
+            penalties: data.penalties,
+            warnings: data.warnings,
+            num_unserved_drive_through_penalties: data.num_unserved_drive_through_penalties,
+            num_unserved_stop_go_penalties: data.num_unserved_stop_go_penalties,
+            grid_position: data.grid_position,
+            driver_status: data.driver_status,
+            result_status: data.result_status,
+            pit_lane_timer_active: data.pit_lane_timer_active,
+            pit_lane_time_in_lane: data.pit_lane_time_in_lane,
+            pit_stop_timer: data.pit_stop_timer,
+            pit_stop_should_serve_penalty: data.pit_stop_should_serve_penalty,
+        }
+    }
+}
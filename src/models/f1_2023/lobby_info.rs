@@ -0,0 +1,86 @@
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader, write_string};
+use crate::error::ParseError;
+use byteorder::WriteBytesExt;
+use crate::models::enums::{GameEnum, Nationality, ReadyStatus, Team};
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 23 lobby entry, with the platform of the player.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LobbyInfo {
+    pub ai_controlled: bool,
+    pub team: Team,
+    pub nationality: u8,
+    /// 1 is Steam, 3 PlayStation, 4 Xbox, 6 Origin and 255 unknown.
+    pub platform: u8,
+    pub name: String,
+    pub car_number: u8,
+    pub ready_status: ReadyStatus,
+}
+
+impl LobbyInfo {
+    pub fn nationality(&self) -> Nationality {
+        Nationality::from_value(self.nationality).unwrap_or(Nationality::Unknown(self.nationality))
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<LobbyInfo, ParseError> {
+        Ok(LobbyInfo {
+            ai_controlled: reader.read_bool("ai_controlled")?,
+            team: reader.read_enum("team")?,
+            nationality: reader.read_u8("nationality")?,
+            platform: reader.read_u8("platform")?,
+            name: reader.read_string::<48>("name")?,
+            car_number: reader.read_u8("car_number")?,
+            ready_status: reader.read_enum("ready_status")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.ai_controlled as u8)?;
+        writer.write_u8(self.team.to_raw())?;
+        writer.write_u8(self.nationality)?;
+        writer.write_u8(self.platform)?;
+        write_string::<_, 48>(writer, &self.name)?;
+        writer.write_u8(self.car_number)?;
+        writer.write_u8(self.ready_status.to_raw())?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketLobbyInfo {
+    pub header: PacketHeader,
+    pub num_lobbies: u8,
+    pub lobbies: Vec<LobbyInfo>,
+}
+
+impl Packet for PacketLobbyInfo {
+    const PACKET_ID: u8 = 9;
+    const PACKET_SIZE: usize = 1218;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketLobbyInfo, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let num_lobbies = reader.read_u8("num_lobbies")?;
+        let mut lobbies = Vec::new();
+        for _ in 0..22 {
+            lobbies.push(LobbyInfo::new(reader)?);
+        }
+        Ok(PacketLobbyInfo {
+            header,
+            num_lobbies,
+            lobbies,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.num_lobbies)?;
+        for lobby in &self.lobbies {
+            lobby.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
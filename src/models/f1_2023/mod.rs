@@ -0,0 +1,26 @@
+//! Packets of the F1 23 format that differ from F1 2022.
+//!
+//! The F1 23 header is longer, [`crate::models::PacketHeader`] reads the extra fields according to
+//! the packet format. With that the car setups, car telemetry, final classification and car
+//! damage packets share their layout with F1 2022 and decode into the same types. The player
+//! only motion data moved to [`MotionExPacket`] and the tyre sets packet is new.
+
+mod motion;
+mod session;
+mod lap_data;
+mod event;
+mod participant;
+mod car_status;
+mod lobby_info;
+mod session_history;
+mod tyre_sets;
+
+pub use motion::*;
+pub use session::*;
+pub use lap_data::*;
+pub use event::*;
+pub use participant::*;
+pub use car_status::*;
+pub use lobby_info::*;
+pub use session_history::*;
+pub use tyre_sets::*;
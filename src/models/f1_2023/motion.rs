@@ -0,0 +1,105 @@
+use std::io::{self, Read, Write};
+use crate::models::{CarMotionData, PacketHeader, PacketReader, Vector3D, WheelsVector};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 23 motion packet, only the data of all cars, the player car data is in [`MotionExPacket`].
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct MotionPacket {
+    pub header: PacketHeader,
+    pub car_motion_data: [CarMotionData; 22],
+}
+
+impl Packet for MotionPacket {
+    const PACKET_ID: u8 = 0;
+    const PACKET_SIZE: usize = 1349;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<MotionPacket, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let mut car_motion_data = Vec::with_capacity(22);
+        for _ in 0..22 {
+            car_motion_data.push(CarMotionData::new(reader)?);
+        }
+
+        Ok(MotionPacket {
+            header,
+            car_motion_data: car_motion_data.try_into().expect("22 cars were read"),
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for motion_data in &self.car_motion_data {
+            motion_data.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extra motion data of the player car, sent since F1 23.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct MotionExPacket {
+    pub header: PacketHeader,
+    pub suspension_position: WheelsVector<f32>,
+    pub suspension_velocity: WheelsVector<f32>,
+    pub suspension_acceleration: WheelsVector<f32>,
+    pub wheel_speed: WheelsVector<f32>,
+    pub wheel_slip_ratio: WheelsVector<f32>,
+    pub wheel_slip_angle: WheelsVector<f32>,
+    pub wheel_lat_force: WheelsVector<f32>,
+    pub wheel_long_force: WheelsVector<f32>,
+    pub height_of_cog_above_ground: f32,
+    pub local_velocity: Vector3D<f32>,
+    pub angular_velocity: Vector3D<f32>,
+    pub angular_acceleration: Vector3D<f32>,
+    pub front_wheels_angle: f32,
+    pub wheel_vert_force: WheelsVector<f32>,
+}
+
+impl Packet for MotionExPacket {
+    const PACKET_ID: u8 = 13;
+    const PACKET_SIZE: usize = 217;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<MotionExPacket, ParseError> {
+        Ok(MotionExPacket {
+            header: PacketHeader::new(reader)?,
+            suspension_position: WheelsVector::<f32>::read_from(reader, "suspension_position")?,
+            suspension_velocity: WheelsVector::<f32>::read_from(reader, "suspension_velocity")?,
+            suspension_acceleration: WheelsVector::<f32>::read_from(reader, "suspension_acceleration")?,
+            wheel_speed: WheelsVector::<f32>::read_from(reader, "wheel_speed")?,
+            wheel_slip_ratio: WheelsVector::<f32>::read_from(reader, "wheel_slip_ratio")?,
+            wheel_slip_angle: WheelsVector::<f32>::read_from(reader, "wheel_slip_angle")?,
+            wheel_lat_force: WheelsVector::<f32>::read_from(reader, "wheel_lat_force")?,
+            wheel_long_force: WheelsVector::<f32>::read_from(reader, "wheel_long_force")?,
+            height_of_cog_above_ground: reader.read_f32("height_of_cog_above_ground")?,
+            local_velocity: Vector3D::<f32>::read_from(reader, "local_velocity")?,
+            angular_velocity: Vector3D::<f32>::read_from(reader, "angular_velocity")?,
+            angular_acceleration: Vector3D::<f32>::read_from(reader, "angular_acceleration")?,
+            front_wheels_angle: reader.read_f32("front_wheels_angle")?,
+            wheel_vert_force: WheelsVector::<f32>::read_from(reader, "wheel_vert_force")?,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        self.suspension_position.write_to(writer)?;
+        self.suspension_velocity.write_to(writer)?;
+        self.suspension_acceleration.write_to(writer)?;
+        self.wheel_speed.write_to(writer)?;
+        self.wheel_slip_ratio.write_to(writer)?;
+        self.wheel_slip_angle.write_to(writer)?;
+        self.wheel_lat_force.write_to(writer)?;
+        self.wheel_long_force.write_to(writer)?;
+        writer.write_f32::<LittleEndian>(self.height_of_cog_above_ground)?;
+        self.local_velocity.write_to(writer)?;
+        self.angular_velocity.write_to(writer)?;
+        self.angular_acceleration.write_to(writer)?;
+        writer.write_f32::<LittleEndian>(self.front_wheels_angle)?;
+        self.wheel_vert_force.write_to(writer)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use std::io::{self, Read, Write};
+use crate::models::enums::{Driver, GameEnum, Nationality, Team};
+use crate::models::{PacketHeader, PacketReader, write_string};
+use crate::error::ParseError;
+use byteorder::WriteBytesExt;
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+/// The F1 23 participant, with the online name setting and the platform of the player.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParticipantData {
+    pub ai_controlled: bool,
+    pub driver_id: u8,
+    pub network_id: u8,
+    pub team: Team,
+    pub my_team: bool,
+    pub race_number: u8,
+    pub nationality: u8,
+    pub name: String,
+    pub your_telemetry: bool,
+    pub show_online_names: bool,
+    /// 1 is Steam, 3 PlayStation, 4 Xbox, 6 Origin and 255 unknown.
+    pub platform: u8,
+}
+
+impl ParticipantData {
+    pub fn driver(&self) -> Driver {
+        Driver::from_value(self.driver_id).unwrap_or(Driver::Unknown(self.driver_id))
+    }
+
+    pub fn nationality(&self) -> Nationality {
+        Nationality::from_value(self.nationality).unwrap_or(Nationality::Unknown(self.nationality))
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<ParticipantData, ParseError> {
+        Ok(ParticipantData {
+            ai_controlled: reader.read_bool("ai_controlled")?,
+            driver_id: reader.read_u8("driver_id")?,
+            network_id: reader.read_u8("network_id")?,
+            team: reader.read_enum("team")?,
+            my_team: reader.read_bool("my_team")?,
+            race_number: reader.read_u8("race_number")?,
+            nationality: reader.read_u8("nationality")?,
+            name: reader.read_string::<48>("name")?,
+            your_telemetry: reader.read_bool("your_telemetry")?,
+            show_online_names: reader.read_bool("show_online_names")?,
+            platform: reader.read_u8("platform")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.ai_controlled as u8)?;
+        writer.write_u8(self.driver_id)?;
+        writer.write_u8(self.network_id)?;
+        writer.write_u8(self.team.to_raw())?;
+        writer.write_u8(self.my_team as u8)?;
+        writer.write_u8(self.race_number)?;
+        writer.write_u8(self.nationality)?;
+        write_string::<_, 48>(writer, &self.name)?;
+        writer.write_u8(self.your_telemetry as u8)?;
+        writer.write_u8(self.show_online_names as u8)?;
+        writer.write_u8(self.platform)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParticipantPacket {
+    pub header: PacketHeader,
+    pub num_active_cars: u8,
+    pub participants: Vec<ParticipantData>,
+}
+
+impl Packet for ParticipantPacket {
+
+    const PACKET_ID: u8 = 4;
+    const PACKET_SIZE: usize = 1306;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<ParticipantPacket, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let num_active_cars = reader.read_u8("num_active_cars")?;
+        let mut participants = Vec::new();
+        for _ in 0..22 {
+            participants.push(ParticipantData::new(reader)?);
+        }
+        Ok(ParticipantPacket {
+            header,
+            num_active_cars,
+            participants,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.num_active_cars)?;
+        for participant in &self.participants {
+            participant.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The F1 2022 participant, without the online name setting and the platform.
+impl From<&ParticipantData> for crate::models::ParticipantData {
+    fn from(data: &ParticipantData) -> crate::models::ParticipantData {
+        crate::models::ParticipantData {
+            ai_controlled: data.ai_controlled,
+            driver_id: data.driver_id,
+            network_id: data.network_id,
+            team: data.team,
+            my_team: data.my_team,
+            race_number: data.race_number,
+            nationality: data.nationality,
+            name: data.name.clone(),
+            your_telemetry: data.your_telemetry,
+        }
+    }
+}
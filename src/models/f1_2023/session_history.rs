@@ -0,0 +1,159 @@
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader, TyreStintHistory};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::traits::Packet;
+use crate::models::types::ValidLaps;
+use serde::Serialize;
+
+/// The F1 23 lap history, sector times are split in minutes and milliseconds.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct LapHistory {
+    pub lap_time: u32,
+    pub sector_1_time_ms_part: u16,
+    pub sector_1_time_minutes: u8,
+    pub sector_2_time_ms_part: u16,
+    pub sector_2_time_minutes: u8,
+    pub sector_3_time_ms_part: u16,
+    pub sector_3_time_minutes: u8,
+    pub lap_valid_bit_flags: ValidLaps,
+}
+
+impl LapHistory {
+    /// Times of the three sectors in milliseconds.
+    pub fn sector_times(&self) -> [u32; 3] {
+        [
+            self.sector_1_time_minutes as u32 * 60_000 + self.sector_1_time_ms_part as u32,
+            self.sector_2_time_minutes as u32 * 60_000 + self.sector_2_time_ms_part as u32,
+            self.sector_3_time_minutes as u32 * 60_000 + self.sector_3_time_ms_part as u32,
+        ]
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<LapHistory, ParseError> {
+        Ok(LapHistory {
+            lap_time: reader.read_u32("lap_time")?,
+            sector_1_time_ms_part: reader.read_u16("sector_1_time_ms_part")?,
+            sector_1_time_minutes: reader.read_u8("sector_1_time_minutes")?,
+            sector_2_time_ms_part: reader.read_u16("sector_2_time_ms_part")?,
+            sector_2_time_minutes: reader.read_u8("sector_2_time_minutes")?,
+            sector_3_time_ms_part: reader.read_u16("sector_3_time_ms_part")?,
+            sector_3_time_minutes: reader.read_u8("sector_3_time_minutes")?,
+            lap_valid_bit_flags: ValidLaps::new(reader)?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u32::<LittleEndian>(self.lap_time)?;
+        writer.write_u16::<LittleEndian>(self.sector_1_time_ms_part)?;
+        writer.write_u8(self.sector_1_time_minutes)?;
+        writer.write_u16::<LittleEndian>(self.sector_2_time_ms_part)?;
+        writer.write_u8(self.sector_2_time_minutes)?;
+        writer.write_u16::<LittleEndian>(self.sector_3_time_ms_part)?;
+        writer.write_u8(self.sector_3_time_minutes)?;
+        self.lap_valid_bit_flags.write_to(writer)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketSessionHistory {
+    pub header: PacketHeader,
+    pub car_idx: u8,
+    pub num_laps: u8,
+    pub num_tyre_stints: u8,
+    pub best_lap_time_lap_num: u8,
+    pub best_sector1_time_lap_num: u8,
+    pub best_sector2_time_lap_num: u8,
+    pub best_sector3_time_lap_num: u8,
+    pub lap_history_data: Vec<LapHistory>,
+    pub tyre_stints_history: Vec<TyreStintHistory>,
+}
+
+impl Packet for PacketSessionHistory {
+    const PACKET_ID: u8 = 11;
+    const PACKET_SIZE: usize = 1460;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketSessionHistory, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let car_idx = reader.read_u8("car_idx")?;
+        let num_laps = reader.read_u8("num_laps")?;
+        let num_tyre_stints = reader.read_u8("num_tyre_stints")?;
+        let best_lap_time_lap_num = reader.read_u8("best_lap_time_lap_num")?;
+        let best_sector1_time_lap_num = reader.read_u8("best_sector1_time_lap_num")?;
+        let best_sector2_time_lap_num = reader.read_u8("best_sector2_time_lap_num")?;
+        let best_sector3_time_lap_num = reader.read_u8("best_sector3_time_lap_num")?;
+        let mut lap_history_data = Vec::new();
+        for _ in 0..100 {
+            lap_history_data.push(LapHistory::new(reader)?);
+        }
+        let mut tyre_stints_history = Vec::new();
+        for _ in 0..8 {
+            tyre_stints_history.push(TyreStintHistory::new(reader)?);
+        }
+        Ok(PacketSessionHistory {
+            header,
+            car_idx,
+            num_laps,
+            num_tyre_stints,
+            best_lap_time_lap_num,
+            best_sector1_time_lap_num,
+            best_sector2_time_lap_num,
+            best_sector3_time_lap_num,
+            lap_history_data,
+            tyre_stints_history,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.car_idx)?;
+        writer.write_u8(self.num_laps)?;
+        writer.write_u8(self.num_tyre_stints)?;
+        writer.write_u8(self.best_lap_time_lap_num)?;
+        writer.write_u8(self.best_sector1_time_lap_num)?;
+        writer.write_u8(self.best_sector2_time_lap_num)?;
+        writer.write_u8(self.best_sector3_time_lap_num)?;
+        for lap_history in &self.lap_history_data {
+            lap_history.write_to(writer)?;
+        }
+        for tyre_stint in &self.tyre_stints_history {
+            tyre_stint.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The F1 2022 lap history, sector times too long for it are capped.
+impl From<&LapHistory> for crate::models::LapHistory {
+    fn from(history: &LapHistory) -> crate::models::LapHistory {
+        let [sector_1_time, sector_2_time, sector_3_time] = history.sector_times()
+            .map(|time| time.min(u16::MAX as u32) as u16);
+        crate::models::LapHistory {
+            lap_time: history.lap_time,
+            sector_1_time,
+            sector_2_time,
+            sector_3_time,
+            lap_valid_bit_flags: history.lap_valid_bit_flags,
+        }
+    }
+}
+
+/// The F1 2022 session history.
+impl From<&PacketSessionHistory> for crate::models::PacketSessionHistory {
+    fn from(packet: &PacketSessionHistory) -> crate::models::PacketSessionHistory {
+        crate::models::PacketSessionHistory {
+            header: packet.header,
+            car_idx: packet.car_idx,
+            num_laps: packet.num_laps,
+            num_tyre_stints: packet.num_tyre_stints,
+            best_lap_time_lap_num: packet.best_lap_time_lap_num,
+            best_sector1_time_lap_num: packet.best_sector1_time_lap_num,
+            best_sector2_time_lap_num: packet.best_sector2_time_lap_num,
+            best_sector3_time_lap_num: packet.best_sector3_time_lap_num,
+            lap_history_data: packet.lap_history_data.iter().map(Into::into).collect(),
+            tyre_stints_history: packet.tyre_stints_history.clone(),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::{self, Read, Write};
+use crate::models::enums::{ActualTyreCompound, GameEnum, SessionType, VisualTyreCompound};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::traits::Packet;
+use serde::Serialize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct TyreSetData {
+    pub actual_tyre_compound: ActualTyreCompound,
+    pub visual_tyre_compound: VisualTyreCompound,
+    /// Wear in percent.
+    pub wear: u8,
+    pub available: bool,
+    pub recommended_session: SessionType,
+    /// Laps left in the tyre set.
+    pub life_span: u8,
+    /// Maximum number of laps recommended for the compound.
+    pub usable_life: u8,
+    /// Lap time difference in milliseconds to the fitted set.
+    pub lap_delta_time: i16,
+    pub fitted: bool,
+}
+
+impl TyreSetData {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<TyreSetData, ParseError> {
+        Ok(TyreSetData {
+            actual_tyre_compound: reader.read_enum("actual_tyre_compound")?,
+            visual_tyre_compound: reader.read_enum("visual_tyre_compound")?,
+            wear: reader.read_u8("wear")?,
+            available: reader.read_bool("available")?,
+            recommended_session: reader.read_enum("recommended_session")?,
+            life_span: reader.read_u8("life_span")?,
+            usable_life: reader.read_u8("usable_life")?,
+            lap_delta_time: reader.read_i16("lap_delta_time")?,
+            fitted: reader.read_bool("fitted")?,
+        })
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.actual_tyre_compound.to_raw())?;
+        writer.write_u8(self.visual_tyre_compound.to_raw())?;
+        writer.write_u8(self.wear)?;
+        writer.write_u8(self.available as u8)?;
+        writer.write_u8(self.recommended_session.to_raw())?;
+        writer.write_u8(self.life_span)?;
+        writer.write_u8(self.usable_life)?;
+        writer.write_i16::<LittleEndian>(self.lap_delta_time)?;
+        writer.write_u8(self.fitted as u8)?;
+
+        Ok(())
+    }
+}
+
+/// The tyre sets of a single car, 13 dry and 7 wet sets.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PacketTyreSets {
+    pub header: PacketHeader,
+    pub car_idx: u8,
+    pub tyre_set_data: Vec<TyreSetData>,
+    pub fitted_idx: u8,
+}
+
+impl PacketTyreSets {
+    pub fn fitted(&self) -> Option<&TyreSetData> {
+        self.tyre_set_data.get(self.fitted_idx as usize)
+    }
+}
+
+impl Packet for PacketTyreSets {
+    const PACKET_ID: u8 = 12;
+    const PACKET_SIZE: usize = 231;
+
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketTyreSets, ParseError> {
+        let header = PacketHeader::new(reader)?;
+        let car_idx = reader.read_u8("car_idx")?;
+        let mut tyre_set_data = Vec::new();
+        for _ in 0..20 {
+            tyre_set_data.push(TyreSetData::new(reader)?);
+        }
+        let fitted_idx = reader.read_u8("fitted_idx")?;
+        Ok(PacketTyreSets {
+            header,
+            car_idx,
+            tyre_set_data,
+            fitted_idx,
+        })
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.car_idx)?;
+        for tyre_set in &self.tyre_set_data {
+            tyre_set.write_to(writer)?;
+        }
+        writer.write_u8(self.fitted_idx)?;
+
+        Ok(())
+    }
+}
@@ -1,14 +1,12 @@
-use std::io::Read;
-use crate::models::PacketHeader;
-use byteorder::{ReadBytesExt, LittleEndian};
-use num_traits::FromPrimitive;
-use crate::models::enums::{ActualTyreCompound, ResultStatus, VisualTyreCompound};
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::enums::{ActualTyreCompound, GameEnum, ResultStatus, VisualTyreCompound};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ClassificationData {
     pub position: u8,
     pub num_laps: u8,
@@ -23,56 +21,85 @@ pub struct ClassificationData {
     pub num_tyre_stints: u8,
     pub tyre_stints_actual: Vec<ActualTyreCompound>,
     pub tyre_stints_visual: Vec<VisualTyreCompound>,
+    pub tyre_stints_end_laps: Vec<u8>,
 }
 
 impl ClassificationData {
-    pub fn new<R: Read>(reader: &mut R) -> Result<ClassificationData, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<ClassificationData, ParseError> {
         Ok(ClassificationData {
-            position: reader.read_u8()?,
-            num_laps: reader.read_u8()?,
-            grid_position: reader.read_u8()?,
-            points: reader.read_u8()?,
-            num_pit_stops: reader.read_u8()?,
-            result_status: ResultStatus::from_u8(reader.read_u8()?).unwrap(),
-            best_lap_time: reader.read_u32::<LittleEndian>()?,
-            total_race_time: reader.read_f64::<LittleEndian>()?,
-            penalties_time: reader.read_u8()?,
-            num_penalties: reader.read_u8()?,
-            num_tyre_stints: reader.read_u8()?,
+            position: reader.read_u8("position")?,
+            num_laps: reader.read_u8("num_laps")?,
+            grid_position: reader.read_u8("grid_position")?,
+            points: reader.read_u8("points")?,
+            num_pit_stops: reader.read_u8("num_pit_stops")?,
+            result_status: reader.read_enum("result_status")?,
+            best_lap_time: reader.read_u32("best_lap_time")?,
+            total_race_time: reader.read_f64("total_race_time")?,
+            penalties_time: reader.read_u8("penalties_time")?,
+            num_penalties: reader.read_u8("num_penalties")?,
+            num_tyre_stints: reader.read_u8("num_tyre_stints")?,
             tyre_stints_actual: {
                 let mut v = Vec::new();
                 for _ in 0..8 {
-                    v.push(ActualTyreCompound::from_u8(reader.read_u8()?).unwrap());
+                    v.push(reader.read_enum("tyre_stints_actual")?);
                 }
                 v
             },
             tyre_stints_visual: {
                 let mut v = Vec::new();
                 for _ in 0..8 {
-                    v.push(VisualTyreCompound::from_u8(reader.read_u8()?).unwrap());
+                    v.push(reader.read_enum("tyre_stints_visual")?);
+                }
+                v
+            },
+            tyre_stints_end_laps: {
+                let mut v = Vec::new();
+                for _ in 0..8 {
+                    v.push(reader.read_u8("tyre_stints_end_laps")?);
                 }
                 v
             },
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.position)?;
+        writer.write_u8(self.num_laps)?;
+        writer.write_u8(self.grid_position)?;
+        writer.write_u8(self.points)?;
+        writer.write_u8(self.num_pit_stops)?;
+        writer.write_u8(self.result_status.to_raw())?;
+        writer.write_u32::<LittleEndian>(self.best_lap_time)?;
+        writer.write_f64::<LittleEndian>(self.total_race_time)?;
+        writer.write_u8(self.penalties_time)?;
+        writer.write_u8(self.num_penalties)?;
+        writer.write_u8(self.num_tyre_stints)?;
+        for compound in &self.tyre_stints_actual {
+            writer.write_u8(compound.to_raw())?;
+        }
+        for compound in &self.tyre_stints_visual {
+            writer.write_u8(compound.to_raw())?;
+        }
+        writer.write_all(&self.tyre_stints_end_laps)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PacketFinalClassificationData {
     pub header: PacketHeader,
     pub num_cars: u8,
     pub classification_data: Vec<ClassificationData>,
 }
 
-event_system::signal!(FinalClassificationSignal<FinalClassificationReceiver, PacketFinalClassificationData> = []);
-
 impl Packet for PacketFinalClassificationData {
     const PACKET_ID: u8 = 8;
     const PACKET_SIZE: usize = 1015;
 
-    fn new<R: Read>(reader: &mut R) -> Result<PacketFinalClassificationData, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketFinalClassificationData, ParseError> {
         let header = PacketHeader::new(reader)?;
-        let num_cars = reader.read_u8()?;
+        let num_cars = reader.read_u8("num_cars")?;
         let mut classification_data = Vec::new();
         for _ in 0..22 {
             classification_data.push(ClassificationData::new(reader)?);
@@ -84,5 +111,13 @@ impl Packet for PacketFinalClassificationData {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.num_cars)?;
+        for classification in &self.classification_data {
+            classification.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
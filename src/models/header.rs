@@ -1,13 +1,18 @@
-use std::io::Error;
 use std::io::{
+    self,
     Read,
+    Write,
 };
-use byteorder::{ReadBytesExt, LittleEndian};
+use crate::models::PacketReader;
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct PacketHeader {
     pub packet_format: u16,
+    /// Last two digits of the game year, only sent since F1 23.
+    pub game_year: Option<u8>,
     pub game_major_version: u8,
     pub game_minor_version: u8,
     pub packet_version: u8,
@@ -15,27 +20,68 @@ pub struct PacketHeader {
     pub session_uid: u64,
     pub session_time: f32,
     pub frame_identifier: u32,
+    /// Frame identifier that doesn't go back after flashbacks, only sent since F1 23.
+    pub overall_frame_identifier: Option<u32>,
     pub player_car_index: u8,
     pub secondary_player_car_index: u8,
 }
 
 impl PacketHeader {
     pub const PACKET_SIZE: usize = 24;
+    pub const PACKET_SIZE_2023: usize = 29;
+
+    /// Whether the header has the fields added in F1 23.
+    fn is_2023(packet_format: u16) -> bool {
+        packet_format >= 2023
+    }
+
+    /// Size of the header in the given packet format.
+    pub fn size(packet_format: u16) -> usize {
+        if Self::is_2023(packet_format) { Self::PACKET_SIZE_2023 } else { Self::PACKET_SIZE }
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketHeader, ParseError> {
+        let packet_format = reader.read_u16("packet_format")?;
+        let is_2023 = Self::is_2023(packet_format);
 
-    pub fn new<R: Read>(reader: &mut R) -> Result<PacketHeader, Error> {
         Ok(PacketHeader {
-            packet_format: reader.read_u16::<LittleEndian>()?,
-            game_major_version: reader.read_u8()?,
-            game_minor_version: reader.read_u8()?,
-            packet_version: reader.read_u8()?,
-            packet_id: reader.read_u8()?,
-            session_uid: reader.read_u64::<LittleEndian>()?,
-            session_time: reader.read_f32::<LittleEndian>()?,
-            frame_identifier: reader.read_u32::<LittleEndian>()?,
-            player_car_index: reader.read_u8()?,
-            secondary_player_car_index: reader.read_u8()?,
+            packet_format,
+            game_year: if is_2023 { Some(reader.read_u8("game_year")?) } else { None },
+            game_major_version: reader.read_u8("game_major_version")?,
+            game_minor_version: reader.read_u8("game_minor_version")?,
+            packet_version: reader.read_u8("packet_version")?,
+            packet_id: reader.read_u8("packet_id")?,
+            session_uid: reader.read_u64("session_uid")?,
+            session_time: reader.read_f32("session_time")?,
+            frame_identifier: reader.read_u32("frame_identifier")?,
+            overall_frame_identifier: if is_2023 { Some(reader.read_u32("overall_frame_identifier")?) } else { None },
+            player_car_index: reader.read_u8("player_car_index")?,
+            secondary_player_car_index: reader.read_u8("secondary_player_car_index")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let is_2023 = Self::is_2023(self.packet_format);
+
+        writer.write_u16::<LittleEndian>(self.packet_format)?;
+        if is_2023 {
+            writer.write_u8(self.game_year.unwrap_or_default())?;
+        }
+        writer.write_u8(self.game_major_version)?;
+        writer.write_u8(self.game_minor_version)?;
+        writer.write_u8(self.packet_version)?;
+        writer.write_u8(self.packet_id)?;
+        writer.write_u64::<LittleEndian>(self.session_uid)?;
+        writer.write_f32::<LittleEndian>(self.session_time)?;
+        writer.write_u32::<LittleEndian>(self.frame_identifier)?;
+        if is_2023 {
+            writer.write_u32::<LittleEndian>(self.overall_frame_identifier.unwrap_or_default())?;
+        }
+        writer.write_u8(self.player_car_index)?;
+        writer.write_u8(self.secondary_player_car_index)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -59,7 +105,7 @@ mod tests {
         buf.write_u8(8).expect("Failed to write player car index");
         buf.write_u8(9).expect("Failed to write secondary player car index");
 
-        let header = PacketHeader::new(&mut buf).unwrap();
+        let header = PacketHeader::new(&mut PacketReader::new(&mut buf)).unwrap();
         assert_eq!(header.packet_format, 2022);
         assert_eq!(header.game_major_version, 1);
         assert_eq!(header.game_minor_version, 2);
@@ -70,6 +116,33 @@ mod tests {
         assert_eq!(header.frame_identifier, 7);
         assert_eq!(header.player_car_index, 8);
         assert_eq!(header.secondary_player_car_index, 9);
+        assert_eq!(header.game_year, None);
+    }
+
+    #[test]
+    fn reads_the_f1_23_fields() {
+        let mut buf = Buff::new();
+
+        buf.write_u16::<LittleEndian>(2023).unwrap();
+        buf.write_u8(23).unwrap();
+        buf.write_all(&[1, 2, 3, 4]).unwrap();
+        buf.write_u64::<LittleEndian>(5).unwrap();
+        buf.write_f32::<LittleEndian>(6.0).unwrap();
+        buf.write_u32::<LittleEndian>(7).unwrap();
+        buf.write_u32::<LittleEndian>(10).unwrap();
+        buf.write_all(&[8, 9]).unwrap();
+        assert_eq!(buf.buf.len(), PacketHeader::PACKET_SIZE_2023);
+
+        let header = PacketHeader::new(&mut PacketReader::new(&mut buf)).unwrap();
+        assert_eq!(header.game_year, Some(23));
+        assert_eq!(header.packet_id, 4);
+        assert_eq!(header.frame_identifier, 7);
+        assert_eq!(header.overall_frame_identifier, Some(10));
+        assert_eq!(header.secondary_player_car_index, 9);
+
+        let mut bytes = vec![];
+        header.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes, buf.buf);
     }
 
     struct Buff {
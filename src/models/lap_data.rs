@@ -1,14 +1,12 @@
-use std::io::Read;
-use crate::models::enums::{DriverStatus, PitStatus, ResultStatus, Sector};
-use crate::models::PacketHeader;
-use byteorder::{ReadBytesExt, LittleEndian};
-use num_traits::FromPrimitive;
+use std::io::{self, Read, Write};
+use crate::models::enums::{DriverStatus, GameEnum, PitStatus, ResultStatus, Sector};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct LapData {
     pub last_lap_time: u32,
     pub current_lap_time: u32,
@@ -37,31 +35,31 @@ pub struct LapData {
 }
 
 impl LapData {
-    pub fn new<R: Read>(reader: &mut R) -> Result<LapData, std::io::Error> {
-        let last_lap_time = reader.read_u32::<LittleEndian>()?;
-        let current_lap_time = reader.read_u32::<LittleEndian>()?;
-        let sector1_time = reader.read_u16::<LittleEndian>()?;
-        let sector2_time = reader.read_u16::<LittleEndian>()?;
-        let lap_distance = reader.read_f32::<LittleEndian>()?;
-        let total_distance = reader.read_f32::<LittleEndian>()?;
-        let safety_car_delta = reader.read_f32::<LittleEndian>()?;
-        let car_position = reader.read_u8()?;
-        let current_lap_num = reader.read_u8()?;
-        let pit_status = PitStatus::from_u8(reader.read_u8()?).unwrap();
-        let num_pit_stops = reader.read_u8()?;
-        let sector = Sector::from_u8(reader.read_u8()?).unwrap();
-        let current_lap_invalid = reader.read_u8()? != 0;
-        let penalties = reader.read_u8()?;
-        let warnings = reader.read_u8()?;
-        let num_unserved_drive_through_penalties = reader.read_u8()?;
-        let num_unserved_stop_go_penalties = reader.read_u8()?;
-        let grid_position = reader.read_u8()?;
-        let driver_status = DriverStatus::from_u8(reader.read_u8()?).unwrap();
-        let result_status = ResultStatus::from_u8(reader.read_u8()?).unwrap();
-        let pit_lane_timer_active = reader.read_u8()? != 0;
-        let pit_lane_time_in_lane = reader.read_u16::<LittleEndian>()?;
-        let pit_stop_timer = reader.read_u16::<LittleEndian>()?;
-        let pit_stop_should_serve_penalty = reader.read_u8()? != 0;
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<LapData, ParseError> {
+        let last_lap_time = reader.read_u32("last_lap_time")?;
+        let current_lap_time = reader.read_u32("current_lap_time")?;
+        let sector1_time = reader.read_u16("sector1_time")?;
+        let sector2_time = reader.read_u16("sector2_time")?;
+        let lap_distance = reader.read_f32("lap_distance")?;
+        let total_distance = reader.read_f32("total_distance")?;
+        let safety_car_delta = reader.read_f32("safety_car_delta")?;
+        let car_position = reader.read_u8("car_position")?;
+        let current_lap_num = reader.read_u8("current_lap_num")?;
+        let pit_status = reader.read_enum("pit_status")?;
+        let num_pit_stops = reader.read_u8("num_pit_stops")?;
+        let sector = reader.read_enum("sector")?;
+        let current_lap_invalid = reader.read_bool("current_lap_invalid")?;
+        let penalties = reader.read_u8("penalties")?;
+        let warnings = reader.read_u8("warnings")?;
+        let num_unserved_drive_through_penalties = reader.read_u8("num_unserved_drive_through_penalties")?;
+        let num_unserved_stop_go_penalties = reader.read_u8("num_unserved_stop_go_penalties")?;
+        let grid_position = reader.read_u8("grid_position")?;
+        let driver_status = reader.read_enum("driver_status")?;
+        let result_status = reader.read_enum("result_status")?;
+        let pit_lane_timer_active = reader.read_bool("pit_lane_timer_active")?;
+        let pit_lane_time_in_lane = reader.read_u16("pit_lane_time_in_lane")?;
+        let pit_stop_timer = reader.read_u16("pit_stop_timer")?;
+        let pit_stop_should_serve_penalty = reader.read_bool("pit_stop_should_serve_penalty")?;
         
         Ok(LapData {
             last_lap_time,
@@ -90,9 +88,38 @@ impl LapData {
             pit_stop_should_serve_penalty,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u32::<LittleEndian>(self.last_lap_time)?;
+        writer.write_u32::<LittleEndian>(self.current_lap_time)?;
+        writer.write_u16::<LittleEndian>(self.sector1_time)?;
+        writer.write_u16::<LittleEndian>(self.sector2_time)?;
+        writer.write_f32::<LittleEndian>(self.lap_distance)?;
+        writer.write_f32::<LittleEndian>(self.total_distance)?;
+        writer.write_f32::<LittleEndian>(self.safety_car_delta)?;
+        writer.write_u8(self.car_position)?;
+        writer.write_u8(self.current_lap_num)?;
+        writer.write_u8(self.pit_status.to_raw())?;
+        writer.write_u8(self.num_pit_stops)?;
+        writer.write_u8(self.sector.to_raw())?;
+        writer.write_u8(self.current_lap_invalid as u8)?;
+        writer.write_u8(self.penalties)?;
+        writer.write_u8(self.warnings)?;
+        writer.write_u8(self.num_unserved_drive_through_penalties)?;
+        writer.write_u8(self.num_unserved_stop_go_penalties)?;
+        writer.write_u8(self.grid_position)?;
+        writer.write_u8(self.driver_status.to_raw())?;
+        writer.write_u8(self.result_status.to_raw())?;
+        writer.write_u8(self.pit_lane_timer_active as u8)?;
+        writer.write_u16::<LittleEndian>(self.pit_lane_time_in_lane)?;
+        writer.write_u16::<LittleEndian>(self.pit_stop_timer)?;
+        writer.write_u8(self.pit_stop_should_serve_penalty as u8)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PacketLapData {
     pub header: PacketHeader,
     pub lap_data: Vec<LapData>,
@@ -100,20 +127,18 @@ pub struct PacketLapData {
     pub time_trial_rival_car_idx: u8,
 }
 
-event_system::signal!(LapDataSignal<LapDataReceiver, PacketLapData> = []);
-
 impl Packet for PacketLapData {
     const PACKET_ID: u8 = 2;
     const PACKET_SIZE: usize = 972;
 
-    fn new<R: Read>(reader: &mut R) -> Result<PacketLapData, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketLapData, ParseError> {
         let header = PacketHeader::new(reader)?;
         let mut lap_data = vec![];
         for _ in 0..22 {
             lap_data.push(LapData::new(reader)?);
         }
-        let time_trial_pb_car_idx = reader.read_u8()?;
-        let time_trial_rival_car_idx = reader.read_u8()?;
+        let time_trial_pb_car_idx = reader.read_u8("time_trial_pb_car_idx")?;
+        let time_trial_rival_car_idx = reader.read_u8("time_trial_rival_car_idx")?;
         Ok(PacketLapData {
             header,
             lap_data,
@@ -122,5 +147,14 @@ impl Packet for PacketLapData {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for lap_data in &self.lap_data {
+            lap_data.write_to(writer)?;
+        }
+        writer.write_u8(self.time_trial_pb_car_idx)?;
+        writer.write_u8(self.time_trial_rival_car_idx)?;
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,14 +1,12 @@
-use std::io::Read;
-use crate::models::PacketHeader;
-use byteorder::ReadBytesExt;
-use num_traits::FromPrimitive;
-use crate::models::enums::{ReadyStatus, Team};
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader, write_string};
+use crate::error::ParseError;
+use byteorder::WriteBytesExt;
+use crate::models::enums::{GameEnum, Nationality, ReadyStatus, Team};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct LobbyInfo {
     pub ai_controlled: bool,
     pub team: Team,
@@ -19,40 +17,47 @@ pub struct LobbyInfo {
 }
 
 impl LobbyInfo {
-    pub fn new<R: Read>(reader: &mut R) -> Result<LobbyInfo, std::io::Error> {
+    pub fn nationality(&self) -> Nationality {
+        Nationality::from_value(self.nationality).unwrap_or(Nationality::Unknown(self.nationality))
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<LobbyInfo, ParseError> {
         Ok(LobbyInfo {
-            ai_controlled: reader.read_u8()? == 1,
-            team: Team::from_u8(reader.read_u8()?).unwrap(),
-            nationality: reader.read_u8()?,
-            name: {
-                let mut v = Vec::new();
-                for _ in 0..48 {
-                    v.push(reader.read_u8()?);
-                }
-                String::from_utf8(v).unwrap()
-            },
-            car_number: reader.read_u8()?,
-            ready_status: ReadyStatus::from_u8(reader.read_u8()?).unwrap(),
+            ai_controlled: reader.read_bool("ai_controlled")?,
+            team: reader.read_enum("team")?,
+            nationality: reader.read_u8("nationality")?,
+            name: reader.read_string::<48>("name")?,
+            car_number: reader.read_u8("car_number")?,
+            ready_status: reader.read_enum("ready_status")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.ai_controlled as u8)?;
+        writer.write_u8(self.team.to_raw())?;
+        writer.write_u8(self.nationality)?;
+        write_string::<_, 48>(writer, &self.name)?;
+        writer.write_u8(self.car_number)?;
+        writer.write_u8(self.ready_status.to_raw())?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PacketLobbyInfo {
     pub header: PacketHeader,
     pub num_lobbies: u8,
     pub lobbies: Vec<LobbyInfo>,
 }
 
-event_system::signal!(LobbyInfoSignal<LobbyInfoReceiver, PacketLobbyInfo> = []);
-
 impl Packet for PacketLobbyInfo {
     const PACKET_ID: u8 = 9;
     const PACKET_SIZE: usize = 1191;
 
-    fn new<R: Read>(reader: &mut R) -> Result<PacketLobbyInfo, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketLobbyInfo, ParseError> {
         let header = PacketHeader::new(reader)?;
-        let num_lobbies = reader.read_u8()?;
+        let num_lobbies = reader.read_u8("num_lobbies")?;
         let mut lobbies = Vec::new();
         for _ in 0..22 {
             lobbies.push(LobbyInfo::new(reader)?);
@@ -64,5 +69,13 @@ impl Packet for PacketLobbyInfo {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.num_lobbies)?;
+        for lobby in &self.lobbies {
+            lobby.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -14,7 +14,11 @@ mod final_classification;
 mod lobby_info;
 mod car_damage;
 mod session_history;
+mod reader;
+mod writer;
 pub mod types;
+pub mod f1_2021;
+pub mod f1_2023;
 pub mod traits;
 
 pub use header::*;
@@ -31,4 +35,6 @@ pub use car_status::*;
 pub use final_classification::*;
 pub use lobby_info::*;
 pub use car_damage::*;
-pub use session_history::*;
\ No newline at end of file
+pub use session_history::*;
+pub use reader::*;
+pub use writer::*;
\ No newline at end of file
@@ -1,12 +1,11 @@
-use std::io::{Error, Read};
-use crate::models::{PacketHeader, Vector3D, WheelsVector};
-use byteorder::{ReadBytesExt, LittleEndian};
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader, Vector3D, WheelsVector};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
 use crate::models::traits::Packet;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct MotionPacket {
     pub header: PacketHeader,
     pub car_motion_data: [CarMotionData; 22],
@@ -22,7 +21,7 @@ pub struct MotionPacket {
     pub front_wheels_angle: f32,
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct CarMotionData {
     pub world_position: Vector3D<f32>,
     pub world_velocity: Vector3D<f32>,
@@ -37,23 +36,36 @@ pub struct CarMotionData {
 }
 
 impl CarMotionData {
-    pub fn new<R: Read>(reader: &mut R) -> Result<CarMotionData, Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<CarMotionData, ParseError> {
         Ok(CarMotionData {
-            world_position: Vector3D::<f32>::read_from(reader)?,
-            world_velocity: Vector3D::<f32>::read_from(reader)?,
-            world_forward_dir: Vector3D::<i16>::read_from(reader)?,
-            world_right_dir: Vector3D::<i16>::read_from(reader)?,
-            g_force_lateral: reader.read_f32::<LittleEndian>()?,
-            g_force_longitudinal: reader.read_f32::<LittleEndian>()?,
-            g_force_vertical: reader.read_f32::<LittleEndian>()?,
-            yaw: reader.read_f32::<LittleEndian>()?,
-            pitch: reader.read_f32::<LittleEndian>()?,
-            roll: reader.read_f32::<LittleEndian>()?,
+            world_position: Vector3D::<f32>::read_from(reader, "world_position")?,
+            world_velocity: Vector3D::<f32>::read_from(reader, "world_velocity")?,
+            world_forward_dir: Vector3D::<i16>::read_from(reader, "world_forward_dir")?,
+            world_right_dir: Vector3D::<i16>::read_from(reader, "world_right_dir")?,
+            g_force_lateral: reader.read_f32("g_force_lateral")?,
+            g_force_longitudinal: reader.read_f32("g_force_longitudinal")?,
+            g_force_vertical: reader.read_f32("g_force_vertical")?,
+            yaw: reader.read_f32("yaw")?,
+            pitch: reader.read_f32("pitch")?,
+            roll: reader.read_f32("roll")?,
         })
     }
-}
 
-event_system::signal!(MotionSignal<MotionReceiver, MotionPacket> = []);
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.world_position.write_to(writer)?;
+        self.world_velocity.write_to(writer)?;
+        self.world_forward_dir.write_to(writer)?;
+        self.world_right_dir.write_to(writer)?;
+        writer.write_f32::<LittleEndian>(self.g_force_lateral)?;
+        writer.write_f32::<LittleEndian>(self.g_force_longitudinal)?;
+        writer.write_f32::<LittleEndian>(self.g_force_vertical)?;
+        writer.write_f32::<LittleEndian>(self.yaw)?;
+        writer.write_f32::<LittleEndian>(self.pitch)?;
+        writer.write_f32::<LittleEndian>(self.roll)?;
+
+        Ok(())
+    }
+}
 
 impl Packet for MotionPacket {
 
@@ -61,7 +73,7 @@ impl Packet for MotionPacket {
     const PACKET_SIZE: usize = 1464;
 
 
-    fn new<R: Read>(mut reader: &mut R) -> Result<MotionPacket, Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<MotionPacket, ParseError> {
         let header = PacketHeader::new(reader)?;
         let mut car_motion_data: [CarMotionData; 22] = [CarMotionData {
             world_position: Vector3D {x: 0.0, y: 0.0, z: 0.0},
@@ -80,15 +92,15 @@ impl Packet for MotionPacket {
             *motion_data = CarMotionData::new(reader)?;
         }
 
-        let suspension_position = WheelsVector::<f32>::read_from(&mut reader)?;
-        let suspension_velocity = WheelsVector::<f32>::read_from(&mut reader)?;
-        let suspension_acceleration = WheelsVector::<f32>::read_from(&mut reader)?;
-        let wheel_speed = WheelsVector::<f32>::read_from(&mut reader)?;
-        let wheel_slip = WheelsVector::<f32>::read_from(&mut reader)?;
-        let local_velocity = Vector3D::<f32>::read_from(&mut reader)?;
-        let angular_velocity = Vector3D::<f32>::read_from(&mut reader)?;
-        let angular_acceleration = Vector3D::<f32>::read_from(&mut reader)?;
-        let front_wheels_angle = reader.read_f32::<LittleEndian>()?;
+        let suspension_position = WheelsVector::<f32>::read_from(reader, "suspension_position")?;
+        let suspension_velocity = WheelsVector::<f32>::read_from(reader, "suspension_velocity")?;
+        let suspension_acceleration = WheelsVector::<f32>::read_from(reader, "suspension_acceleration")?;
+        let wheel_speed = WheelsVector::<f32>::read_from(reader, "wheel_speed")?;
+        let wheel_slip = WheelsVector::<f32>::read_from(reader, "wheel_slip")?;
+        let local_velocity = Vector3D::<f32>::read_from(reader, "local_velocity")?;
+        let angular_velocity = Vector3D::<f32>::read_from(reader, "angular_velocity")?;
+        let angular_acceleration = Vector3D::<f32>::read_from(reader, "angular_acceleration")?;
+        let front_wheels_angle = reader.read_f32("front_wheels_angle")?;
 
         Ok(MotionPacket {
             header,
@@ -105,5 +117,21 @@ impl Packet for MotionPacket {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        for motion_data in &self.car_motion_data {
+            motion_data.write_to(writer)?;
+        }
+        self.suspension_position.write_to(writer)?;
+        self.suspension_velocity.write_to(writer)?;
+        self.suspension_acceleration.write_to(writer)?;
+        self.wheel_speed.write_to(writer)?;
+        self.wheel_slip.write_to(writer)?;
+        self.local_velocity.write_to(writer)?;
+        self.angular_velocity.write_to(writer)?;
+        self.angular_acceleration.write_to(writer)?;
+        writer.write_f32::<LittleEndian>(self.front_wheels_angle)?;
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,14 +1,12 @@
-use std::io::Read;
-use crate::models::enums::Team;
-use num_traits::FromPrimitive;
-use byteorder::ReadBytesExt;
-use crate::models::PacketHeader;
+use std::io::{self, Read, Write};
+use crate::models::enums::{Driver, GameEnum, Nationality, Team};
+use crate::models::{PacketHeader, PacketReader, write_string};
+use crate::error::ParseError;
+use byteorder::WriteBytesExt;
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ParticipantData {
     pub ai_controlled: bool,
     pub driver_id: u8,
@@ -22,44 +20,58 @@ pub struct ParticipantData {
 }
 
 impl ParticipantData {
-    pub fn new<R: Read>(reader: &mut R) -> Result<ParticipantData, std::io::Error> {
+    pub fn driver(&self) -> Driver {
+        Driver::from_value(self.driver_id).unwrap_or(Driver::Unknown(self.driver_id))
+    }
+
+    pub fn nationality(&self) -> Nationality {
+        Nationality::from_value(self.nationality).unwrap_or(Nationality::Unknown(self.nationality))
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<ParticipantData, ParseError> {
         Ok(ParticipantData {
-            ai_controlled: reader.read_u8()? == 1,
-            driver_id: reader.read_u8()?,
-            network_id: reader.read_u8()?,
-            team: Team::from_u8(reader.read_u8()?).unwrap(),
-            my_team: reader.read_u8()? == 1,
-            race_number: reader.read_u8()?,
-            nationality: reader.read_u8()?,
-            name: {
-                let mut name = String::new();
-                for _ in 0..48 {
-                    name.push(reader.read_u8()? as char);
-                }
-                name
-            },
-            your_telemetry: reader.read_u8()? == 1,
+            ai_controlled: reader.read_bool("ai_controlled")?,
+            driver_id: reader.read_u8("driver_id")?,
+            network_id: reader.read_u8("network_id")?,
+            team: reader.read_enum("team")?,
+            my_team: reader.read_bool("my_team")?,
+            race_number: reader.read_u8("race_number")?,
+            nationality: reader.read_u8("nationality")?,
+            name: reader.read_string::<48>("name")?,
+            your_telemetry: reader.read_bool("your_telemetry")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.ai_controlled as u8)?;
+        writer.write_u8(self.driver_id)?;
+        writer.write_u8(self.network_id)?;
+        writer.write_u8(self.team.to_raw())?;
+        writer.write_u8(self.my_team as u8)?;
+        writer.write_u8(self.race_number)?;
+        writer.write_u8(self.nationality)?;
+        write_string::<_, 48>(writer, &self.name)?;
+        writer.write_u8(self.your_telemetry as u8)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ParticipantPacket {
     pub header: PacketHeader,
     pub num_active_cars: u8,
     pub participants: Vec<ParticipantData>,
 }
 
-event_system::signal!(ParticipantSignal<ParticipantReceiver, ParticipantPacket> = []);
-
 impl Packet for ParticipantPacket {
 
     const PACKET_ID: u8 = 4;
     const PACKET_SIZE: usize = 1257;
 
-    fn new<R: Read>(reader: &mut R) -> Result<ParticipantPacket, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<ParticipantPacket, ParseError> {
         let header = PacketHeader::new(reader)?;
-        let num_active_cars = reader.read_u8()?;
+        let num_active_cars = reader.read_u8("num_active_cars")?;
         let mut participants = Vec::new();
         for _ in 0..22 {
             participants.push(ParticipantData::new(reader)?);
@@ -71,5 +83,13 @@ impl Packet for ParticipantPacket {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.num_active_cars)?;
+        for participant in &self.participants {
+            participant.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
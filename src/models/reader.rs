@@ -0,0 +1,155 @@
+use std::io::Read;
+use byteorder::{LittleEndian, ReadBytesExt};
+use crate::error::{ParseError, ParseErrorKind};
+use crate::models::enums::GameEnum;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// Values that don't match any enum variant are rejected with a [`ParseError`].
+    #[default]
+    Strict,
+    /// Values that don't match any enum variant are kept in its `Unknown` variant.
+    Lenient,
+}
+
+/// Reads the fields of a packet while keeping track of the byte offset, so errors can
+/// point at the field that failed to decode.
+pub struct PacketReader<R> {
+    inner: R,
+    offset: usize,
+    mode: DecodeMode,
+}
+
+macro_rules! read_number {
+    ($name:ident, $ty:ty) => {
+        pub fn $name(&mut self, field: &'static str) -> Result<$ty, ParseError> {
+            let value = self.inner.$name::<LittleEndian>()
+                .map_err(|e| ParseError::from_io(self.offset, field, e))?;
+            self.offset += std::mem::size_of::<$ty>();
+            Ok(value)
+        }
+    };
+}
+
+impl<R: Read> PacketReader<R> {
+    pub fn new(inner: R) -> PacketReader<R> {
+        PacketReader::with_mode(inner, DecodeMode::Strict)
+    }
+
+    pub fn with_mode(inner: R, mode: DecodeMode) -> PacketReader<R> {
+        PacketReader {
+            inner,
+            offset: 0,
+            mode,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn mode(&self) -> DecodeMode {
+        self.mode
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    pub fn read_u8(&mut self, field: &'static str) -> Result<u8, ParseError> {
+        let value = self.inner.read_u8().map_err(|e| ParseError::from_io(self.offset, field, e))?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    pub fn read_i8(&mut self, field: &'static str) -> Result<i8, ParseError> {
+        Ok(self.read_u8(field)? as i8)
+    }
+
+    pub fn read_bool(&mut self, field: &'static str) -> Result<bool, ParseError> {
+        Ok(self.read_u8(field)? != 0)
+    }
+
+    read_number!(read_u16, u16);
+    read_number!(read_i16, i16);
+    read_number!(read_u32, u32);
+    read_number!(read_u64, u64);
+    read_number!(read_f32, f32);
+    read_number!(read_f64, f64);
+
+    pub fn read_bytes<const N: usize>(&mut self, field: &'static str) -> Result<[u8; N], ParseError> {
+        let mut bytes = [0; N];
+        self.inner.read_exact(&mut bytes).map_err(|e| ParseError::from_io(self.offset, field, e))?;
+        self.offset += N;
+        Ok(bytes)
+    }
+
+    /// Reads a null terminated string stored in a fixed size field of `N` bytes.
+    pub fn read_string<const N: usize>(&mut self, field: &'static str) -> Result<String, ParseError> {
+        let offset = self.offset;
+        let bytes = self.read_bytes::<N>(field)?;
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(N);
+
+        match (String::from_utf8(bytes[..end].to_vec()), self.mode) {
+            (Ok(value), _) => Ok(value),
+            (Err(e), DecodeMode::Lenient) => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+            (Err(_), DecodeMode::Strict) => Err(ParseError::new(offset, field, ParseErrorKind::InvalidUtf8)),
+        }
+    }
+
+    pub fn read_enum<E: GameEnum>(&mut self, field: &'static str) -> Result<E, ParseError> {
+        let offset = self.offset;
+        let raw = self.read_u8(field)?;
+
+        match (E::from_raw(raw), self.mode) {
+            (Some(value), _) => Ok(value),
+            (None, DecodeMode::Lenient) => Ok(E::unknown(raw)),
+            (None, DecodeMode::Strict) => Err(ParseError::new(offset, field, ParseErrorKind::InvalidDiscriminant(raw))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EventDetails, PacketEventData};
+    use crate::models::enums::PenaltyType;
+    use crate::models::traits::Packet;
+
+    fn penalty_event(penalty_type: u8) -> Vec<u8> {
+        let mut bytes = vec![0; PacketEventData::PACKET_SIZE];
+        bytes[0..2].copy_from_slice(&2022u16.to_le_bytes());
+        bytes[5] = PacketEventData::PACKET_ID;
+        bytes[24..28].copy_from_slice(b"PENA");
+        bytes[28] = penalty_type;
+        bytes
+    }
+
+    #[test]
+    fn strict_mode_rejects_unknown_discriminants() {
+        let error = PacketEventData::new(&mut &penalty_event(200)[..]).unwrap_err();
+
+        assert_eq!(error.packet_id, Some(PacketEventData::PACKET_ID));
+        assert_eq!(error.offset, 28);
+        assert_eq!(error.field, "penalty_type");
+        assert_eq!(error.kind, ParseErrorKind::InvalidDiscriminant(200));
+    }
+
+    #[test]
+    fn lenient_mode_keeps_unknown_discriminants() {
+        let packet = PacketEventData::new_lenient(&mut &penalty_event(200)[..]).unwrap();
+
+        match packet.event_details {
+            Some(EventDetails::Penalty(penalty)) => assert_eq!(penalty.penalty_type, PenaltyType::Unknown(200)),
+            _ => panic!("Expected a penalty event"),
+        }
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let error = PacketEventData::new(&mut &penalty_event(1)[..30]).unwrap_err();
+
+        assert_eq!(error.offset, 30);
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+    }
+}
\ No newline at end of file
@@ -1,29 +1,34 @@
-use std::io::Read;
-use crate::models::enums::{DynamicRacingLine, DynamicRacingLineType, GameMode, GearboxAssist, NetworkGame, RuleSet, SafetyCarStatus, SessionLength, SessionType, Weather, ZoneFlag};
-use crate::models::PacketHeader;
-use byteorder::{ReadBytesExt, LittleEndian};
-use num_traits::FromPrimitive;
+use std::io::{self, Read, Write};
+use crate::models::enums::{DynamicRacingLine, DynamicRacingLineType, GameEnum, GameMode, GearboxAssist, NetworkGame, RuleSet, SafetyCarStatus, SessionLength, SessionType, Track, Weather, ZoneFlag};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
 use crate::models::traits::Packet;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MarshalZone {
     pub zone_start: f32,
     pub zone_flag: ZoneFlag,
 }
 
 impl MarshalZone {
-    pub fn new<R: Read>(reader: &mut R) -> Result<MarshalZone, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<MarshalZone, ParseError> {
         Ok(MarshalZone {
-            zone_start: reader.read_f32::<LittleEndian>()?,
-            zone_flag: ZoneFlag::from_i8(reader.read_i8()?).unwrap(),
+            zone_start: reader.read_f32("zone_start")?,
+            zone_flag: reader.read_enum("zone_flag")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_f32::<LittleEndian>(self.zone_start)?;
+        writer.write_u8(self.zone_flag.to_raw())?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct WeatherForecastSample {
     pub session_type: SessionType,
     pub time_offset: u8,
@@ -36,21 +41,34 @@ pub struct WeatherForecastSample {
 }
 
 impl WeatherForecastSample {
-    pub fn new<R: Read>(reader: &mut R) -> Result<WeatherForecastSample, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<WeatherForecastSample, ParseError> {
         Ok(WeatherForecastSample {
-            session_type: SessionType::from_u8(reader.read_u8()?).unwrap(),
-            time_offset: reader.read_u8()?,
-            weather: Weather::from_u8(reader.read_u8()?).unwrap(),
-            track_temperature: reader.read_i8()?,
-            track_temperature_change: reader.read_i8()?,
-            air_temperature: reader.read_i8()?,
-            air_temperature_change: reader.read_i8()?,
-            rain_percentage: reader.read_u8()?,
+            session_type: reader.read_enum("session_type")?,
+            time_offset: reader.read_u8("time_offset")?,
+            weather: reader.read_enum("weather")?,
+            track_temperature: reader.read_i8("track_temperature")?,
+            track_temperature_change: reader.read_i8("track_temperature_change")?,
+            air_temperature: reader.read_i8("air_temperature")?,
+            air_temperature_change: reader.read_i8("air_temperature_change")?,
+            rain_percentage: reader.read_u8("rain_percentage")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.session_type.to_raw())?;
+        writer.write_u8(self.time_offset)?;
+        writer.write_u8(self.weather.to_raw())?;
+        writer.write_i8(self.track_temperature)?;
+        writer.write_i8(self.track_temperature_change)?;
+        writer.write_i8(self.air_temperature)?;
+        writer.write_i8(self.air_temperature_change)?;
+        writer.write_u8(self.rain_percentage)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SessionDataPacket {
     pub header: PacketHeader,
     pub weather: u8,
@@ -99,62 +117,66 @@ pub struct SessionDataPacket {
     pub session_length: SessionLength,
 }
 
-event_system::signal!(SessionSignal<SessionReceiver, SessionDataPacket> = []);
+impl SessionDataPacket {
+    pub fn track(&self) -> Track {
+        Track::from_value(self.track_id).unwrap_or(Track::Unknown(self.track_id))
+    }
+}
 
 impl Packet for SessionDataPacket {
     const PACKET_ID: u8 = 1;
     const PACKET_SIZE: usize = 632;
 
-    fn new<R: Read>(reader: &mut R) -> Result<SessionDataPacket, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<SessionDataPacket, ParseError> {
         let header = PacketHeader::new(reader)?;
-        let weather = reader.read_u8()?;
-        let track_temperature = reader.read_i8()?;
-        let air_temperature = reader.read_i8()?;
-        let total_laps = reader.read_u8()?;
-        let track_length = reader.read_u16::<LittleEndian>()?;
-        let session_type = SessionType::from_u8(reader.read_u8()?).unwrap();
-        let track_id = reader.read_i8()?;
-        let formula = reader.read_u8()?;
-        let session_time_left = reader.read_u16::<LittleEndian>()?;
-        let session_duration = reader.read_u16::<LittleEndian>()?;
-        let pit_speed_limit = reader.read_u8()?;
-        let game_paused = reader.read_u8()?;
-        let is_spectating = reader.read_u8()?;
-        let spectator_car_index = reader.read_u8()?;
-        let sli_pro_native_support = reader.read_u8()?;
-        let num_marshal_zones = reader.read_u8()?;
+        let weather = reader.read_u8("weather")?;
+        let track_temperature = reader.read_i8("track_temperature")?;
+        let air_temperature = reader.read_i8("air_temperature")?;
+        let total_laps = reader.read_u8("total_laps")?;
+        let track_length = reader.read_u16("track_length")?;
+        let session_type = reader.read_enum("session_type")?;
+        let track_id = reader.read_i8("track_id")?;
+        let formula = reader.read_u8("formula")?;
+        let session_time_left = reader.read_u16("session_time_left")?;
+        let session_duration = reader.read_u16("session_duration")?;
+        let pit_speed_limit = reader.read_u8("pit_speed_limit")?;
+        let game_paused = reader.read_u8("game_paused")?;
+        let is_spectating = reader.read_u8("is_spectating")?;
+        let spectator_car_index = reader.read_u8("spectator_car_index")?;
+        let sli_pro_native_support = reader.read_u8("sli_pro_native_support")?;
+        let num_marshal_zones = reader.read_u8("num_marshal_zones")?;
         let mut marshal_zones = Vec::new();
         for _ in 0..21 {
             marshal_zones.push(MarshalZone::new(reader)?);
         }
-        let safety_car_status = SafetyCarStatus::from_u8(reader.read_u8()?).unwrap();
-        let network_game = NetworkGame::from_u8(reader.read_u8()?).unwrap();
-        let num_weather_forecast_samples = reader.read_u8()?;
+        let safety_car_status = reader.read_enum("safety_car_status")?;
+        let network_game = reader.read_enum("network_game")?;
+        let num_weather_forecast_samples = reader.read_u8("num_weather_forecast_samples")?;
         let mut weather_forecast_samples = Vec::new();
         for _ in 0..56 {
             weather_forecast_samples.push(WeatherForecastSample::new(reader)?);
         }
-        let forecast_accuracy = reader.read_u8()?;
-        let ai_difficulty = reader.read_u8()?;
-        let season_link_identifier = reader.read_u32::<LittleEndian>()?;
-        let weekend_link_identifier = reader.read_u32::<LittleEndian>()?;
-        let session_link_identifier = reader.read_u32::<LittleEndian>()?;
-        let pit_stop_window_ideal_lap = reader.read_u8()?;
-        let pit_stop_window_latest_lap = reader.read_u8()?;
-        let pit_stop_rejoin_position = reader.read_u8()?;
-        let steering_assist = reader.read_u8()? != 0;
-        let braking_assist = reader.read_u8()? != 0;
-        let gearbox_assist = GearboxAssist::from_u8(reader.read_u8()?).unwrap();
-        let pit_assist = reader.read_u8()? != 0;
-        let pit_release_assist = reader.read_u8()? != 0;
-        let ers_assist = reader.read_u8()? != 0;
-        let drs_assist = reader.read_u8()? != 0;
-        let dynamic_racing_line = DynamicRacingLine::from_u8(reader.read_u8()?).unwrap();
-        let dynamic_racing_line_type = DynamicRacingLineType::from_u8(reader.read_u8()?).unwrap();
-        let game_mode = GameMode::from_u8(reader.read_u8()?).unwrap();
-        let rule_set = RuleSet::from_u8(reader.read_u8()?).unwrap();
-        let time_of_day = reader.read_u32::<LittleEndian>()?;
-        let session_length = SessionLength::from_u8(reader.read_u8()?).unwrap();
+        let forecast_accuracy = reader.read_u8("forecast_accuracy")?;
+        let ai_difficulty = reader.read_u8("ai_difficulty")?;
+        let season_link_identifier = reader.read_u32("season_link_identifier")?;
+        let weekend_link_identifier = reader.read_u32("weekend_link_identifier")?;
+        let session_link_identifier = reader.read_u32("session_link_identifier")?;
+        let pit_stop_window_ideal_lap = reader.read_u8("pit_stop_window_ideal_lap")?;
+        let pit_stop_window_latest_lap = reader.read_u8("pit_stop_window_latest_lap")?;
+        let pit_stop_rejoin_position = reader.read_u8("pit_stop_rejoin_position")?;
+        let steering_assist = reader.read_bool("steering_assist")?;
+        let braking_assist = reader.read_bool("braking_assist")?;
+        let gearbox_assist = reader.read_enum("gearbox_assist")?;
+        let pit_assist = reader.read_bool("pit_assist")?;
+        let pit_release_assist = reader.read_bool("pit_release_assist")?;
+        let ers_assist = reader.read_bool("ers_assist")?;
+        let drs_assist = reader.read_bool("drs_assist")?;
+        let dynamic_racing_line = reader.read_enum("dynamic_racing_line")?;
+        let dynamic_racing_line_type = reader.read_enum("dynamic_racing_line_type")?;
+        let game_mode = reader.read_enum("game_mode")?;
+        let rule_set = reader.read_enum("rule_set")?;
+        let time_of_day = reader.read_u32("time_of_day")?;
+        let session_length = reader.read_enum("session_length")?;
 
         Ok(SessionDataPacket {
             header,
@@ -203,5 +225,55 @@ impl Packet for SessionDataPacket {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.weather)?;
+        writer.write_i8(self.track_temperature)?;
+        writer.write_i8(self.air_temperature)?;
+        writer.write_u8(self.total_laps)?;
+        writer.write_u16::<LittleEndian>(self.track_length)?;
+        writer.write_u8(self.session_type.to_raw())?;
+        writer.write_i8(self.track_id)?;
+        writer.write_u8(self.formula)?;
+        writer.write_u16::<LittleEndian>(self.session_time_left)?;
+        writer.write_u16::<LittleEndian>(self.session_duration)?;
+        writer.write_u8(self.pit_speed_limit)?;
+        writer.write_u8(self.game_paused)?;
+        writer.write_u8(self.is_spectating)?;
+        writer.write_u8(self.spectator_car_index)?;
+        writer.write_u8(self.sli_pro_native_support)?;
+        writer.write_u8(self.num_marshal_zones)?;
+        for marshal_zone in &self.marshal_zones {
+            marshal_zone.write_to(writer)?;
+        }
+        writer.write_u8(self.safety_car_status.to_raw())?;
+        writer.write_u8(self.network_game.to_raw())?;
+        writer.write_u8(self.num_weather_forecast_samples)?;
+        for sample in &self.weather_forecast_samples {
+            sample.write_to(writer)?;
+        }
+        writer.write_u8(self.forecast_accuracy)?;
+        writer.write_u8(self.ai_difficulty)?;
+        writer.write_u32::<LittleEndian>(self.season_link_identifier)?;
+        writer.write_u32::<LittleEndian>(self.weekend_link_identifier)?;
+        writer.write_u32::<LittleEndian>(self.session_link_identifier)?;
+        writer.write_u8(self.pit_stop_window_ideal_lap)?;
+        writer.write_u8(self.pit_stop_window_latest_lap)?;
+        writer.write_u8(self.pit_stop_rejoin_position)?;
+        writer.write_u8(self.steering_assist as u8)?;
+        writer.write_u8(self.braking_assist as u8)?;
+        writer.write_u8(self.gearbox_assist.to_raw())?;
+        writer.write_u8(self.pit_assist as u8)?;
+        writer.write_u8(self.pit_release_assist as u8)?;
+        writer.write_u8(self.ers_assist as u8)?;
+        writer.write_u8(self.drs_assist as u8)?;
+        writer.write_u8(self.dynamic_racing_line.to_raw())?;
+        writer.write_u8(self.dynamic_racing_line_type.to_raw())?;
+        writer.write_u8(self.game_mode.to_raw())?;
+        writer.write_u8(self.rule_set.to_raw())?;
+        writer.write_u32::<LittleEndian>(self.time_of_day)?;
+        writer.write_u8(self.session_length.to_raw())?;
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,15 +1,13 @@
-use std::io::Read;
-use crate::models::PacketHeader;
-use byteorder::{LittleEndian, ReadBytesExt};
-use num_traits::FromPrimitive;
-use crate::models::enums::{ActualTyreCompound, VisualTyreCompound};
+use std::io::{self, Read, Write};
+use crate::models::{PacketHeader, PacketReader};
+use crate::error::ParseError;
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::enums::{ActualTyreCompound, GameEnum, VisualTyreCompound};
 use crate::models::traits::Packet;
 use crate::models::types::ValidLaps;
-use crate::event_system::{Signal, Receiver};
-use crate::event_system;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct LapHistory {
     pub lap_time: u32,
     pub sector_1_time: u16,
@@ -19,18 +17,33 @@ pub struct LapHistory {
 }
 
 impl LapHistory {
-    pub fn new<R: Read>(reader: &mut R) -> Result<LapHistory, std::io::Error> {
+    /// Times of the three sectors in milliseconds.
+    pub fn sector_times(&self) -> [u32; 3] {
+        [self.sector_1_time as u32, self.sector_2_time as u32, self.sector_3_time as u32]
+    }
+
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<LapHistory, ParseError> {
         Ok(LapHistory {
-            lap_time: reader.read_u32::<LittleEndian>()?,
-            sector_1_time: reader.read_u16::<LittleEndian>()?,
-            sector_2_time: reader.read_u16::<LittleEndian>()?,
-            sector_3_time: reader.read_u16::<LittleEndian>()?,
+            lap_time: reader.read_u32("lap_time")?,
+            sector_1_time: reader.read_u16("sector_1_time")?,
+            sector_2_time: reader.read_u16("sector_2_time")?,
+            sector_3_time: reader.read_u16("sector_3_time")?,
             lap_valid_bit_flags: ValidLaps::new(reader)?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u32::<LittleEndian>(self.lap_time)?;
+        writer.write_u16::<LittleEndian>(self.sector_1_time)?;
+        writer.write_u16::<LittleEndian>(self.sector_2_time)?;
+        writer.write_u16::<LittleEndian>(self.sector_3_time)?;
+        self.lap_valid_bit_flags.write_to(writer)?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct TyreStintHistory {
     pub end_lap: u8,
     pub tyre_actual_compound: ActualTyreCompound,
@@ -38,16 +51,24 @@ pub struct TyreStintHistory {
 }
 
 impl TyreStintHistory {
-    pub fn new<R: Read>(reader: &mut R) -> Result<TyreStintHistory, std::io::Error> {
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<TyreStintHistory, ParseError> {
         Ok(TyreStintHistory {
-            end_lap: reader.read_u8()?,
-            tyre_actual_compound: ActualTyreCompound::from_u8(reader.read_u8()?).unwrap(),
-            tyre_visual_compound: VisualTyreCompound::from_u8(reader.read_u8()?).unwrap(),
+            end_lap: reader.read_u8("end_lap")?,
+            tyre_actual_compound: reader.read_enum("tyre_actual_compound")?,
+            tyre_visual_compound: reader.read_enum("tyre_visual_compound")?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.end_lap)?;
+        writer.write_u8(self.tyre_actual_compound.to_raw())?;
+        writer.write_u8(self.tyre_visual_compound.to_raw())?;
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PacketSessionHistory {
     pub header: PacketHeader,
     pub car_idx: u8,
@@ -61,21 +82,19 @@ pub struct PacketSessionHistory {
     pub tyre_stints_history: Vec<TyreStintHistory>,
 }
 
-event_system::signal!(SessionHistorySignal<SessionHistoryReceiver, PacketSessionHistory> = []);
-
 impl Packet for PacketSessionHistory {
     const PACKET_ID: u8 = 11;
     const PACKET_SIZE: usize = 1155;
 
-    fn new<R: Read>(reader: &mut R) -> Result<PacketSessionHistory, std::io::Error> {
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<PacketSessionHistory, ParseError> {
         let header = PacketHeader::new(reader)?;
-        let car_idx = reader.read_u8()?;
-        let num_laps = reader.read_u8()?;
-        let num_tyre_stints = reader.read_u8()?;
-        let best_lap_time_lap_num = reader.read_u8()?;
-        let best_sector1_time_lap_num = reader.read_u8()?;
-        let best_sector2_time_lap_num = reader.read_u8()?;
-        let best_sector3_time_lap_num = reader.read_u8()?;
+        let car_idx = reader.read_u8("car_idx")?;
+        let num_laps = reader.read_u8("num_laps")?;
+        let num_tyre_stints = reader.read_u8("num_tyre_stints")?;
+        let best_lap_time_lap_num = reader.read_u8("best_lap_time_lap_num")?;
+        let best_sector1_time_lap_num = reader.read_u8("best_sector1_time_lap_num")?;
+        let best_sector2_time_lap_num = reader.read_u8("best_sector2_time_lap_num")?;
+        let best_sector3_time_lap_num = reader.read_u8("best_sector3_time_lap_num")?;
         let mut lap_history_data = Vec::new();
         for _ in 0..100 {
             lap_history_data.push(LapHistory::new(reader)?);
@@ -98,5 +117,22 @@ impl Packet for PacketSessionHistory {
         })
     }
 
-    event_system::signal_fns!();
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.write_to(writer)?;
+        writer.write_u8(self.car_idx)?;
+        writer.write_u8(self.num_laps)?;
+        writer.write_u8(self.num_tyre_stints)?;
+        writer.write_u8(self.best_lap_time_lap_num)?;
+        writer.write_u8(self.best_sector1_time_lap_num)?;
+        writer.write_u8(self.best_sector2_time_lap_num)?;
+        writer.write_u8(self.best_sector3_time_lap_num)?;
+        for lap_history in &self.lap_history_data {
+            lap_history.write_to(writer)?;
+        }
+        for tyre_stint in &self.tyre_stints_history {
+            tyre_stint.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,16 +1,44 @@
-use std::io::Read;
+use std::io::{self, Read, Write};
+use crate::error::ParseError;
+use crate::event_system::Dispatcher;
+use crate::models::{DecodeMode, PacketReader};
 
-pub trait Packet {
+pub trait Packet: 'static {
     const PACKET_ID: u8;
     const PACKET_SIZE: usize;
 
-    fn new<R: Read>(reader: &mut R) -> Result<Self, std::io::Error> where Self: Sized;
+    fn decode<R: Read>(reader: &mut PacketReader<R>) -> Result<Self, ParseError> where Self: Sized;
+
+    /// Decodes the packet, rejecting values that don't match any known enum variant.
+    fn new<R: Read>(reader: &mut R) -> Result<Self, ParseError> where Self: Sized {
+        Self::decode(&mut PacketReader::new(reader)).map_err(|e| e.with_packet_id(Self::PACKET_ID))
+    }
+
+    /// Decodes the packet, keeping unknown enum values in their `Unknown` variant.
+    fn new_lenient<R: Read>(reader: &mut R) -> Result<Self, ParseError> where Self: Sized {
+        Self::decode(&mut PacketReader::with_mode(reader, DecodeMode::Lenient)).map_err(|e| e.with_packet_id(Self::PACKET_ID))
+    }
+
+    /// Encodes the packet in the same format the game sends it in.
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::PACKET_SIZE);
+        self.write_to(&mut bytes).expect("writing to a Vec can't fail");
+        bytes
+    }
 
     fn get_packet_size() -> usize {
         Self::PACKET_SIZE
     }
 
-    fn emit(self);
+    /// Emits the packet to the handlers registered on [`Dispatcher::global`].
+    fn emit(self) where Self: Sized {
+        Dispatcher::global().emit(&self)
+    }
 
-    fn listen<F>(callable: F) where F: Fn(&Self) -> () + 'static;
+    /// Registers `callable` on [`Dispatcher::global`] for as long as the process runs.
+    fn listen<F>(callable: F) where Self: Sized, F: Fn(&Self) + Send + Sync + 'static {
+        Dispatcher::global().subscribe(callable).detach()
+    }
 }
\ No newline at end of file
@@ -1,20 +1,26 @@
-use std::io::Read;
-use byteorder::ReadBytesExt;
+use std::io::{self, Read, Write};
+use byteorder::WriteBytesExt;
+use crate::error::ParseError;
+use crate::models::PacketReader;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct ValidLaps {
     pub bit_flags: u8,
 }
 
 impl ValidLaps {
-    pub fn new<R: Read>(reader: &mut R) -> Result<ValidLaps, std::io::Error> {
-        let bit_flags = reader.read_u8()?;
+    pub fn new<R: Read>(reader: &mut PacketReader<R>) -> Result<ValidLaps, ParseError> {
+        let bit_flags = reader.read_u8("lap_valid_bit_flags")?;
         Ok(ValidLaps {
             bit_flags,
         })
     }
 
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.bit_flags)
+    }
+
     pub fn is_valid_lap(&self) -> bool {
         self.bit_flags & 0x01 == 0x01
     }
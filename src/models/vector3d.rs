@@ -1,8 +1,10 @@
-use std::io::{Error, Read};
-use byteorder::{ReadBytesExt, LittleEndian};
+use std::io::{self, Read, Write};
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::PacketReader;
+use crate::error::ParseError;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct Vector3D<T> {
     pub x: T,
     pub y: T,
@@ -20,21 +22,33 @@ impl<T> Vector3D<T> {
 }
 
 impl Vector3D<f32> {
-    pub fn read_from<R: Read>(reader: &mut R) -> Result<Vector3D<f32>, Error> {
-        let x = reader.read_f32::<LittleEndian>()?;
-        let y = reader.read_f32::<LittleEndian>()?;
-        let z = reader.read_f32::<LittleEndian>()?;
+    pub fn read_from<R: Read>(reader: &mut PacketReader<R>, field: &'static str) -> Result<Vector3D<f32>, ParseError> {
+        let x = reader.read_f32(field)?;
+        let y = reader.read_f32(field)?;
+        let z = reader.read_f32(field)?;
 
         Ok(Vector3D::new(x, y, z))
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_f32::<LittleEndian>(self.x)?;
+        writer.write_f32::<LittleEndian>(self.y)?;
+        writer.write_f32::<LittleEndian>(self.z)
+    }
 }
 
 impl Vector3D<i16> {
-    pub fn read_from<R: Read>(reader: &mut R) -> Result<Vector3D<i16>, Error> {
-        let x = reader.read_i16::<LittleEndian>()?;
-        let y = reader.read_i16::<LittleEndian>()?;
-        let z = reader.read_i16::<LittleEndian>()?;
+    pub fn read_from<R: Read>(reader: &mut PacketReader<R>, field: &'static str) -> Result<Vector3D<i16>, ParseError> {
+        let x = reader.read_i16(field)?;
+        let y = reader.read_i16(field)?;
+        let z = reader.read_i16(field)?;
 
         Ok(Vector3D::new(x, y, z))
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_i16::<LittleEndian>(self.x)?;
+        writer.write_i16::<LittleEndian>(self.y)?;
+        writer.write_i16::<LittleEndian>(self.z)
+    }
 }
\ No newline at end of file
@@ -1,11 +1,11 @@
-use std::io::Error;
-use std::io::Read;
-use byteorder::{ReadBytesExt, LittleEndian};
-use num_traits::FromPrimitive;
-use crate::models::enums::SurfaceType;
+use std::io::{self, Read, Write};
+use byteorder::{LittleEndian, WriteBytesExt};
+use crate::models::enums::{GameEnum, SurfaceType};
+use crate::models::PacketReader;
+use crate::error::ParseError;
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct WheelsVector<T> {
     pub rear_left: T,
     pub rear_right: T,
@@ -14,36 +14,63 @@ pub struct WheelsVector<T> {
 }
 
 impl WheelsVector<f32> {
-    pub fn read_from<R: Read>(reader: &mut R) -> Result<WheelsVector<f32>, Error> {
+    pub fn read_from<R: Read>(reader: &mut PacketReader<R>, field: &'static str) -> Result<WheelsVector<f32>, ParseError> {
         Ok(WheelsVector {
-            rear_left: reader.read_f32::<LittleEndian>()?,
-            rear_right: reader.read_f32::<LittleEndian>()?,
-            front_left: reader.read_f32::<LittleEndian>()?,
-            front_right: reader.read_f32::<LittleEndian>()?,
+            rear_left: reader.read_f32(field)?,
+            rear_right: reader.read_f32(field)?,
+            front_left: reader.read_f32(field)?,
+            front_right: reader.read_f32(field)?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_f32::<LittleEndian>(self.rear_left)?;
+        writer.write_f32::<LittleEndian>(self.rear_right)?;
+        writer.write_f32::<LittleEndian>(self.front_left)?;
+        writer.write_f32::<LittleEndian>(self.front_right)?;
+
+        Ok(())
+    }
 }
 
 impl WheelsVector<u16> {
-    pub fn read_from<R: Read>(reader: &mut R) -> Result<WheelsVector<u16>, Error> {
+    pub fn read_from<R: Read>(reader: &mut PacketReader<R>, field: &'static str) -> Result<WheelsVector<u16>, ParseError> {
         Ok(WheelsVector {
-            rear_left: reader.read_u16::<LittleEndian>()?,
-            rear_right: reader.read_u16::<LittleEndian>()?,
-            front_left: reader.read_u16::<LittleEndian>()?,
-            front_right: reader.read_u16::<LittleEndian>()?,
+            rear_left: reader.read_u16(field)?,
+            rear_right: reader.read_u16(field)?,
+            front_left: reader.read_u16(field)?,
+            front_right: reader.read_u16(field)?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u16::<LittleEndian>(self.rear_left)?;
+        writer.write_u16::<LittleEndian>(self.rear_right)?;
+        writer.write_u16::<LittleEndian>(self.front_left)?;
+        writer.write_u16::<LittleEndian>(self.front_right)?;
+
+        Ok(())
+    }
 }
 
 impl WheelsVector<u8> {
-    pub fn read_from<R: Read>(reader: &mut R) -> Result<WheelsVector<u8>, Error> {
+    pub fn read_from<R: Read>(reader: &mut PacketReader<R>, field: &'static str) -> Result<WheelsVector<u8>, ParseError> {
         Ok(WheelsVector {
-            rear_left: reader.read_u8()?,
-            rear_right: reader.read_u8()?,
-            front_left: reader.read_u8()?,
-            front_right: reader.read_u8()?,
+            rear_left: reader.read_u8(field)?,
+            rear_right: reader.read_u8(field)?,
+            front_left: reader.read_u8(field)?,
+            front_right: reader.read_u8(field)?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.rear_left)?;
+        writer.write_u8(self.rear_right)?;
+        writer.write_u8(self.front_left)?;
+        writer.write_u8(self.front_right)?;
+
+        Ok(())
+    }
 }
 
 impl<T> WheelsVector<T> {
@@ -58,12 +85,21 @@ impl<T> WheelsVector<T> {
 }
 
 impl WheelsVector<SurfaceType> {
-    pub fn read_from<R: Read>(reader: &mut R) -> Result<WheelsVector<SurfaceType>, Error> {
+    pub fn read_from<R: Read>(reader: &mut PacketReader<R>, field: &'static str) -> Result<WheelsVector<SurfaceType>, ParseError> {
         Ok(WheelsVector {
-            rear_left: SurfaceType::from_u8(reader.read_u8()?).unwrap(),
-            rear_right: SurfaceType::from_u8(reader.read_u8()?).unwrap(),
-            front_left: SurfaceType::from_u8(reader.read_u8()?).unwrap(),
-            front_right: SurfaceType::from_u8(reader.read_u8()?).unwrap(),
+            rear_left: reader.read_enum(field)?,
+            rear_right: reader.read_enum(field)?,
+            front_left: reader.read_enum(field)?,
+            front_right: reader.read_enum(field)?,
         })
     }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.rear_left.to_raw())?;
+        writer.write_u8(self.rear_right.to_raw())?;
+        writer.write_u8(self.front_left.to_raw())?;
+        writer.write_u8(self.front_right.to_raw())?;
+
+        Ok(())
+    }
 }
\ No newline at end of file
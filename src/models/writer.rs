@@ -0,0 +1,9 @@
+use std::io::{self, Write};
+
+/// Writes `value` into a fixed size field of `N` bytes, padding it with zeros.
+pub fn write_string<W: Write, const N: usize>(writer: &mut W, value: &str) -> io::Result<()> {
+    let mut bytes = [0; N];
+    let len = value.len().min(N);
+    bytes[..len].copy_from_slice(&value.as_bytes()[..len]);
+    writer.write_all(&bytes)
+}
\ No newline at end of file
@@ -0,0 +1,281 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::frames::PacketKind;
+use crate::models::EventDetails;
+use crate::models::enums::{PitStatus, ResultStatus};
+use crate::packets::Packets;
+use crate::telemetry::{Frame, Lap};
+
+/// A car passed another car on track.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Overtake {
+    pub attacker: u8,
+    pub defender: u8,
+    /// Lap of the attacker.
+    pub lap: u8,
+    /// Lap distance of the attacker in metres.
+    pub lap_distance: f32,
+    pub drs_active: bool,
+}
+
+/// Why two cars swapped positions without one passing the other on track.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum SwapCause {
+    /// One of the cars was in the pit lane.
+    Pit,
+    /// The car that lost the position is no longer racing.
+    Retirement,
+    /// The car that gained the position is still behind on distance, the order changed by a
+    /// penalty.
+    Penalty,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct PositionSwap {
+    pub gained: u8,
+    pub lost: u8,
+    /// Lap of the car that gained the position.
+    pub lap: u8,
+    pub cause: SwapCause,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum PositionEvent {
+    Overtake(Overtake),
+    Swap(PositionSwap),
+}
+
+impl PositionEvent {
+    /// Emits the contained event to the handlers of its concrete type.
+    pub fn emit_to(&self, dispatcher: &Dispatcher) {
+        match self {
+            PositionEvent::Overtake(event) => dispatcher.emit(event),
+            PositionEvent::Swap(event) => dispatcher.emit(event),
+        }
+    }
+}
+
+fn in_pit_lane(lap: &Lap) -> bool {
+    lap.pit_status != PitStatus::None
+}
+
+fn racing(lap: &Lap) -> bool {
+    lap.result_status == ResultStatus::Active
+}
+
+/// Detects position changes between successive lap data packets.
+///
+/// Two cars that swapped positions are an overtake when the car that gained the position is
+/// ahead on total distance while both are racing on track. The game can update the positions a
+/// few packets after the distances crossed, so only the current distances are compared. A flashback forgets the previous positions, so going back in
+/// time doesn't count as position changes.
+#[derive(Debug, Clone, Default)]
+pub struct OvertakeDetector {
+    frame: Option<Frame>,
+    overtakes: Vec<Overtake>,
+}
+
+impl OvertakeDetector {
+    pub fn new() -> OvertakeDetector {
+        OvertakeDetector::default()
+    }
+
+    /// Creates a detector fed by every packet dispatched by `dispatcher`, its events are emitted
+    /// back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<OvertakeDetector>>, Subscription) {
+        OvertakeDetector::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<OvertakeDetector>>, Subscription) {
+        event_system::attach(self, dispatcher, OvertakeDetector::ingest, PositionEvent::emit_to)
+    }
+
+    /// The overtakes of the session in the order they happened.
+    pub fn overtakes(&self) -> &[Overtake] {
+        &self.overtakes
+    }
+
+    pub fn reset(&mut self) {
+        *self = OvertakeDetector::default();
+    }
+
+    /// Updates the detector and returns the position changes caused by `packet`.
+    pub fn ingest(&mut self, packet: &Packets) -> Vec<PositionEvent> {
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return vec![],
+        };
+        if self.frame.as_ref().is_some_and(|frame| frame.session_uid != header.session_uid) {
+            self.reset();
+        }
+
+        let frame = self.frame.get_or_insert_with(|| Frame::new(header));
+        if matches!(packet.event_details(), Some(EventDetails::Flashback(_))) {
+            frame.cars.iter_mut().for_each(|car| car.lap = None);
+            return vec![];
+        }
+
+        let previous: Vec<Option<Lap>> = frame.cars.iter().map(|car| car.lap).collect();
+        if !frame.update(packet) || PacketKind::of(packet) != Some(PacketKind::LapData) {
+            return vec![];
+        }
+
+        let mut events = vec![];
+        for (gained, car) in frame.cars.iter().enumerate() {
+            let (before, now) = match (previous[gained], car.lap) {
+                (Some(before), Some(now)) => (before, now),
+                _ => continue,
+            };
+
+            for (lost, other) in frame.cars.iter().enumerate() {
+                let (other_before, other_now) = match (previous[lost], other.lap) {
+                    (Some(before), Some(now)) if lost != gained => (before, now),
+                    _ => continue,
+                };
+                // Position 0 marks unused entries.
+                if [before, now, other_before, other_now].iter().any(|lap| lap.car_position == 0) {
+                    continue;
+                }
+                if before.car_position < other_before.car_position || now.car_position > other_now.car_position {
+                    continue;
+                }
+
+                let passed = now.total_distance > other_now.total_distance;
+                let cause = if [before, now, other_before, other_now].iter().any(in_pit_lane) {
+                    Some(SwapCause::Pit)
+                } else if !racing(&other_now) {
+                    Some(SwapCause::Retirement)
+                } else if !passed {
+                    Some(SwapCause::Penalty)
+                } else {
+                    None
+                };
+
+                events.push(match cause {
+                    Some(cause) => PositionEvent::Swap(PositionSwap {
+                        gained: gained as u8,
+                        lost: lost as u8,
+                        lap: now.current_lap_num,
+                        cause,
+                    }),
+                    None => PositionEvent::Overtake(Overtake {
+                        attacker: gained as u8,
+                        defender: lost as u8,
+                        lap: now.current_lap_num,
+                        lap_distance: now.lap_distance,
+                        drs_active: car.telemetry.is_some_and(|telemetry| telemetry.drs),
+                    }),
+                });
+            }
+        }
+
+        self.overtakes.extend(events.iter().filter_map(|event| match event {
+            PositionEvent::Overtake(overtake) => Some(*overtake),
+            PositionEvent::Swap(_) => None,
+        }));
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CarTelemetryPacket, PacketLapData};
+    use crate::testing::packet;
+
+    struct Car {
+        position: u8,
+        total_distance: f32,
+        pit_status: PitStatus,
+        result_status: ResultStatus,
+    }
+
+    fn car(position: u8, total_distance: f32) -> Car {
+        Car { position, total_distance, pit_status: PitStatus::None, result_status: ResultStatus::Active }
+    }
+
+    fn lap_data(cars: &[Car]) -> Packets {
+        let mut packet = packet::<PacketLapData>(0.0);
+        for (lap_data, car) in packet.lap_data.iter_mut().zip(cars) {
+            lap_data.lap_distance = car.total_distance % 5000.0;
+            lap_data.total_distance = car.total_distance;
+            lap_data.car_position = car.position;
+            lap_data.current_lap_num = 2;
+            lap_data.pit_status = car.pit_status;
+            lap_data.result_status = car.result_status;
+        }
+        Packets::LapData(packet)
+    }
+
+    #[test]
+    fn detects_passes_on_track() {
+        let mut detector = OvertakeDetector::new();
+
+        let mut telemetry = packet::<CarTelemetryPacket>(0.0);
+        telemetry.car_telemetry_data[1].drs = true;
+        detector.ingest(&Packets::CarTelemetry(telemetry));
+
+        detector.ingest(&lap_data(&[car(1, 6000.0), car(2, 5990.0), car(3, 5900.0)]));
+        let events = detector.ingest(&lap_data(&[car(2, 6010.0), car(1, 6015.0), car(3, 5910.0)]));
+
+        assert_eq!(events, [PositionEvent::Overtake(Overtake {
+            attacker: 1,
+            defender: 0,
+            lap: 2,
+            lap_distance: 1015.0,
+            drs_active: true,
+        })]);
+        assert_eq!(detector.overtakes().len(), 1);
+    }
+
+    #[test]
+    fn detects_passes_with_lagging_positions() {
+        let mut detector = OvertakeDetector::new();
+        detector.ingest(&lap_data(&[car(1, 6000.0), car(2, 5990.0)]));
+
+        // The distances cross a packet before the positions are updated.
+        let events = detector.ingest(&lap_data(&[car(1, 6010.0), car(2, 6015.0)]));
+        assert!(events.is_empty());
+        let events = detector.ingest(&lap_data(&[car(2, 6020.0), car(1, 6030.0)]));
+
+        assert!(matches!(events[..], [PositionEvent::Overtake(Overtake { attacker: 1, defender: 0, .. })]));
+        assert_eq!(detector.overtakes().len(), 1);
+    }
+
+    #[test]
+    fn distinguishes_pits_retirements_and_penalties() {
+        let mut detector = OvertakeDetector::new();
+        detector.ingest(&lap_data(&[car(1, 300.0), car(2, 200.0), car(3, 100.0), car(4, 50.0)]));
+
+        let events = detector.ingest(&lap_data(&[
+            Car { pit_status: PitStatus::Pitting, ..car(2, 310.0) },
+            car(1, 320.0),
+            Car { result_status: ResultStatus::Retired, ..car(4, 100.0) },
+            car(3, 60.0),
+        ]));
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], PositionEvent::Swap(PositionSwap { gained: 1, lost: 0, cause: SwapCause::Pit, .. })));
+        assert!(matches!(events[1], PositionEvent::Swap(PositionSwap { gained: 3, lost: 2, cause: SwapCause::Retirement, .. })));
+
+        let events = detector.ingest(&lap_data(&[
+            Car { pit_status: PitStatus::Pitting, ..car(2, 320.0) },
+            car(1, 330.0),
+            Car { result_status: ResultStatus::Retired, ..car(4, 100.0) },
+            car(3, 70.0),
+        ]));
+        assert!(events.is_empty());
+
+        let events = detector.ingest(&lap_data(&[
+            Car { pit_status: PitStatus::Pitting, ..car(3, 330.0) },
+            car(2, 340.0),
+            Car { result_status: ResultStatus::Retired, ..car(4, 100.0) },
+            car(1, 80.0),
+        ]));
+        assert!(matches!(events[..], [
+            PositionEvent::Swap(PositionSwap { gained: 3, lost: 0, cause: SwapCause::Pit, .. }),
+            PositionEvent::Swap(PositionSwap { gained: 3, lost: 1, cause: SwapCause::Penalty, .. }),
+        ]));
+        assert!(detector.overtakes().is_empty());
+    }
+}
@@ -1,6 +1,167 @@
-use crate::models::{CarSetupPacket, CarTelemetryPacket, MotionPacket, PacketCarDamage, PacketCarStatus, PacketEventData, PacketFinalClassificationData, PacketLapData, PacketLobbyInfo, PacketSessionHistory, ParticipantPacket, SessionDataPacket};
+use crate::models::{CarDamage, CarMotionData, CarSetup, CarStatusData, CarTelemetryData, ClassificationData, LapData, LobbyInfo, ParticipantData};
+use crate::models::{CarSetupPacket, PacketHeader, CarTelemetryPacket, MotionPacket, PacketCarDamage, PacketCarStatus, PacketEventData, PacketFinalClassificationData, PacketLapData, PacketLobbyInfo, PacketSessionHistory, ParticipantPacket, SessionDataPacket};
+use crate::models::{f1_2021, f1_2023, EventDetails};
 use crate::models::traits::Packet;
+use crate::event_system::Dispatcher;
+use std::io::{self, Read, Write};
+use crate::error::{ParseError, ParseErrorKind};
+use crate::models::{DecodeMode, PacketReader};
+use serde::Serialize;
 
+/// Names of the known packet types by packet id, as returned by [`Packets::name`].
+pub const PACKET_NAMES: [(u8, &str); 14] = [
+    (MotionPacket::PACKET_ID, "motion"),
+    (SessionDataPacket::PACKET_ID, "session"),
+    (PacketLapData::PACKET_ID, "lap_data"),
+    (PacketEventData::PACKET_ID, "event"),
+    (ParticipantPacket::PACKET_ID, "participants"),
+    (CarSetupPacket::PACKET_ID, "car_setups"),
+    (CarTelemetryPacket::PACKET_ID, "car_telemetry"),
+    (PacketCarStatus::PACKET_ID, "car_status"),
+    (PacketFinalClassificationData::PACKET_ID, "final_classification"),
+    (PacketLobbyInfo::PACKET_ID, "lobby_info"),
+    (PacketCarDamage::PACKET_ID, "car_damage"),
+    (PacketSessionHistory::PACKET_ID, "session_history"),
+    (f1_2023::PacketTyreSets::PACKET_ID, "tyre_sets"),
+    (f1_2023::MotionExPacket::PACKET_ID, "motion_ex"),
+];
+
+/// Size in bytes of the known packet types by packet id.
+pub const PACKET_SIZES: [(u8, usize); 12] = [
+    (MotionPacket::PACKET_ID, MotionPacket::PACKET_SIZE),
+    (SessionDataPacket::PACKET_ID, SessionDataPacket::PACKET_SIZE),
+    (PacketLapData::PACKET_ID, PacketLapData::PACKET_SIZE),
+    (PacketEventData::PACKET_ID, PacketEventData::PACKET_SIZE),
+    (ParticipantPacket::PACKET_ID, ParticipantPacket::PACKET_SIZE),
+    (CarSetupPacket::PACKET_ID, CarSetupPacket::PACKET_SIZE),
+    (CarTelemetryPacket::PACKET_ID, CarTelemetryPacket::PACKET_SIZE),
+    (PacketCarStatus::PACKET_ID, PacketCarStatus::PACKET_SIZE),
+    (PacketFinalClassificationData::PACKET_ID, PacketFinalClassificationData::PACKET_SIZE),
+    (PacketLobbyInfo::PACKET_ID, PacketLobbyInfo::PACKET_SIZE),
+    (PacketCarDamage::PACKET_ID, PacketCarDamage::PACKET_SIZE),
+    (PacketSessionHistory::PACKET_ID, PacketSessionHistory::PACKET_SIZE),
+];
+
+/// Size in bytes of the F1 2021 packet types by packet id.
+pub const PACKET_SIZES_2021: [(u8, usize); 12] = [
+    (MotionPacket::PACKET_ID, MotionPacket::PACKET_SIZE),
+    (f1_2021::SessionDataPacket::PACKET_ID, f1_2021::SessionDataPacket::PACKET_SIZE),
+    (f1_2021::PacketLapData::PACKET_ID, f1_2021::PacketLapData::PACKET_SIZE),
+    (f1_2021::PacketEventData::PACKET_ID, f1_2021::PacketEventData::PACKET_SIZE),
+    (ParticipantPacket::PACKET_ID, ParticipantPacket::PACKET_SIZE),
+    (CarSetupPacket::PACKET_ID, CarSetupPacket::PACKET_SIZE),
+    (CarTelemetryPacket::PACKET_ID, CarTelemetryPacket::PACKET_SIZE),
+    (PacketCarStatus::PACKET_ID, PacketCarStatus::PACKET_SIZE),
+    (f1_2021::PacketFinalClassificationData::PACKET_ID, f1_2021::PacketFinalClassificationData::PACKET_SIZE),
+    (PacketLobbyInfo::PACKET_ID, PacketLobbyInfo::PACKET_SIZE),
+    (f1_2021::PacketCarDamage::PACKET_ID, f1_2021::PacketCarDamage::PACKET_SIZE),
+    (PacketSessionHistory::PACKET_ID, PacketSessionHistory::PACKET_SIZE),
+];
+
+/// Bytes the F1 23 header adds to packets that otherwise share their layout with F1 2022.
+const HEADER_2023_EXTRA: usize = PacketHeader::PACKET_SIZE_2023 - PacketHeader::PACKET_SIZE;
+
+/// Size in bytes of the F1 23 packet types by packet id.
+pub const PACKET_SIZES_2023: [(u8, usize); 14] = [
+    (f1_2023::MotionPacket::PACKET_ID, f1_2023::MotionPacket::PACKET_SIZE),
+    (f1_2023::SessionDataPacket::PACKET_ID, f1_2023::SessionDataPacket::PACKET_SIZE),
+    (f1_2023::PacketLapData::PACKET_ID, f1_2023::PacketLapData::PACKET_SIZE),
+    (f1_2023::PacketEventData::PACKET_ID, f1_2023::PacketEventData::PACKET_SIZE),
+    (f1_2023::ParticipantPacket::PACKET_ID, f1_2023::ParticipantPacket::PACKET_SIZE),
+    (CarSetupPacket::PACKET_ID, CarSetupPacket::PACKET_SIZE + HEADER_2023_EXTRA),
+    (CarTelemetryPacket::PACKET_ID, CarTelemetryPacket::PACKET_SIZE + HEADER_2023_EXTRA),
+    (f1_2023::PacketCarStatus::PACKET_ID, f1_2023::PacketCarStatus::PACKET_SIZE),
+    (PacketFinalClassificationData::PACKET_ID, PacketFinalClassificationData::PACKET_SIZE + HEADER_2023_EXTRA),
+    (f1_2023::PacketLobbyInfo::PACKET_ID, f1_2023::PacketLobbyInfo::PACKET_SIZE),
+    (PacketCarDamage::PACKET_ID, PacketCarDamage::PACKET_SIZE + HEADER_2023_EXTRA),
+    (f1_2023::PacketSessionHistory::PACKET_ID, f1_2023::PacketSessionHistory::PACKET_SIZE),
+    (f1_2023::PacketTyreSets::PACKET_ID, f1_2023::PacketTyreSets::PACKET_SIZE),
+    (f1_2023::MotionExPacket::PACKET_ID, f1_2023::MotionExPacket::PACKET_SIZE),
+];
+
+/// Largest datagram the game sends is the motion packet, this leaves some headroom on top of it.
+pub const MAX_DATAGRAM_SIZE: usize = 2048;
+
+pub fn packet_size(packet_id: u8) -> Option<usize> {
+    PACKET_SIZES.iter().find(|(id, _)| *id == packet_id).map(|(_, size)| *size)
+}
+
+/// Size of a packet type in the given `packet_format`, `None` for unknown formats and packet ids.
+pub fn packet_size_for_format(packet_format: u16, packet_id: u8) -> Option<usize> {
+    let sizes = match packet_format {
+        2021 => &PACKET_SIZES_2021[..],
+        2022 => &PACKET_SIZES[..],
+        2023 => &PACKET_SIZES_2023[..],
+        _ => return None,
+    };
+    sizes.iter().find(|(id, _)| *id == packet_id).map(|(_, size)| *size)
+}
+
+pub fn packet_name(packet_id: u8) -> Option<&'static str> {
+    PACKET_NAMES.iter().find(|(id, _)| *id == packet_id).map(|(_, name)| *name)
+}
+
+pub fn packet_id_by_name(name: &str) -> Option<u8> {
+    PACKET_NAMES.iter().find(|(_, known)| *known == name).map(|(id, _)| *id)
+}
+
+/// Decodes a whole datagram of any supported game, rejecting unknown enum values.
+///
+/// The decoder is chosen by the packet format in the header. The length must match the size of
+/// the packet type, datagrams of unknown packet types decode into [`Packets::Unknown`].
+pub fn decode(datagram: &[u8]) -> Result<Packets, ParseError> {
+    decode_with_mode(datagram, DecodeMode::Strict)
+}
+
+pub fn decode_with_mode(datagram: &[u8], mode: DecodeMode) -> Result<Packets, ParseError> {
+    let header = PacketHeader::new(&mut PacketReader::new(datagram))?;
+    if !matches!(header.packet_format, 2021..=2023) {
+        return Err(ParseError::new(0, "packet_format", ParseErrorKind::UnsupportedFormat(header.packet_format)));
+    }
+
+    if let Some(expected) = packet_size_for_format(header.packet_format, header.packet_id) {
+        let actual = datagram.len();
+        let kind = match actual {
+            actual if actual < expected => Some(ParseErrorKind::Truncated { expected, actual }),
+            actual if actual > expected => Some(ParseErrorKind::Oversized { expected, actual }),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            return Err(ParseError::new(actual.min(expected), "datagram", kind).with_packet_id(header.packet_id));
+        }
+    }
+
+    let reader = &mut PacketReader::with_mode(datagram, mode);
+    match header.packet_format {
+        2021 => Packets::read_from_2021(header.packet_id, reader),
+        2023 => Packets::read_from_2023(header.packet_id, reader),
+        _ => Packets::read_from(header.packet_id, reader),
+    }
+}
+
+/// The entry of a single car in a packet that holds data for every car.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum CarData<'a> {
+    Motion(&'a CarMotionData),
+    LapData(&'a LapData),
+    Participant(&'a ParticipantData),
+    CarSetup(&'a CarSetup),
+    CarTelemetry(&'a CarTelemetryData),
+    CarStatus(&'a CarStatusData),
+    Classification(&'a ClassificationData),
+    LobbyInfo(&'a LobbyInfo),
+    CarDamage(&'a CarDamage),
+    Classification2021(&'a f1_2021::ClassificationData),
+    CarDamage2021(&'a f1_2021::CarDamage),
+    LapData2023(&'a f1_2023::LapData),
+    Participant2023(&'a f1_2023::ParticipantData),
+    CarStatus2023(&'a f1_2023::CarStatusData),
+    LobbyInfo2023(&'a f1_2023::LobbyInfo),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum Packets {
     Motion(MotionPacket),
     Session(SessionDataPacket),
@@ -14,25 +175,454 @@ pub enum Packets {
     LobbyInfo(PacketLobbyInfo),
     CarDamage(PacketCarDamage),
     SessionHistory(PacketSessionHistory),
+    Session2021(f1_2021::SessionDataPacket),
+    LapData2021(f1_2021::PacketLapData),
+    Event2021(f1_2021::PacketEventData),
+    FinalClassification2021(f1_2021::PacketFinalClassificationData),
+    CarDamage2021(f1_2021::PacketCarDamage),
+    Motion2023(f1_2023::MotionPacket),
+    Session2023(f1_2023::SessionDataPacket),
+    LapData2023(f1_2023::PacketLapData),
+    Event2023(f1_2023::PacketEventData),
+    Participants2023(f1_2023::ParticipantPacket),
+    CarStatus2023(f1_2023::PacketCarStatus),
+    LobbyInfo2023(f1_2023::PacketLobbyInfo),
+    SessionHistory2023(f1_2023::PacketSessionHistory),
+    TyreSets(f1_2023::PacketTyreSets),
+    MotionEx(f1_2023::MotionExPacket),
     Unknown,
 }
 
 impl Packets {
+    pub fn read_from<R: Read>(packet_id: u8, reader: &mut PacketReader<R>) -> Result<Packets, ParseError> {
+        let packet = match packet_id {
+            MotionPacket::PACKET_ID => MotionPacket::decode(reader).map(Packets::Motion),
+            SessionDataPacket::PACKET_ID => SessionDataPacket::decode(reader).map(Packets::Session),
+            PacketLapData::PACKET_ID => PacketLapData::decode(reader).map(Packets::LapData),
+            PacketEventData::PACKET_ID => PacketEventData::decode(reader).map(Packets::Event),
+            ParticipantPacket::PACKET_ID => ParticipantPacket::decode(reader).map(Packets::Participants),
+            CarSetupPacket::PACKET_ID => CarSetupPacket::decode(reader).map(Packets::CarSetups),
+            CarTelemetryPacket::PACKET_ID => CarTelemetryPacket::decode(reader).map(Packets::CarTelemetry),
+            PacketCarStatus::PACKET_ID => PacketCarStatus::decode(reader).map(Packets::CarStatus),
+            PacketFinalClassificationData::PACKET_ID => PacketFinalClassificationData::decode(reader).map(Packets::FinalClassification),
+            PacketLobbyInfo::PACKET_ID => PacketLobbyInfo::decode(reader).map(Packets::LobbyInfo),
+            PacketCarDamage::PACKET_ID => PacketCarDamage::decode(reader).map(Packets::CarDamage),
+            PacketSessionHistory::PACKET_ID => PacketSessionHistory::decode(reader).map(Packets::SessionHistory),
+            _ => Ok(Packets::Unknown),
+        };
+
+        packet.map_err(|e| e.with_packet_id(packet_id))
+    }
+
+    /// Decodes an F1 2021 packet, packets that share their layout with F1 2022 decode into the
+    /// same variants.
+    pub fn read_from_2021<R: Read>(packet_id: u8, reader: &mut PacketReader<R>) -> Result<Packets, ParseError> {
+        let packet = match packet_id {
+            f1_2021::SessionDataPacket::PACKET_ID => f1_2021::SessionDataPacket::decode(reader).map(Packets::Session2021),
+            f1_2021::PacketLapData::PACKET_ID => f1_2021::PacketLapData::decode(reader).map(Packets::LapData2021),
+            f1_2021::PacketEventData::PACKET_ID => f1_2021::PacketEventData::decode(reader).map(Packets::Event2021),
+            f1_2021::PacketFinalClassificationData::PACKET_ID => f1_2021::PacketFinalClassificationData::decode(reader).map(Packets::FinalClassification2021),
+            f1_2021::PacketCarDamage::PACKET_ID => f1_2021::PacketCarDamage::decode(reader).map(Packets::CarDamage2021),
+            _ => return Packets::read_from(packet_id, reader),
+        };
+
+        packet.map_err(|e| e.with_packet_id(packet_id))
+    }
+
+    /// Decodes an F1 23 packet, packets that share their layout with F1 2022 apart from the
+    /// header decode into the same variants.
+    pub fn read_from_2023<R: Read>(packet_id: u8, reader: &mut PacketReader<R>) -> Result<Packets, ParseError> {
+        let packet = match packet_id {
+            f1_2023::MotionPacket::PACKET_ID => f1_2023::MotionPacket::decode(reader).map(Packets::Motion2023),
+            f1_2023::SessionDataPacket::PACKET_ID => f1_2023::SessionDataPacket::decode(reader).map(Packets::Session2023),
+            f1_2023::PacketLapData::PACKET_ID => f1_2023::PacketLapData::decode(reader).map(Packets::LapData2023),
+            f1_2023::PacketEventData::PACKET_ID => f1_2023::PacketEventData::decode(reader).map(Packets::Event2023),
+            f1_2023::ParticipantPacket::PACKET_ID => f1_2023::ParticipantPacket::decode(reader).map(Packets::Participants2023),
+            f1_2023::PacketCarStatus::PACKET_ID => f1_2023::PacketCarStatus::decode(reader).map(Packets::CarStatus2023),
+            f1_2023::PacketLobbyInfo::PACKET_ID => f1_2023::PacketLobbyInfo::decode(reader).map(Packets::LobbyInfo2023),
+            f1_2023::PacketSessionHistory::PACKET_ID => f1_2023::PacketSessionHistory::decode(reader).map(Packets::SessionHistory2023),
+            f1_2023::PacketTyreSets::PACKET_ID => f1_2023::PacketTyreSets::decode(reader).map(Packets::TyreSets),
+            f1_2023::MotionExPacket::PACKET_ID => f1_2023::MotionExPacket::decode(reader).map(Packets::MotionEx),
+            _ => return Packets::read_from(packet_id, reader),
+        };
+
+        packet.map_err(|e| e.with_packet_id(packet_id))
+    }
+
+    pub fn header(&self) -> Option<&PacketHeader> {
+        match self {
+            Packets::Motion(packet) => Some(&packet.header),
+            Packets::Session(packet) => Some(&packet.header),
+            Packets::LapData(packet) => Some(&packet.header),
+            Packets::Event(packet) => Some(&packet.header),
+            Packets::Participants(packet) => Some(&packet.header),
+            Packets::CarSetups(packet) => Some(&packet.header),
+            Packets::CarTelemetry(packet) => Some(&packet.header),
+            Packets::CarStatus(packet) => Some(&packet.header),
+            Packets::FinalClassification(packet) => Some(&packet.header),
+            Packets::LobbyInfo(packet) => Some(&packet.header),
+            Packets::CarDamage(packet) => Some(&packet.header),
+            Packets::SessionHistory(packet) => Some(&packet.header),
+            Packets::Session2021(packet) => Some(&packet.header),
+            Packets::LapData2021(packet) => Some(&packet.header),
+            Packets::Event2021(packet) => Some(&packet.header),
+            Packets::FinalClassification2021(packet) => Some(&packet.header),
+            Packets::CarDamage2021(packet) => Some(&packet.header),
+            Packets::Motion2023(packet) => Some(&packet.header),
+            Packets::Session2023(packet) => Some(&packet.header),
+            Packets::LapData2023(packet) => Some(&packet.header),
+            Packets::Event2023(packet) => Some(&packet.header),
+            Packets::Participants2023(packet) => Some(&packet.header),
+            Packets::CarStatus2023(packet) => Some(&packet.header),
+            Packets::LobbyInfo2023(packet) => Some(&packet.header),
+            Packets::SessionHistory2023(packet) => Some(&packet.header),
+            Packets::TyreSets(packet) => Some(&packet.header),
+            Packets::MotionEx(packet) => Some(&packet.header),
+            Packets::Unknown => None,
+        }
+    }
+
+    pub fn packet_id(&self) -> Option<u8> {
+        self.header().map(|header| header.packet_id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.packet_id().and_then(packet_name).unwrap_or("unknown")
+    }
+
+    /// The details of events shared by all game years, `None` for other packets and events.
+    pub fn event_details(&self) -> Option<&EventDetails> {
+        match self {
+            Packets::Event(packet) => packet.event_details.as_ref(),
+            Packets::Event2021(packet) => match &packet.event_details {
+                Some(f1_2021::EventDetails::Other(details)) => Some(details),
+                _ => None,
+            },
+            Packets::Event2023(packet) => match &packet.event_details {
+                Some(f1_2023::EventDetails::Other(details)) => Some(details),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the entry of `car_idx`, `None` for packets that don't hold per car data.
+    pub fn car_data(&self, car_idx: usize) -> Option<CarData<'_>> {
+        match self {
+            Packets::Motion(packet) => packet.car_motion_data.get(car_idx).map(CarData::Motion),
+            Packets::LapData(packet) => packet.lap_data.get(car_idx).map(CarData::LapData),
+            Packets::Participants(packet) => packet.participants.get(car_idx).map(CarData::Participant),
+            Packets::CarSetups(packet) => packet.car_setups.get(car_idx).map(CarData::CarSetup),
+            Packets::CarTelemetry(packet) => packet.car_telemetry_data.get(car_idx).map(CarData::CarTelemetry),
+            Packets::CarStatus(packet) => packet.car_status_data.get(car_idx).map(CarData::CarStatus),
+            Packets::FinalClassification(packet) => packet.classification_data.get(car_idx).map(CarData::Classification),
+            Packets::LobbyInfo(packet) => packet.lobbies.get(car_idx).map(CarData::LobbyInfo),
+            Packets::CarDamage(packet) => packet.car_damage_data.get(car_idx).map(CarData::CarDamage),
+            Packets::LapData2021(packet) => packet.lap_data.get(car_idx).map(CarData::LapData),
+            Packets::FinalClassification2021(packet) => packet.classification_data.get(car_idx).map(CarData::Classification2021),
+            Packets::CarDamage2021(packet) => packet.car_damage_data.get(car_idx).map(CarData::CarDamage2021),
+            Packets::Motion2023(packet) => packet.car_motion_data.get(car_idx).map(CarData::Motion),
+            Packets::LapData2023(packet) => packet.lap_data.get(car_idx).map(CarData::LapData2023),
+            Packets::Participants2023(packet) => packet.participants.get(car_idx).map(CarData::Participant2023),
+            Packets::CarStatus2023(packet) => packet.car_status_data.get(car_idx).map(CarData::CarStatus2023),
+            Packets::LobbyInfo2023(packet) => packet.lobbies.get(car_idx).map(CarData::LobbyInfo2023),
+            _ => None,
+        }
+    }
+
+    /// Encodes the contained packet, writes nothing for [`Packets::Unknown`].
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            Packets::Motion(packet) => packet.write_to(writer),
+            Packets::Session(packet) => packet.write_to(writer),
+            Packets::LapData(packet) => packet.write_to(writer),
+            Packets::Event(packet) => packet.write_to(writer),
+            Packets::Participants(packet) => packet.write_to(writer),
+            Packets::CarSetups(packet) => packet.write_to(writer),
+            Packets::CarTelemetry(packet) => packet.write_to(writer),
+            Packets::CarStatus(packet) => packet.write_to(writer),
+            Packets::FinalClassification(packet) => packet.write_to(writer),
+            Packets::LobbyInfo(packet) => packet.write_to(writer),
+            Packets::CarDamage(packet) => packet.write_to(writer),
+            Packets::SessionHistory(packet) => packet.write_to(writer),
+            Packets::Session2021(packet) => packet.write_to(writer),
+            Packets::LapData2021(packet) => packet.write_to(writer),
+            Packets::Event2021(packet) => packet.write_to(writer),
+            Packets::FinalClassification2021(packet) => packet.write_to(writer),
+            Packets::CarDamage2021(packet) => packet.write_to(writer),
+            Packets::Motion2023(packet) => packet.write_to(writer),
+            Packets::Session2023(packet) => packet.write_to(writer),
+            Packets::LapData2023(packet) => packet.write_to(writer),
+            Packets::Event2023(packet) => packet.write_to(writer),
+            Packets::Participants2023(packet) => packet.write_to(writer),
+            Packets::CarStatus2023(packet) => packet.write_to(writer),
+            Packets::LobbyInfo2023(packet) => packet.write_to(writer),
+            Packets::SessionHistory2023(packet) => packet.write_to(writer),
+            Packets::TyreSets(packet) => packet.write_to(writer),
+            Packets::MotionEx(packet) => packet.write_to(writer),
+            Packets::Unknown => Ok(()),
+        }
+    }
+
+    /// Emits the contained packet to the handlers registered on [`Dispatcher::global`].
     pub fn emit_packet(self) {
         match self {
-            Packets::Motion(packet) => packet.emit(),
-            Packets::Session(packet) => packet.emit(),
-            Packets::LapData(packet) => packet.emit(),
-            Packets::Event(packet) => packet.emit(),
-            Packets::Participants(packet) => packet.emit(),
-            Packets::CarSetups(packet) => packet.emit(),
-            Packets::CarTelemetry(packet) => packet.emit(),
-            Packets::CarStatus(packet) => packet.emit(),
-            Packets::FinalClassification(packet) => packet.emit(),
-            Packets::LobbyInfo(packet) => packet.emit(),
-            Packets::CarDamage(packet) => packet.emit(),
-            Packets::SessionHistory(packet) => packet.emit(),
             Packets::Unknown => println!("Unknown packet"),
+            packet => packet.emit_to(Dispatcher::global()),
+        }
+    }
+
+    pub fn emit_to(&self, dispatcher: &Dispatcher) {
+        match self {
+            Packets::Motion(packet) => dispatcher.emit(packet),
+            Packets::Session(packet) => dispatcher.emit(packet),
+            Packets::LapData(packet) => dispatcher.emit(packet),
+            Packets::Event(packet) => dispatcher.emit(packet),
+            Packets::Participants(packet) => dispatcher.emit(packet),
+            Packets::CarSetups(packet) => dispatcher.emit(packet),
+            Packets::CarTelemetry(packet) => dispatcher.emit(packet),
+            Packets::CarStatus(packet) => dispatcher.emit(packet),
+            Packets::FinalClassification(packet) => dispatcher.emit(packet),
+            Packets::LobbyInfo(packet) => dispatcher.emit(packet),
+            Packets::CarDamage(packet) => dispatcher.emit(packet),
+            Packets::SessionHistory(packet) => dispatcher.emit(packet),
+            Packets::Session2021(packet) => dispatcher.emit(packet),
+            Packets::LapData2021(packet) => dispatcher.emit(packet),
+            Packets::Event2021(packet) => dispatcher.emit(packet),
+            Packets::FinalClassification2021(packet) => dispatcher.emit(packet),
+            Packets::CarDamage2021(packet) => dispatcher.emit(packet),
+            Packets::Motion2023(packet) => dispatcher.emit(packet),
+            Packets::Session2023(packet) => dispatcher.emit(packet),
+            Packets::LapData2023(packet) => dispatcher.emit(packet),
+            Packets::Event2023(packet) => dispatcher.emit(packet),
+            Packets::Participants2023(packet) => dispatcher.emit(packet),
+            Packets::CarStatus2023(packet) => dispatcher.emit(packet),
+            Packets::LobbyInfo2023(packet) => dispatcher.emit(packet),
+            Packets::SessionHistory2023(packet) => dispatcher.emit(packet),
+            Packets::TyreSets(packet) => dispatcher.emit(packet),
+            Packets::MotionEx(packet) => dispatcher.emit(packet),
+            Packets::Unknown => {},
+        }
+    }
+}
+
+/// What a [`PacketFilter`] lets through of a packet.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Selection<'a> {
+    Packet(&'a Packets),
+    Car(CarData<'a>),
+}
+
+/// Selects packet types and the data of a single car, `None` fields don't filter.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PacketFilter {
+    pub packets: Option<Vec<u8>>,
+    pub car: Option<usize>,
+}
+
+impl PacketFilter {
+    /// Returns what the filter lets through of `packet`. With a car selected, per car packets are
+    /// reduced to the entry of that car and packets about another car are dropped.
+    pub fn select<'a>(&self, packet: &'a Packets) -> Option<Selection<'a>> {
+        if let Some(packets) = &self.packets {
+            if !packet.packet_id().is_some_and(|id| packets.contains(&id)) {
+                return None;
+            }
+        }
+
+        match (packet, self.car) {
+            (_, None) => Some(Selection::Packet(packet)),
+            (Packets::SessionHistory(history), Some(car)) if history.car_idx as usize != car => None,
+            (Packets::SessionHistory2023(history), Some(car)) if history.car_idx as usize != car => None,
+            (Packets::TyreSets(tyre_sets), Some(car)) if tyre_sets.car_idx as usize != car => None,
+            (Packets::MotionEx(motion), Some(car)) if motion.header.player_car_index as usize != car => None,
+            (Packets::Session(_) | Packets::Event(_) | Packets::SessionHistory(_)
+                | Packets::Session2021(_) | Packets::Event2021(_)
+                | Packets::Session2023(_) | Packets::Event2023(_) | Packets::SessionHistory2023(_)
+                | Packets::TyreSets(_) | Packets::MotionEx(_), Some(_)) => Some(Selection::Packet(packet)),
+            (_, Some(car)) => packet.car_data(car).map(Selection::Car),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DecodeMode;
+
+    /// Fills a datagram with pseudo random ascii bytes, which keeps floats finite and strings valid.
+    fn random_datagram(seed: u64, packet_format: u16, packet_id: u8, size: usize) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9E3779B97F4A7C15) | 1;
+        let mut bytes: Vec<u8> = (0..size).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state & 0x7F) as u8
+        }).collect();
+        bytes[0..2].copy_from_slice(&packet_format.to_le_bytes());
+        // F1 23 inserts the game year before the versions and the packet id.
+        bytes[if packet_format >= 2023 { 6 } else { 5 }] = packet_id;
+        if packet_id == PacketEventData::PACKET_ID {
+            let code = PacketHeader::size(packet_format);
+            bytes[code..code + 4].copy_from_slice(b"SPTP");
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Packets {
+        decode_with_mode(bytes, DecodeMode::Lenient).unwrap()
+    }
+
+    #[test]
+    fn decoding_encoded_packets_gives_the_same_packet() {
+        for seed in 0..20 {
+            for (packet_format, sizes) in [(2021, &PACKET_SIZES_2021[..]), (2022, &PACKET_SIZES[..]), (2023, &PACKET_SIZES_2023[..])] {
+                for &(packet_id, size) in sizes {
+                    let packet = decode(&random_datagram(seed, packet_format, packet_id, size));
+
+                    let mut bytes = Vec::new();
+                    packet.write_to(&mut bytes).unwrap();
+
+                    assert_eq!(bytes.len(), size, "size of {} packet {}", packet_format, packet_id);
+                    assert_eq!(decode(&bytes), packet, "{} packet {} with seed {}", packet_format, packet_id, seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn filters_select_packet_types_and_cars() {
+        let lap_data = decode(&random_datagram(1, 2022, PacketLapData::PACKET_ID, PacketLapData::PACKET_SIZE));
+        let session = decode(&random_datagram(1, 2022, SessionDataPacket::PACKET_ID, SessionDataPacket::PACKET_SIZE));
+        let Packets::LapData(packet) = &lap_data else { panic!("expected lap data") };
+
+        let filter = PacketFilter { packets: Some(vec![PacketLapData::PACKET_ID]), car: None };
+        assert_eq!(filter.select(&lap_data), Some(Selection::Packet(&lap_data)));
+        assert_eq!(filter.select(&session), None);
+        assert_eq!(filter.select(&Packets::Unknown), None);
+
+        let filter = PacketFilter { packets: None, car: Some(3) };
+        assert_eq!(filter.select(&lap_data), Some(Selection::Car(CarData::LapData(&packet.lap_data[3]))));
+        assert_eq!(filter.select(&session), Some(Selection::Packet(&session)));
+        assert_eq!(PacketFilter { packets: None, car: Some(22) }.select(&lap_data), None);
+    }
+
+    #[test]
+    fn rejects_datagrams_of_the_wrong_size() {
+        let datagram = random_datagram(1, 2022, PacketLapData::PACKET_ID, PacketLapData::PACKET_SIZE + 1);
+
+        let error = decode_with_mode(&datagram[..100], DecodeMode::Lenient).unwrap_err();
+        assert_eq!(error.packet_id, Some(PacketLapData::PACKET_ID));
+        assert_eq!(error.kind, ParseErrorKind::Truncated { expected: PacketLapData::PACKET_SIZE, actual: 100 });
+
+        let error = decode_with_mode(&datagram, DecodeMode::Lenient).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Oversized { expected: PacketLapData::PACKET_SIZE, actual: PacketLapData::PACKET_SIZE + 1 });
+
+        let unknown = random_datagram(1, 2022, 200, 30);
+        assert_eq!(decode(&unknown), Packets::Unknown);
+    }
+
+    fn header_2021(packet_id: u8, size: usize) -> Vec<u8> {
+        let mut datagram = vec![0; size];
+        datagram[0..2].copy_from_slice(&2021u16.to_le_bytes());
+        datagram[5] = packet_id;
+        datagram
+    }
+
+    #[test]
+    fn decodes_f1_2021_layouts() {
+        let mut datagram = header_2021(f1_2021::PacketEventData::PACKET_ID, 36);
+        datagram[24..28].copy_from_slice(b"SPTP");
+        datagram[28] = 5;
+        datagram[29..33].copy_from_slice(&321.5f32.to_le_bytes());
+        datagram[33] = 1;
+        match decode(&datagram) {
+            Packets::Event2021(packet) => assert_eq!(packet.event_details, Some(f1_2021::EventDetails::SpeedTrap(f1_2021::SpeedTrap {
+                vehicle_index: 5,
+                speed: 321.5,
+                is_overall_fastest_in_session: true,
+                is_driver_fastest_in_session: false,
+            }))),
+            packet => panic!("Expected a 2021 event, got {:?}", packet),
+        }
+
+        // Without the time trial indices the lap data ends after the cars, car 1 starts at 24 + 43.
+        let mut datagram = header_2021(f1_2021::PacketLapData::PACKET_ID, 970);
+        datagram[24 + 43 + 24] = 3;
+        match decode(&datagram) {
+            Packets::LapData2021(packet) => assert_eq!(packet.lap_data[1].car_position, 3),
+            packet => panic!("Expected 2021 lap data, got {:?}", packet),
+        }
+
+        // The telemetry layout is shared, so it decodes into the 2022 type.
+        let datagram = header_2021(CarTelemetryPacket::PACKET_ID, CarTelemetryPacket::PACKET_SIZE);
+        assert!(matches!(decode(&datagram), Packets::CarTelemetry(_)));
+
+        let datagram = header_2021(f1_2021::PacketCarDamage::PACKET_ID, PacketCarDamage::PACKET_SIZE);
+        let error = decode_with_mode(&datagram, DecodeMode::Lenient).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Oversized { expected: 882, actual: PacketCarDamage::PACKET_SIZE });
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        let datagram = random_datagram(1, 2019, PacketLapData::PACKET_ID, PacketLapData::PACKET_SIZE);
+        let error = decode_with_mode(&datagram, DecodeMode::Lenient).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnsupportedFormat(2019));
+    }
+
+    fn header_2023(packet_id: u8, size: usize) -> Vec<u8> {
+        let mut datagram = vec![0; size];
+        datagram[0..2].copy_from_slice(&2023u16.to_le_bytes());
+        datagram[2] = 23;
+        datagram[6] = packet_id;
+        datagram
+    }
+
+    #[test]
+    fn decodes_f1_23_layouts() {
+        let mut datagram = header_2023(f1_2023::PacketEventData::PACKET_ID, 45);
+        datagram[29..33].copy_from_slice(b"OVTK");
+        datagram[33] = 4;
+        datagram[34] = 11;
+        match decode(&datagram) {
+            Packets::Event2023(packet) => {
+                assert_eq!(packet.header.game_year, Some(23));
+                assert_eq!(packet.event_details, Some(f1_2023::EventDetails::Overtake(f1_2023::Overtake {
+                    overtaking_vehicle_index: 4,
+                    being_overtaken_vehicle_index: 11,
+                })));
+            },
+            packet => panic!("Expected an F1 23 event, got {:?}", packet),
+        }
+
+        // Car 1 starts after the 29 byte header and the 50 bytes of car 0, its delta to the
+        // leader is at offset 16.
+        let mut datagram = header_2023(f1_2023::PacketLapData::PACKET_ID, 1131);
+        datagram[29 + 50 + 16..29 + 50 + 18].copy_from_slice(&1234u16.to_le_bytes());
+        datagram[1130] = 7;
+        match decode(&datagram) {
+            Packets::LapData2023(packet) => {
+                assert_eq!(packet.lap_data[1].delta_to_race_leader, 1234);
+                assert_eq!(packet.time_trial_rival_car_idx, 7);
+            },
+            packet => panic!("Expected F1 23 lap data, got {:?}", packet),
         }
+
+        let mut datagram = header_2023(f1_2023::PacketTyreSets::PACKET_ID, 231);
+        datagram[29] = 3;
+        datagram[230] = 2;
+        datagram[30 + 2 * 10 + 2] = 40;
+        match decode(&datagram) {
+            Packets::TyreSets(packet) => {
+                assert_eq!(packet.car_idx, 3);
+                assert_eq!(packet.fitted().unwrap().wear, 40);
+            },
+            packet => panic!("Expected tyre sets, got {:?}", packet),
+        }
+
+        // The telemetry layout only differs in the header, so it decodes into the 2022 type.
+        let datagram = header_2023(CarTelemetryPacket::PACKET_ID, 1352);
+        assert!(matches!(decode(&datagram), Packets::CarTelemetry(_)));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,299 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::frames::PacketKind;
+use crate::models::enums::{ActualTyreCompound, PitStatus, VisualTyreCompound};
+use crate::packets::Packets;
+use crate::session_state::MAX_CARS;
+use crate::telemetry::{Frame, Lap, Status};
+
+/// The tyres fitted to a car.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Tyres {
+    pub actual_compound: ActualTyreCompound,
+    pub visual_compound: VisualTyreCompound,
+    pub age_laps: u8,
+}
+
+impl From<&Status> for Tyres {
+    fn from(status: &Status) -> Tyres {
+        Tyres {
+            actual_compound: status.actual_tyre_compound,
+            visual_compound: status.visual_tyre_compound,
+            age_laps: status.tyres_age_laps,
+        }
+    }
+}
+
+/// A visit to the pit lane, times are in milliseconds unless noted otherwise.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct PitStop {
+    /// The lap the car entered the pit lane on.
+    pub lap: u8,
+    /// Session time of the entry in seconds.
+    pub entered_at: f32,
+    /// Session time of the exit in seconds.
+    pub exited_at: f32,
+    pub lane_time: u16,
+    /// Zero for drive throughs.
+    pub stationary_time: u16,
+    /// `None` when no car status arrived before the stop.
+    pub tyres_before: Option<Tyres>,
+    pub tyres_after: Option<Tyres>,
+    pub penalty_served: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct PitEntry {
+    pub car_idx: u8,
+    pub lap: u8,
+    pub tyres: Option<Tyres>,
+}
+
+/// The pit crew started working on a car.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct PitStopStationary {
+    pub car_idx: u8,
+    pub lap: u8,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct PitExit {
+    pub car_idx: u8,
+    pub stop: PitStop,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum PitEvent {
+    Entry(PitEntry),
+    Stationary(PitStopStationary),
+    Exit(PitExit),
+}
+
+impl PitEvent {
+    /// Emits the contained event to the handlers of its concrete type.
+    pub fn emit_to(&self, dispatcher: &Dispatcher) {
+        match self {
+            PitEvent::Entry(event) => dispatcher.emit(event),
+            PitEvent::Stationary(event) => dispatcher.emit(event),
+            PitEvent::Exit(event) => dispatcher.emit(event),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ActiveStop {
+    stop: PitStop,
+    unserved_penalties: u8,
+    stationary: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarPits {
+    active: Option<ActiveStop>,
+    stops: Vec<PitStop>,
+}
+
+fn unserved_penalties(lap: &Lap) -> u8 {
+    lap.num_unserved_drive_through_penalties + lap.num_unserved_stop_go_penalties
+}
+
+fn in_pit_lane(lap: &Lap) -> bool {
+    lap.pit_status != PitStatus::None
+}
+
+/// Turns the pit status and timers of the lap data into pit stops.
+///
+/// A stop starts when a car enters the pit lane and ends when it leaves it again, cars that
+/// are in the pits when they are first seen, like cars in the garage, are skipped until they
+/// left. The tyres come from the latest car status of the car.
+#[derive(Debug, Clone)]
+pub struct PitTracker {
+    frame: Option<Frame>,
+    cars: Vec<CarPits>,
+}
+
+impl Default for PitTracker {
+    fn default() -> PitTracker {
+        PitTracker {
+            frame: None,
+            cars: vec![CarPits::default(); MAX_CARS],
+        }
+    }
+}
+
+impl PitTracker {
+    pub fn new() -> PitTracker {
+        PitTracker::default()
+    }
+
+    /// Creates a tracker fed by every packet dispatched by `dispatcher`, its events are emitted
+    /// back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<PitTracker>>, Subscription) {
+        PitTracker::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<PitTracker>>, Subscription) {
+        event_system::attach(self, dispatcher, PitTracker::ingest, PitEvent::emit_to)
+    }
+
+    /// The completed stops of `car_idx` in the order they happened.
+    pub fn stops(&self, car_idx: usize) -> &[PitStop] {
+        self.cars.get(car_idx).map_or(&[], |car| &car.stops)
+    }
+
+    pub fn in_pits(&self, car_idx: usize) -> bool {
+        self.cars.get(car_idx).is_some_and(|car| car.active.is_some())
+    }
+
+    pub fn reset(&mut self) {
+        *self = PitTracker::default();
+    }
+
+    /// Updates the tracker and returns the events caused by `packet`.
+    pub fn ingest(&mut self, packet: &Packets) -> Vec<PitEvent> {
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return vec![],
+        };
+        if self.frame.as_ref().is_some_and(|frame| frame.session_uid != header.session_uid) {
+            self.reset();
+        }
+
+        let frame = self.frame.get_or_insert_with(|| Frame::new(header));
+        let previous: Vec<Option<Lap>> = frame.cars.iter().map(|car| car.lap).collect();
+        if !frame.update(packet) || PacketKind::of(packet) != Some(PacketKind::LapData) {
+            return vec![];
+        }
+
+        let mut events = vec![];
+        for (car_idx, (car, previous)) in self.cars.iter_mut().zip(previous).enumerate() {
+            let snapshot = &frame.cars[car_idx];
+            if let (Some(previous), Some(lap)) = (previous, snapshot.lap) {
+                let tyres = snapshot.status.as_ref().map(Tyres::from);
+                car.update(car_idx as u8, &previous, &lap, tyres, header.session_time, &mut events);
+            }
+        }
+
+        events
+    }
+}
+
+impl CarPits {
+    fn update(&mut self, car_idx: u8, previous: &Lap, lap: &Lap, tyres: Option<Tyres>, session_time: f32, events: &mut Vec<PitEvent>) {
+        if self.active.is_none() && !in_pit_lane(previous) && in_pit_lane(lap) {
+            self.active = Some(ActiveStop {
+                stop: PitStop {
+                    lap: lap.current_lap_num,
+                    entered_at: session_time,
+                    exited_at: session_time,
+                    lane_time: 0,
+                    stationary_time: 0,
+                    tyres_before: tyres,
+                    tyres_after: None,
+                    penalty_served: false,
+                },
+                unserved_penalties: unserved_penalties(previous),
+                stationary: false,
+            });
+            events.push(PitEvent::Entry(PitEntry { car_idx, lap: lap.current_lap_num, tyres }));
+        }
+
+        let active = match &mut self.active {
+            Some(active) => active,
+            None => return,
+        };
+
+        let stop = &mut active.stop;
+        stop.lane_time = stop.lane_time.max(lap.pit_lane_time_in_lane);
+        stop.stationary_time = stop.stationary_time.max(lap.pit_stop_timer);
+        stop.penalty_served |= lap.pit_stop_should_serve_penalty;
+        if !active.stationary && lap.pit_stop_timer > 0 {
+            active.stationary = true;
+            events.push(PitEvent::Stationary(PitStopStationary { car_idx, lap: stop.lap }));
+        }
+
+        if !in_pit_lane(lap) {
+            stop.exited_at = session_time;
+            stop.tyres_after = tyres;
+            stop.penalty_served |= unserved_penalties(lap) < active.unserved_penalties;
+
+            let stop = *stop;
+            self.active = None;
+            self.stops.push(stop);
+            events.push(PitEvent::Exit(PitExit { car_idx, stop }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketCarStatus, PacketLapData};
+    use crate::testing::packet;
+
+    const SOFT: (ActualTyreCompound, VisualTyreCompound) = (ActualTyreCompound::C3, VisualTyreCompound::Soft);
+    const MEDIUM: (ActualTyreCompound, VisualTyreCompound) = (ActualTyreCompound::C2, VisualTyreCompound::Medium);
+
+    // Car 1 is set, car 0 stays on track.
+    fn lap_data(session_time: f32, pit_status: PitStatus, time_in_lane: u16, stop_timer: u16, unserved: u8) -> Packets {
+        let mut packet = packet::<PacketLapData>(session_time);
+        let lap_data = &mut packet.lap_data[1];
+        lap_data.current_lap_num = 3;
+        lap_data.pit_status = pit_status;
+        lap_data.num_unserved_stop_go_penalties = unserved;
+        lap_data.pit_lane_time_in_lane = time_in_lane;
+        lap_data.pit_stop_timer = stop_timer;
+        Packets::LapData(packet)
+    }
+
+    fn tyres(session_time: f32, (actual, visual): (ActualTyreCompound, VisualTyreCompound), age: u8) -> Packets {
+        let mut packet = packet::<PacketCarStatus>(session_time);
+        let status = &mut packet.car_status_data[1];
+        status.actual_tyre_compound = actual;
+        status.visual_tyre_compound = visual;
+        status.tyres_age_laps = age;
+        Packets::CarStatus(packet)
+    }
+
+    #[test]
+    fn records_stops_with_tyres_and_penalties() {
+        let mut tracker = PitTracker::new();
+        tracker.ingest(&tyres(0.0, SOFT, 12));
+        tracker.ingest(&lap_data(0.0, PitStatus::None, 0, 0, 1));
+
+        let events = tracker.ingest(&lap_data(1.0, PitStatus::Pitting, 100, 0, 1));
+        assert!(matches!(events[..], [PitEvent::Entry(PitEntry { car_idx: 1, lap: 3, tyres: Some(_) })]));
+        assert!(tracker.in_pits(1));
+
+        let events = tracker.ingest(&lap_data(10.0, PitStatus::InPitArea, 9000, 100, 1));
+        assert!(matches!(events[..], [PitEvent::Stationary(PitStopStationary { car_idx: 1, .. })]));
+        tracker.ingest(&lap_data(12.0, PitStatus::InPitArea, 11000, 2400, 0));
+        tracker.ingest(&tyres(13.0, MEDIUM, 0));
+
+        let events = tracker.ingest(&lap_data(20.0, PitStatus::None, 19500, 2400, 0));
+        let stop = match events[..] {
+            [PitEvent::Exit(PitExit { car_idx: 1, stop })] => stop,
+            _ => panic!("unexpected events {:?}", events),
+        };
+        assert_eq!((stop.lane_time, stop.stationary_time), (19500, 2400));
+        assert_eq!((stop.entered_at, stop.exited_at), (1.0, 20.0));
+        assert_eq!(stop.tyres_before.unwrap().visual_compound, VisualTyreCompound::Soft);
+        assert_eq!(stop.tyres_after.unwrap().visual_compound, VisualTyreCompound::Medium);
+        assert!(stop.penalty_served);
+        assert_eq!(tracker.stops(1), [stop]);
+        assert!(tracker.stops(0).is_empty());
+    }
+
+    #[test]
+    fn cars_first_seen_in_the_pits_are_skipped() {
+        let mut tracker = PitTracker::new();
+        tracker.ingest(&lap_data(0.0, PitStatus::InPitArea, 0, 0, 0));
+        assert!(tracker.ingest(&lap_data(1.0, PitStatus::None, 0, 0, 0)).is_empty());
+
+        let events = tracker.ingest(&lap_data(2.0, PitStatus::Pitting, 0, 0, 0));
+        assert!(matches!(events[..], [PitEvent::Entry(_)]));
+        let events = tracker.ingest(&lap_data(3.0, PitStatus::None, 800, 0, 0));
+        assert!(matches!(events[..], [PitEvent::Exit(PitExit { stop: PitStop { stationary_time: 0, penalty_served: false, .. }, .. })]));
+    }
+}
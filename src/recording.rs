@@ -0,0 +1,364 @@
+//! Capturing raw datagrams to a file and replaying them later.
+//!
+//! A capture file is little endian and laid out as follows:
+//!
+//! | Field              | Type      | Description                                        |
+//! |--------------------|-----------|----------------------------------------------------|
+//! | magic              | `[u8; 4]` | Always `F1CP`                                      |
+//! | version            | `u16`     | Format version, currently `1`                      |
+//! | started_at         | `u64`     | Microseconds since the unix epoch at recording start |
+//!
+//! followed by one record per datagram until the end of the file:
+//!
+//! | Field              | Type      | Description                                        |
+//! |--------------------|-----------|----------------------------------------------------|
+//! | offset             | `u64`     | Microseconds between recording start and receiving |
+//! | length             | `u16`     | Length of the datagram in bytes                    |
+//! | data               | `[u8]`    | The datagram exactly as it was received            |
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use crate::event_system::Dispatcher;
+use crate::error::ParseError;
+use crate::models::{DecodeMode, PacketHeader, PacketReader};
+use crate::packets::{decode_with_mode, Packets};
+
+pub const CAPTURE_MAGIC: [u8; 4] = *b"F1CP";
+pub const CAPTURE_VERSION: u16 = 1;
+
+pub struct Recorder<W: Write> {
+    writer: W,
+    started: Instant,
+    records: u64,
+}
+
+impl Recorder<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Recorder<BufWriter<File>>> {
+        Recorder::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(mut writer: W) -> io::Result<Recorder<W>> {
+        let started_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        writer.write_all(&CAPTURE_MAGIC)?;
+        writer.write_u16::<LittleEndian>(CAPTURE_VERSION)?;
+        writer.write_u64::<LittleEndian>(started_at.as_micros() as u64)?;
+
+        Ok(Recorder {
+            writer,
+            started: Instant::now(),
+            records: 0,
+        })
+    }
+
+    /// Appends a datagram timestamped with the time elapsed since the recorder was created.
+    pub fn record(&mut self, datagram: &[u8]) -> io::Result<()> {
+        let offset = self.started.elapsed();
+        self.record_at(offset, datagram)
+    }
+
+    pub fn record_at(&mut self, offset: Duration, datagram: &[u8]) -> io::Result<()> {
+        let length = u16::try_from(datagram.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "datagram too large to record"))?;
+
+        // The record is written at once so an interrupted capture ends in at most one partial record.
+        let mut record = Vec::with_capacity(10 + datagram.len());
+        record.write_u64::<LittleEndian>(offset.as_micros() as u64)?;
+        record.write_u16::<LittleEndian>(length)?;
+        record.extend_from_slice(datagram);
+        self.writer.write_all(&record)?;
+        self.records += 1;
+
+        Ok(())
+    }
+
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    pub(crate) fn boxed(self) -> Recorder<Box<dyn Write + Send>> where W: Send + 'static {
+        Recorder {
+            writer: Box::new(self.writer),
+            started: self.started,
+            records: self.records,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub offset: Duration,
+    pub data: Vec<u8>,
+}
+
+impl Record {
+    pub fn header(&self) -> Result<PacketHeader, ParseError> {
+        PacketHeader::new(&mut PacketReader::new(&self.data[..]))
+    }
+
+    pub fn decode(&self) -> Result<Packets, ParseError> {
+        self.decode_with_mode(DecodeMode::Strict)
+    }
+
+    pub fn decode_with_mode(&self, mode: DecodeMode) -> Result<Packets, ParseError> {
+        decode_with_mode(&self.data, mode)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReplaySpeed {
+    /// Keeps the original spacing between datagrams.
+    RealTime,
+    /// Plays back `n` times faster than recorded, multipliers that aren't above zero or too
+    /// small to wait for play as fast as possible.
+    Multiplier(f32),
+    AsFastAsPossible,
+}
+
+pub struct Replayer {
+    started_at: SystemTime,
+    records: Vec<Record>,
+    position: usize,
+}
+
+impl Replayer {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Replayer> {
+        Replayer::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Replayer> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != CAPTURE_MAGIC {
+            return Err(io::Error::new(ErrorKind::InvalidData, "not a capture file"));
+        }
+
+        let version = reader.read_u16::<LittleEndian>()?;
+        if version != CAPTURE_VERSION {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("unsupported capture version {}", version)));
+        }
+
+        let started_at = UNIX_EPOCH + Duration::from_micros(reader.read_u64::<LittleEndian>()?);
+
+        let mut records = Vec::new();
+        loop {
+            // A capture that wasn't closed properly ends in a partial record, which is dropped.
+            match Replayer::read_record(&mut reader) {
+                Ok(record) => records.push(record),
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Replayer {
+            started_at,
+            records,
+            position: 0,
+        })
+    }
+
+    fn read_record<R: Read>(reader: &mut R) -> io::Result<Record> {
+        let offset = Duration::from_micros(reader.read_u64::<LittleEndian>()?);
+        let length = reader.read_u16::<LittleEndian>()?;
+        let mut data = vec![0; length as usize];
+        reader.read_exact(&mut data)?;
+
+        Ok(Record { offset, data })
+    }
+
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn rewind(&mut self) {
+        self.position = 0;
+    }
+
+    /// Moves to the first datagram with a frame identifier of at least `frame_identifier`.
+    pub fn seek_to_frame(&mut self, frame_identifier: u32) {
+        self.seek_by(|header| header.frame_identifier >= frame_identifier)
+    }
+
+    /// Moves to the first datagram with a session time of at least `session_time` seconds.
+    pub fn seek_to_session_time(&mut self, session_time: f32) {
+        self.seek_by(|header| header.session_time >= session_time)
+    }
+
+    fn seek_by<F: Fn(&PacketHeader) -> bool>(&mut self, predicate: F) {
+        self.position = self.records.iter()
+            .position(|record| record.header().map(|header| predicate(&header)).unwrap_or(false))
+            .unwrap_or(self.records.len());
+    }
+
+    /// Returns the next datagram without waiting.
+    pub fn next_record(&mut self) -> Option<&Record> {
+        let record = self.records.get(self.position)?;
+        self.position += 1;
+        Some(record)
+    }
+
+    /// Hands every remaining datagram to `f`, spaced out according to `speed`.
+    ///
+    /// Datagrams recorded before the first remaining one, in a concatenated or edited capture,
+    /// are handed over without waiting.
+    pub fn play<F: FnMut(&Record)>(&mut self, speed: ReplaySpeed, mut f: F) {
+        let first_offset = match self.records.get(self.position) {
+            Some(record) => record.offset,
+            None => return,
+        };
+        let started = Instant::now();
+
+        while let Some(record) = self.records.get(self.position) {
+            let due = match speed {
+                ReplaySpeed::RealTime => Some(record.offset.saturating_sub(first_offset)),
+                ReplaySpeed::Multiplier(multiplier) if multiplier > 0.0 => {
+                    let offset = record.offset.saturating_sub(first_offset);
+                    Duration::try_from_secs_f64(offset.as_secs_f64() / multiplier as f64).ok()
+                },
+                _ => None,
+            };
+
+            if let Some(due) = due {
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    thread::sleep(wait);
+                }
+            }
+
+            f(record);
+            self.position += 1;
+        }
+    }
+
+    /// Decodes the remaining datagrams and emits them to `dispatcher`, datagrams that fail to
+    /// decode are skipped. Returns the number of skipped datagrams.
+    pub fn play_to(&mut self, speed: ReplaySpeed, dispatcher: &Dispatcher) -> usize {
+        let mut skipped = 0;
+        self.play(speed, |record| match record.decode() {
+            Ok(packet) => dispatcher.dispatch(&packet),
+            Err(_) => skipped += 1,
+        });
+        skipped
+    }
+
+    /// Re-sends the remaining datagrams to `target`, for example a running [`crate::server::Server`].
+    pub fn send_to<A: ToSocketAddrs>(&mut self, speed: ReplaySpeed, socket: &UdpSocket, target: A) -> io::Result<()> {
+        let target = target.to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "no address to replay to"))?;
+
+        let mut result = Ok(());
+        self.play(speed, |record| {
+            if result.is_ok() {
+                result = socket.send_to(&record.data, target).map(|_| ());
+            }
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datagram(frame_identifier: u32, session_time: f32) -> Vec<u8> {
+        let mut datagram = vec![0; PacketHeader::PACKET_SIZE];
+        datagram[0..2].copy_from_slice(&2022u16.to_le_bytes());
+        datagram[5] = 255;
+        datagram[14..18].copy_from_slice(&session_time.to_le_bytes());
+        datagram[18..22].copy_from_slice(&frame_identifier.to_le_bytes());
+        datagram
+    }
+
+    #[test]
+    fn replays_recorded_datagrams() {
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+        for frame in 0..5 {
+            recorder.record_at(Duration::from_millis(frame as u64 * 10), &datagram(frame, frame as f32 / 10.0)).unwrap();
+        }
+        assert_eq!(recorder.records(), 5);
+
+        let mut replayer = Replayer::from_reader(&recorder.into_inner().unwrap()[..]).unwrap();
+        assert_eq!(replayer.records().len(), 5);
+        assert_eq!(replayer.records()[3].offset, Duration::from_millis(30));
+
+        replayer.seek_to_frame(2);
+        assert_eq!(replayer.next_record().unwrap().header().unwrap().frame_identifier, 2);
+
+        replayer.seek_to_session_time(0.25);
+        let mut frames = Vec::new();
+        replayer.play(ReplaySpeed::AsFastAsPossible, |record| {
+            frames.push(record.header().unwrap().frame_identifier);
+        });
+        assert_eq!(frames, vec![3, 4]);
+        assert!(replayer.next_record().is_none());
+    }
+
+    #[test]
+    fn replays_offsets_out_of_order() {
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+        for (frame, offset) in [(0, 20), (1, 0), (2, 30)] {
+            recorder.record_at(Duration::from_millis(offset), &datagram(frame, 0.0)).unwrap();
+        }
+
+        let mut replayer = Replayer::from_reader(&recorder.into_inner().unwrap()[..]).unwrap();
+        let mut frames = Vec::new();
+        replayer.play(ReplaySpeed::RealTime, |record| frames.push(record.header().unwrap().frame_identifier));
+        assert_eq!(frames, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn drops_a_partial_last_record() {
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+        for frame in 0..3 {
+            recorder.record_at(Duration::from_millis(frame as u64), &datagram(frame, 0.0)).unwrap();
+        }
+        let capture = recorder.into_inner().unwrap();
+
+        // Cut inside the length and inside the data of the last record.
+        for cut in [3, 20] {
+            let replayer = Replayer::from_reader(&capture[..capture.len() - cut]).unwrap();
+            assert_eq!(replayer.records().len(), 2);
+        }
+    }
+
+    #[test]
+    fn tiny_multipliers_play_as_fast_as_possible() {
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+        for frame in 0..2 {
+            recorder.record_at(Duration::from_secs(frame as u64), &datagram(frame, 0.0)).unwrap();
+        }
+
+        let mut replayer = Replayer::from_reader(&recorder.into_inner().unwrap()[..]).unwrap();
+        let mut frames = Vec::new();
+        replayer.play(ReplaySpeed::Multiplier(1e-30), |record| frames.push(record.header().unwrap().frame_identifier));
+        assert_eq!(frames, vec![0, 1]);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(Replayer::from_reader(&b"not a capture"[..]).is_err());
+    }
+}
\ No newline at end of file
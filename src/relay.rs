@@ -0,0 +1,203 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::Serialize;
+
+/// When a datagram is forwarded to a target.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize)]
+pub enum RelayStage {
+    /// Right after receiving, including datagrams that fail to decode.
+    #[default]
+    BeforeDecode,
+    /// Only once the datagram decoded successfully.
+    AfterDecode,
+}
+
+/// A downstream application datagrams are forwarded to.
+#[derive(Debug)]
+pub struct RelayTarget {
+    address: SocketAddr,
+    packets: Option<Vec<u8>>,
+    stage: RelayStage,
+    forwarded: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Counters of a single target, dropped counts filtered, undecodable and failed datagrams.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct RelayStats {
+    pub address: SocketAddr,
+    pub forwarded: u64,
+    pub dropped: u64,
+}
+
+impl RelayTarget {
+    pub fn new<A: ToSocketAddrs>(address: A) -> io::Result<RelayTarget> {
+        let address = address.to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to relay to"))?;
+
+        Ok(RelayTarget {
+            address,
+            packets: None,
+            stage: RelayStage::BeforeDecode,
+            forwarded: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Only forwards datagrams with one of the given packet ids.
+    pub fn with_packets(mut self, packet_ids: &[u8]) -> RelayTarget {
+        self.packets = Some(packet_ids.to_vec());
+        self
+    }
+
+    pub fn with_stage(mut self, stage: RelayStage) -> RelayTarget {
+        self.stage = stage;
+        self
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    fn accepts(&self, packet_id: Option<u8>) -> bool {
+        self.packets.as_ref().is_none_or(|packets| packet_id.is_some_and(|packet_id| packets.contains(&packet_id)))
+    }
+
+    pub fn stats(&self) -> RelayStats {
+        RelayStats {
+            address: self.address,
+            forwarded: self.forwarded.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Forwards raw datagrams to a list of targets, so several applications can use the one
+/// telemetry stream the game sends.
+#[derive(Debug)]
+pub struct Relay {
+    /// Bound only when there are IPv4 targets.
+    ipv4: Option<UdpSocket>,
+    /// Bound only when there are IPv6 targets.
+    ipv6: Option<UdpSocket>,
+    targets: Vec<RelayTarget>,
+}
+
+impl Relay {
+    pub fn new(targets: Vec<RelayTarget>) -> io::Result<Relay> {
+        let bind = |ipv6: bool, address: &str| match targets.iter().any(|target| target.address.is_ipv6() == ipv6) {
+            true => UdpSocket::bind(address).map(Some),
+            false => Ok(None),
+        };
+
+        Ok(Relay {
+            ipv4: bind(false, "0.0.0.0:0")?,
+            ipv6: bind(true, "[::]:0")?,
+            targets,
+        })
+    }
+
+    fn socket_for(&self, address: &SocketAddr) -> Option<&UdpSocket> {
+        match address {
+            SocketAddr::V4(_) => self.ipv4.as_ref(),
+            SocketAddr::V6(_) => self.ipv6.as_ref(),
+        }
+    }
+
+    pub fn targets(&self) -> &[RelayTarget] {
+        &self.targets
+    }
+
+    pub fn stats(&self) -> Vec<RelayStats> {
+        self.targets.iter().map(RelayTarget::stats).collect()
+    }
+
+    /// Sends `datagram` to every target of `stage` that accepts `packet_id`. Datagrams without a
+    /// readable header have no packet id and only reach targets that take every packet.
+    pub fn forward(&self, stage: RelayStage, packet_id: Option<u8>, datagram: &[u8]) {
+        for target in self.targets.iter().filter(|target| target.stage == stage) {
+            let counter = match self.socket_for(&target.address) {
+                Some(socket) if target.accepts(packet_id) && socket.send_to(datagram, target.address).is_ok() => &target.forwarded,
+                _ => &target.dropped,
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Counts a datagram that isn't forwarded, because it failed to decode, as dropped for the
+    /// targets of `stage`.
+    pub fn record_dropped(&self, stage: RelayStage) {
+        self.targets.iter()
+            .filter(|target| target.stage == stage)
+            .for_each(|target| { target.dropped.fetch_add(1, Ordering::Relaxed); });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn forwards_to_matching_targets() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+        let address = receiver.local_addr().unwrap();
+
+        let relay = Relay::new(vec![
+            RelayTarget::new(address).unwrap().with_packets(&[2]),
+            RelayTarget::new(address).unwrap().with_stage(RelayStage::AfterDecode),
+        ]).unwrap();
+
+        relay.forward(RelayStage::BeforeDecode, Some(2), b"lap data");
+        relay.forward(RelayStage::BeforeDecode, Some(6), b"telemetry");
+        relay.record_dropped(RelayStage::AfterDecode);
+
+        let mut buf = [0; 16];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"lap data");
+
+        let stats = relay.stats();
+        assert_eq!((stats[0].forwarded, stats[0].dropped), (1, 1));
+        assert_eq!((stats[1].forwarded, stats[1].dropped), (0, 1));
+    }
+
+    #[test]
+    fn forwards_to_ipv6_targets() {
+        // Not every host has IPv6 loopback.
+        let receiver = match UdpSocket::bind("[::1]:0") {
+            Ok(receiver) => receiver,
+            Err(_) => return,
+        };
+        receiver.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+
+        let relay = Relay::new(vec![RelayTarget::new(receiver.local_addr().unwrap()).unwrap()]).unwrap();
+        relay.forward(RelayStage::BeforeDecode, Some(2), b"lap data");
+
+        let mut buf = [0; 16];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"lap data");
+        assert_eq!(relay.stats()[0].forwarded, 1);
+    }
+
+    #[test]
+    fn forwards_unreadable_datagrams_to_unfiltered_targets() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+        let address = receiver.local_addr().unwrap();
+
+        let relay = Relay::new(vec![
+            RelayTarget::new(address).unwrap().with_packets(&[2]),
+            RelayTarget::new(address).unwrap(),
+        ]).unwrap();
+        relay.forward(RelayStage::BeforeDecode, None, b"garbage");
+
+        let mut buf = [0; 16];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"garbage");
+        let stats = relay.stats();
+        assert_eq!((stats[0].forwarded, stats[0].dropped), (0, 1));
+        assert_eq!((stats[1].forwarded, stats[1].dropped), (1, 0));
+    }
+}
@@ -1,14 +1,30 @@
-use std::io;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use crate::error::{ParseError, ParseErrorKind};
 use crate::models::*;
-use bytebuffer::ByteBuffer;
-use crate::packets::Packets;
-use crate::models::MotionPacket;
-use crate::models::traits::Packet;
+use crate::packets::{decode_with_mode, Packets, MAX_DATAGRAM_SIZE};
+use crate::diagnostics::{PacketDiagnostics, StreamStats};
+use crate::event_system::Dispatcher;
+use crate::recording::Recorder;
+use crate::relay::{Relay, RelayStage, RelayStats};
+
+/// The address `Server::new` and `AsyncServer::new` receive telemetry on.
+pub const DEFAULT_ADDRESS: &str = "0.0.0.0:25123";
 
 pub struct Server {
     pub stream: UdpSocket,
     address: &'static str,
+    dispatcher: Dispatcher,
+    recorder: Option<Mutex<Recorder<Box<dyn Write + Send>>>>,
+    decode_mode: DecodeMode,
+    relay: Option<Relay>,
+    diagnostics: Option<Mutex<PacketDiagnostics>>,
+    buffer: Mutex<Vec<u8>>,
+    counters: ReceiveCounters,
 }
 
 impl Server {
@@ -16,88 +32,273 @@ impl Server {
         Ok(Server {
             stream: UdpSocket::bind(address)?,
             address,
+            dispatcher: Dispatcher::global().clone(),
+            recorder: None,
+            decode_mode: DecodeMode::Strict,
+            relay: None,
+            diagnostics: None,
+            buffer: Mutex::new(vec![0; MAX_DATAGRAM_SIZE]),
+            counters: ReceiveCounters::default(),
         })
     }
 
     pub fn new() -> io::Result<Server> {
-        Ok(Server {
-            stream: UdpSocket::bind("0.0.0.0:25123")?,
-            address: "0.0.0.0:25123",
-        })
+        Server::new_with_address(DEFAULT_ADDRESS)
+    }
+
+    /// Uses `dispatcher` instead of the global one, so handlers are scoped to this server.
+    pub fn with_dispatcher(mut self, dispatcher: Dispatcher) -> Server {
+        self.dispatcher = dispatcher;
+        self
+    }
+
+    pub fn dispatcher(&self) -> &Dispatcher {
+        &self.dispatcher
+    }
+
+    /// Sets whether unknown enum values are rejected or kept, see [`DecodeMode`].
+    pub fn with_decode_mode(mut self, decode_mode: DecodeMode) -> Server {
+        self.decode_mode = decode_mode;
+        self
+    }
+
+    /// Writes every received datagram to `recorder` until [`Server::stop_recording`] is called.
+    pub fn with_recorder<W: Write + Send + 'static>(mut self, recorder: Recorder<W>) -> Server {
+        self.recorder = Some(Mutex::new(recorder.boxed()));
+        self
+    }
+
+    pub fn stop_recording(&mut self) -> io::Result<()> {
+        match self.recorder.take() {
+            Some(recorder) => recorder.into_inner().unwrap_or_else(|e| e.into_inner()).flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Forwards received datagrams to the targets of `relay`.
+    pub fn with_relay(mut self, relay: Relay) -> Server {
+        self.relay = Some(relay);
+        self
+    }
+
+    pub fn relay_stats(&self) -> Vec<RelayStats> {
+        self.relay.as_ref().map(Relay::stats).unwrap_or_default()
     }
 
+    /// Tracks lost, reordered and duplicate datagrams, [`Server::start`] logs them every
+    /// [`DIAGNOSTICS_INTERVAL`].
+    pub fn with_diagnostics(mut self, diagnostics: PacketDiagnostics) -> Server {
+        self.diagnostics = Some(Mutex::new(diagnostics));
+        self
+    }
+
+    pub fn diagnostics(&self) -> Vec<StreamStats> {
+        match &self.diagnostics {
+            Some(diagnostics) => diagnostics.lock().unwrap_or_else(|e| e.into_inner()).stats(),
+            None => vec![],
+        }
+    }
+
+    /// Emits a [`crate::diagnostics::DiagnosticsReport`] of all streams to the dispatcher.
+    pub fn publish_diagnostics(&self) {
+        if let Some(diagnostics) = &self.diagnostics {
+            let report = diagnostics.lock().unwrap_or_else(|e| e.into_inner()).report();
+            self.dispatcher.emit(&report);
+        }
+    }
+
+    /// Counters of the datagrams received so far.
+    pub fn stats(&self) -> ReceiveStats {
+        self.counters.snapshot()
+    }
+
+    /// Receives a single datagram and decodes it.
     pub fn read_packet(&self) -> io::Result<Packets> {
-        let mut buf: [u8; Self::calculate_size(PacketHeader::PACKET_SIZE)] = [0; Self::calculate_size(PacketHeader::PACKET_SIZE)];
-        self.stream.peek(&mut buf)?;
-
-        let header = PacketHeader::new(&mut ByteBuffer::from_bytes(&buf))?;
-
-        Ok(
-            match header.packet_id {
-                MotionPacket::PACKET_ID => {
-                    Packets::Motion(self.read_packet_dynamically::<MotionPacket>()?)
-                },
-                SessionDataPacket::PACKET_ID => {
-                    Packets::Session(self.read_packet_dynamically::<SessionDataPacket>()?)
-                },
-                PacketLapData::PACKET_ID => {
-                    Packets::LapData(self.read_packet_dynamically::<PacketLapData>()?)
-                },
-                PacketEventData::PACKET_ID => {
-                    Packets::Event(self.read_packet_dynamically::<PacketEventData>()?)
-                },
-                ParticipantPacket::PACKET_ID => {
-                    Packets::Participants(self.read_packet_dynamically::<ParticipantPacket>()?)
-                },
-                CarSetupPacket::PACKET_ID => {
-                    Packets::CarSetups(self.read_packet_dynamically::<CarSetupPacket>()?)
-                },
-                CarTelemetryPacket::PACKET_ID => {
-                    Packets::CarTelemetry(self.read_packet_dynamically::<CarTelemetryPacket>()?)
-                },
-                PacketCarStatus::PACKET_ID => {
-                    Packets::CarStatus(self.read_packet_dynamically::<PacketCarStatus>()?)
-                },
-                PacketFinalClassificationData::PACKET_ID => {
-                    Packets::FinalClassification(self.read_packet_dynamically::<PacketFinalClassificationData>()?)
-                },
-                PacketLobbyInfo::PACKET_ID => {
-                    Packets::LobbyInfo(self.read_packet_dynamically::<PacketLobbyInfo>()?)
-                },
-                PacketCarDamage::PACKET_ID => {
-                    Packets::CarDamage(self.read_packet_dynamically::<PacketCarDamage>()?)
-                },
-                PacketSessionHistory::PACKET_ID => {
-                    Packets::SessionHistory(self.read_packet_dynamically::<PacketSessionHistory>()?)
-                },
-                _ => Packets::Unknown,
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        let len = self.stream.recv(&mut buffer[..])?;
+        let datagram = &buffer[..len];
+
+        // A recorder that fails to write doesn't hold up relaying and decoding, the failures
+        // are counted in the stats.
+        if let Some(recorder) = &self.recorder {
+            if recorder.lock().unwrap_or_else(|e| e.into_inner()).record(datagram).is_err() {
+                self.counters.count_recording_error();
+            }
+        }
+
+        let header = PacketHeader::new(&mut PacketReader::new(datagram));
+        if let (Some(diagnostics), Ok(header)) = (&self.diagnostics, &header) {
+            diagnostics.lock().unwrap_or_else(|e| e.into_inner()).record(header);
+        }
+
+        let packet_id = header.as_ref().ok().map(|header| header.packet_id);
+        if let Some(relay) = &self.relay {
+            relay.forward(RelayStage::BeforeDecode, packet_id, datagram);
+        }
+
+        let packet = decode_with_mode(datagram, self.decode_mode);
+        self.counters.count(&packet);
+
+        if let (Some(diagnostics), Ok(packet), Ok(header)) = (&self.diagnostics, &packet, &header) {
+            if matches!(packet.event_details(), Some(EventDetails::Flashback(_))) {
+                diagnostics.lock().unwrap_or_else(|e| e.into_inner()).flashback(header.session_uid);
             }
-        )
+        }
+
+        if let Some(relay) = &self.relay {
+            match packet {
+                Ok(_) => relay.forward(RelayStage::AfterDecode, packet_id, datagram),
+                Err(_) => relay.record_dropped(RelayStage::AfterDecode),
+            }
+        }
+        Ok(packet?)
     }
 
     pub fn start(&self) {
         println!("Listening on {}", self.address);
+        let mut logged = Instant::now();
         loop {
+            if self.diagnostics.is_some() && logged.elapsed() >= DIAGNOSTICS_INTERVAL {
+                for stream in self.diagnostics() {
+                    println!("{}", stream);
+                }
+                logged = Instant::now();
+            }
+
             let packet = self.read_packet();
             match packet {
-                Ok(packet) => packet.emit_packet(),
+                Ok(Packets::Unknown) => println!("Unknown packet"),
+                Ok(packet) => self.dispatcher.dispatch(&packet),
                 Err(e) => println!("Error: {}", e),
             }
         }
     }
+}
+
+/// How often [`Server::start`] logs the diagnostics.
+pub const DIAGNOSTICS_INTERVAL: Duration = Duration::from_secs(10);
 
-    fn read_packet_dynamically<P: Packet>(&self) -> io::Result<P> {
-        let mut buf: Vec<u8> = vec![0; Self::calculate_size(P::get_packet_size())];
-        self.stream.recv(&mut buf)?;
+/// Number of received datagrams by outcome.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReceiveStats {
+    pub decoded: u64,
+    pub unknown: u64,
+    pub truncated: u64,
+    pub oversized: u64,
+    /// Datagrams of the right size that still failed to decode.
+    pub invalid: u64,
+    /// Datagrams the recorder failed to write, they are still relayed and decoded.
+    pub recording_errors: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ReceiveCounters {
+    decoded: AtomicU64,
+    unknown: AtomicU64,
+    truncated: AtomicU64,
+    oversized: AtomicU64,
+    invalid: AtomicU64,
+    recording_errors: AtomicU64,
+}
 
-        P::new(&mut ByteBuffer::from_bytes(&buf))
+impl ReceiveCounters {
+    pub(crate) fn count(&self, packet: &Result<Packets, ParseError>) {
+        let counter = match packet {
+            Ok(Packets::Unknown) => &self.unknown,
+            Ok(_) => &self.decoded,
+            Err(ParseError { kind: ParseErrorKind::Truncated { .. }, .. }) => &self.truncated,
+            Err(ParseError { kind: ParseErrorKind::Oversized { .. }, .. }) => &self.oversized,
+            Err(_) => &self.invalid,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
     }
 
-    pub const fn calculate_size(size: usize) -> usize {
-        if cfg!(windows) {
-            return size + 2048
+    pub(crate) fn count_recording_error(&self) {
+        self.recording_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> ReceiveStats {
+        ReceiveStats {
+            decoded: self.decoded.load(Ordering::Relaxed),
+            unknown: self.unknown.load(Ordering::Relaxed),
+            truncated: self.truncated.load(Ordering::Relaxed),
+            oversized: self.oversized.load(Ordering::Relaxed),
+            invalid: self.invalid.load(Ordering::Relaxed),
+            recording_errors: self.recording_errors.load(Ordering::Relaxed),
         }
-        size
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::traits::Packet;
+
+    #[test]
+    fn receives_each_datagram_once_and_counts_bad_sizes() {
+        let server = Server::new_with_address("127.0.0.1:0").unwrap();
+        let target = server.stream.local_addr().unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let mut datagram = vec![0; MotionPacket::PACKET_SIZE];
+        datagram[0..2].copy_from_slice(&2022u16.to_le_bytes());
+        datagram[5] = MotionPacket::PACKET_ID;
+
+        client.send_to(&datagram[..100], target).unwrap();
+        let mut unknown = [0; 30];
+        unknown[0..2].copy_from_slice(&2022u16.to_le_bytes());
+        unknown[5] = 200;
+        client.send_to(&unknown, target).unwrap();
+        client.send_to(&datagram, target).unwrap();
+
+        let error = server.read_packet().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(server.read_packet(), Ok(Packets::Unknown)));
+        assert!(matches!(server.read_packet(), Ok(Packets::Motion(_))));
+
+        assert_eq!(server.stats(), ReceiveStats {
+            decoded: 1,
+            unknown: 1,
+            truncated: 1,
+            ..ReceiveStats::default()
+        });
+    }
+
+    /// Accepts the capture header and fails every write after it.
+    struct FullDisk {
+        remaining: usize,
+    }
+
+    impl Write for FullDisk {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if buf.len() > self.remaining {
+                return Err(io::Error::other("disk full"));
+            }
+            self.remaining -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn recording_errors_dont_stop_decoding() {
+        let recorder = Recorder::new(FullDisk { remaining: 14 }).unwrap();
+        let server = Server::new_with_address("127.0.0.1:0").unwrap().with_recorder(recorder);
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let mut datagram = vec![0; MotionPacket::PACKET_SIZE];
+        datagram[0..2].copy_from_slice(&2022u16.to_le_bytes());
+        datagram[5] = MotionPacket::PACKET_ID;
+        client.send_to(&datagram, server.stream.local_addr().unwrap()).unwrap();
+
+        assert!(matches!(server.read_packet(), Ok(Packets::Motion(_))));
+        assert_eq!(server.stats(), ReceiveStats {
+            decoded: 1,
+            recording_errors: 1,
+            ..ReceiveStats::default()
+        });
+    }
+}
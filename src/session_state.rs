@@ -0,0 +1,241 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::models::{CarDamage, CarMotionData, CarSetup, CarStatusData, CarTelemetryData, LapData, ParticipantData, SessionDataPacket};
+use crate::models::enums::Team;
+use crate::packets::Packets;
+
+pub const MAX_CARS: usize = 22;
+
+/// Latest known data of a single car, fields stay `None` until the matching packet arrived.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CarState {
+    pub participant: Option<ParticipantData>,
+    pub telemetry: Option<CarTelemetryData>,
+    pub status: Option<CarStatusData>,
+    pub damage: Option<CarDamage>,
+    pub lap_data: Option<LapData>,
+    pub setup: Option<CarSetup>,
+    pub motion: Option<CarMotionData>,
+}
+
+impl CarState {
+    pub fn name(&self) -> Option<&str> {
+        self.participant.as_ref().map(|participant| participant.name.as_str())
+    }
+
+    pub fn team(&self) -> Option<Team> {
+        self.participant.as_ref().map(|participant| participant.team)
+    }
+}
+
+/// Combines the packets of a session into a per car view.
+///
+/// The packets of F1 2021 and F1 23 are converted to the F1 2022 types, fields those formats
+/// don't send are left zero. All state is cleared when a packet of a different session arrives.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionState {
+    session_uid: Option<u64>,
+    session: Option<SessionDataPacket>,
+    player_car_index: u8,
+    num_active_cars: u8,
+    cars: Vec<CarState>,
+}
+
+impl Default for SessionState {
+    fn default() -> SessionState {
+        SessionState {
+            session_uid: None,
+            session: None,
+            player_car_index: 0,
+            num_active_cars: 0,
+            cars: vec![CarState::default(); MAX_CARS],
+        }
+    }
+}
+
+impl SessionState {
+    pub fn new() -> SessionState {
+        SessionState::default()
+    }
+
+    /// Creates a state that is kept up to date with every packet dispatched by `dispatcher`.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<SessionState>>, Subscription) {
+        event_system::share(SessionState::new(), dispatcher, SessionState::ingest)
+    }
+
+    pub fn ingest(&mut self, packet: &Packets) {
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return,
+        };
+
+        if self.session_uid != Some(header.session_uid) {
+            self.reset();
+            self.session_uid = Some(header.session_uid);
+        }
+        self.player_car_index = header.player_car_index;
+
+        match packet {
+            Packets::Motion(packet) => self.update(&packet.car_motion_data, |car, data| car.motion = Some(*data)),
+            Packets::Motion2023(packet) => self.update(&packet.car_motion_data, |car, data| car.motion = Some(*data)),
+            Packets::Session(packet) => self.session = Some(packet.clone()),
+            Packets::Session2021(packet) => self.session = Some(packet.into()),
+            Packets::Session2023(packet) => self.session = Some(packet.into()),
+            Packets::LapData(packet) => self.update(&packet.lap_data, |car, data| car.lap_data = Some(*data)),
+            Packets::LapData2021(packet) => self.update(&packet.lap_data, |car, data| car.lap_data = Some(*data)),
+            Packets::LapData2023(packet) => self.update(&packet.lap_data, |car, data| car.lap_data = Some(data.into())),
+            Packets::Participants(packet) => {
+                self.num_active_cars = packet.num_active_cars;
+                self.update(&packet.participants, |car, data| car.participant = Some(data.clone()))
+            },
+            Packets::Participants2023(packet) => {
+                self.num_active_cars = packet.num_active_cars;
+                self.update(&packet.participants, |car, data| car.participant = Some(data.into()))
+            },
+            Packets::CarSetups(packet) => self.update(&packet.car_setups, |car, data| car.setup = Some(*data)),
+            Packets::CarTelemetry(packet) => self.update(&packet.car_telemetry_data, |car, data| car.telemetry = Some(*data)),
+            Packets::CarStatus(packet) => self.update(&packet.car_status_data, |car, data| car.status = Some(*data)),
+            Packets::CarStatus2023(packet) => self.update(&packet.car_status_data, |car, data| car.status = Some(data.into())),
+            Packets::CarDamage(packet) => self.update(&packet.car_damage_data, |car, data| car.damage = Some(*data)),
+            Packets::CarDamage2021(packet) => self.update(&packet.car_damage_data, |car, data| car.damage = Some(data.into())),
+            _ => {},
+        }
+    }
+
+    fn update<T, F: Fn(&mut CarState, &T)>(&mut self, data: &[T], f: F) {
+        self.cars.iter_mut().zip(data).for_each(|(car, data)| f(car, data));
+    }
+
+    pub fn reset(&mut self) {
+        *self = SessionState::default();
+    }
+
+    pub fn session_uid(&self) -> Option<u64> {
+        self.session_uid
+    }
+
+    pub fn session(&self) -> Option<&SessionDataPacket> {
+        self.session.as_ref()
+    }
+
+    pub fn num_active_cars(&self) -> u8 {
+        self.num_active_cars
+    }
+
+    pub fn car(&self, car_index: usize) -> Option<&CarState> {
+        self.cars.get(car_index)
+    }
+
+    pub fn cars(&self) -> &[CarState] {
+        &self.cars
+    }
+
+    pub fn player_car(&self) -> Option<&CarState> {
+        self.car(self.player_car_index as usize)
+    }
+
+    /// Iterates over the cars taking part in the session together with their index.
+    pub fn active_cars(&self) -> impl Iterator<Item = (usize, &CarState)> {
+        self.cars.iter().enumerate().take(self.num_active_cars as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketHeader, PacketLapData, ParticipantPacket};
+    use crate::models::{f1_2021, f1_2023};
+    use crate::models::traits::Packet;
+    use crate::testing::{self, packet};
+
+    fn lap_data(session_uid: u64) -> PacketLapData {
+        let mut packet = packet::<PacketLapData>(0.0);
+        packet.header.session_uid = session_uid;
+        packet
+    }
+
+    #[test]
+    fn merges_packets_and_resets_on_new_session() {
+        let mut state = SessionState::new();
+
+        let mut participants = packet::<ParticipantPacket>(0.0);
+        participants.header.session_uid = 1;
+        participants.num_active_cars = 2;
+        participants.participants[0].name = "Test".to_string();
+        state.ingest(&Packets::Participants(participants));
+
+        let mut lap = lap_data(1);
+        lap.lap_data[0].car_position = 3;
+        state.ingest(&Packets::LapData(lap));
+
+        assert_eq!(state.session_uid(), Some(1));
+        assert_eq!(state.active_cars().count(), 2);
+        let car = state.car(0).unwrap();
+        assert_eq!(car.name(), Some("Test"));
+        assert_eq!(car.lap_data.unwrap().car_position, 3);
+        assert!(car.telemetry.is_none());
+
+        state.ingest(&Packets::LapData(lap_data(2)));
+
+        assert_eq!(state.session_uid(), Some(2));
+        assert!(state.car(0).unwrap().participant.is_none());
+        assert_eq!(state.num_active_cars(), 0);
+    }
+
+    fn packet_2021<P: Packet>() -> P {
+        testing::packet_with(PacketHeader { packet_format: 2021, ..testing::header(P::PACKET_ID, 0.0) })
+    }
+
+    fn packet_2023<P: Packet>() -> P {
+        testing::packet_with(testing::header_2023(P::PACKET_ID, 0.0))
+    }
+
+    #[test]
+    fn maps_f1_2021_packets() {
+        let mut state = SessionState::new();
+        let mut session = packet_2021::<f1_2021::SessionDataPacket>();
+        session.total_laps = 57;
+        state.ingest(&Packets::Session2021(session));
+        let mut lap_data = packet_2021::<f1_2021::PacketLapData>();
+        lap_data.lap_data[0].car_position = 4;
+        state.ingest(&Packets::LapData2021(lap_data));
+        let mut damage = packet_2021::<f1_2021::PacketCarDamage>();
+        damage.car_damage_data[0].tyre_wear.rear_left = 12.5;
+        state.ingest(&Packets::CarDamage2021(damage));
+
+        assert_eq!(state.session().unwrap().total_laps, 57);
+        let car = state.car(0).unwrap();
+        assert_eq!(car.lap_data.unwrap().car_position, 4);
+        assert_eq!(car.damage.unwrap().tyre_wear.rear_left, 12.5);
+    }
+
+    #[test]
+    fn maps_f1_23_packets() {
+        let mut state = SessionState::new();
+        let mut session = packet_2023::<f1_2023::SessionDataPacket>();
+        session.total_laps = 57;
+        state.ingest(&Packets::Session2023(session));
+        let mut motion = packet_2023::<f1_2023::MotionPacket>();
+        motion.car_motion_data[0].world_position.x = 100.0;
+        state.ingest(&Packets::Motion2023(motion));
+        let mut lap_data = packet_2023::<f1_2023::PacketLapData>();
+        (lap_data.lap_data[0].car_position, lap_data.lap_data[0].current_lap_num) = (4, 2);
+        state.ingest(&Packets::LapData2023(lap_data));
+        let mut participants = packet_2023::<f1_2023::ParticipantPacket>();
+        participants.num_active_cars = 1;
+        participants.participants[0].name = "Test".to_string();
+        state.ingest(&Packets::Participants2023(participants));
+        let mut status = packet_2023::<f1_2023::PacketCarStatus>();
+        status.car_status_data[0].fuel_in_tank = 30.0;
+        state.ingest(&Packets::CarStatus2023(status));
+
+        assert_eq!(state.session().unwrap().total_laps, 57);
+        assert_eq!(state.active_cars().count(), 1);
+        let car = state.car(0).unwrap();
+        assert_eq!(car.motion.unwrap().world_position.x, 100.0);
+        assert_eq!((car.lap_data.unwrap().car_position, car.lap_data.unwrap().current_lap_num), (4, 2));
+        assert_eq!(car.name(), Some("Test"));
+        assert_eq!(car.status.unwrap().fuel_in_tank, 30.0);
+    }
+}
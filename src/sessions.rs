@@ -0,0 +1,335 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::models::EventDetails;
+use crate::models::enums::SessionType;
+use crate::packets::Packets;
+
+/// Identifiers the game uses to link the sessions of a season and a weekend.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SessionLinks {
+    pub season: u32,
+    pub weekend: u32,
+    pub session: u32,
+}
+
+/// What is known about a session, the session packet fields stay `None` until it arrived.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct SessionInfo {
+    pub session_uid: u64,
+    pub packet_format: u16,
+    pub session_type: Option<SessionType>,
+    pub track_id: Option<i8>,
+    pub links: Option<SessionLinks>,
+    /// Session time of the first packet.
+    pub started_at: f32,
+    /// Session time of the latest packet.
+    pub last_seen: f32,
+}
+
+/// Why a session ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum EndReason {
+    /// The game sent its session ended event.
+    EndEvent,
+    /// Packets of another session arrived before the end event.
+    Replaced,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct SessionStarted {
+    pub session: SessionInfo,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct SessionEnded {
+    pub session: SessionInfo,
+    pub reason: EndReason,
+}
+
+/// Packets of a new session arrived, emitted between the end of `previous` and the start of
+/// `current`.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct SessionChanged {
+    pub previous: SessionInfo,
+    pub current: SessionInfo,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum SessionNotification {
+    Started(SessionStarted),
+    Ended(SessionEnded),
+    Changed(SessionChanged),
+}
+
+impl SessionNotification {
+    /// Emits the contained notification to the handlers of its concrete type.
+    pub fn emit_to(&self, dispatcher: &Dispatcher) {
+        match self {
+            SessionNotification::Started(started) => dispatcher.emit(started),
+            SessionNotification::Ended(ended) => dispatcher.emit(ended),
+            SessionNotification::Changed(changed) => dispatcher.emit(changed),
+        }
+    }
+}
+
+/// State that lives as long as a single session, see [`SessionManager::scoped`].
+pub trait SessionScoped: Send + Sync + 'static {
+    /// Called with every packet of the session, the first packet included.
+    fn ingest(&mut self, packet: &Packets);
+
+    /// Called once when the session ended, the state stays readable until the next session starts.
+    fn finish(&mut self, _session: &SessionInfo) {}
+}
+
+trait Scope: Send + Sync {
+    fn start(&self, session: &SessionInfo);
+    fn ingest(&self, packet: &Packets);
+    fn finish(&self, session: &SessionInfo);
+}
+
+struct ScopeSlot<S, F> {
+    state: Arc<RwLock<Option<S>>>,
+    create: F,
+}
+
+impl<S: SessionScoped, F: Fn(&SessionInfo) -> S + Send + Sync> Scope for ScopeSlot<S, F> {
+    fn start(&self, session: &SessionInfo) {
+        *self.state.write().unwrap_or_else(|e| e.into_inner()) = Some((self.create)(session));
+    }
+
+    fn ingest(&self, packet: &Packets) {
+        if let Some(state) = self.state.write().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            state.ingest(packet);
+        }
+    }
+
+    fn finish(&self, session: &SessionInfo) {
+        if let Some(state) = self.state.write().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            state.finish(session);
+        }
+    }
+}
+
+fn session_details(packet: &Packets) -> Option<(SessionType, i8, SessionLinks)> {
+    macro_rules! details {
+        ($packet:expr) => {
+            Some(($packet.session_type, $packet.track_id, SessionLinks {
+                season: $packet.season_link_identifier,
+                weekend: $packet.weekend_link_identifier,
+                session: $packet.session_link_identifier,
+            }))
+        };
+    }
+
+    match packet {
+        Packets::Session(packet) => details!(packet),
+        Packets::Session2021(packet) => details!(packet),
+        Packets::Session2023(packet) => details!(packet),
+        _ => None,
+    }
+}
+
+/// Follows the sessions of the game by their `session_uid`.
+///
+/// A session starts with its first packet or its start event and ends with its end event or
+/// the first packet of another session. Packets that arrive after the end event for the same
+/// session don't start it again.
+#[derive(Default)]
+pub struct SessionManager {
+    current: Option<SessionInfo>,
+    ended: bool,
+    scopes: Vec<Box<dyn Scope>>,
+}
+
+impl SessionManager {
+    pub fn new() -> SessionManager {
+        SessionManager::default()
+    }
+
+    /// Creates a manager fed by every packet dispatched by `dispatcher`, its notifications are
+    /// emitted back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<SessionManager>>, Subscription) {
+        SessionManager::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<SessionManager>>, Subscription) {
+        event_system::attach(self, dispatcher, SessionManager::ingest, SessionNotification::emit_to)
+    }
+
+    /// Keeps a state per session, created by `create` when a session starts and finished when it
+    /// ends. The returned state is `None` until the first session started.
+    pub fn scoped<S, F>(&mut self, create: F) -> Arc<RwLock<Option<S>>>
+        where S: SessionScoped, F: Fn(&SessionInfo) -> S + Send + Sync + 'static {
+        let state = Arc::new(RwLock::new(None));
+        let slot = ScopeSlot { state: state.clone(), create };
+        if let (Some(session), false) = (&self.current, self.ended) {
+            slot.start(session);
+        }
+
+        self.scopes.push(Box::new(slot));
+        state
+    }
+
+    /// The current session, or the latest one when it ended.
+    pub fn current(&self) -> Option<&SessionInfo> {
+        self.current.as_ref()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some() && !self.ended
+    }
+
+    /// Follows `packet` and returns the notifications it caused, in the order they happened.
+    pub fn ingest(&mut self, packet: &Packets) -> Vec<SessionNotification> {
+        let mut notifications = vec![];
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return notifications,
+        };
+
+        let event = packet.event_details();
+        match self.current {
+            Some(current) if current.session_uid == header.session_uid => {},
+            previous => {
+                if let (Some(previous), false) = (previous, self.ended) {
+                    notifications.push(self.end(previous, EndReason::Replaced));
+                }
+
+                let session = SessionInfo {
+                    session_uid: header.session_uid,
+                    packet_format: header.packet_format,
+                    session_type: None,
+                    track_id: None,
+                    links: None,
+                    started_at: header.session_time,
+                    last_seen: header.session_time,
+                };
+                if let Some(previous) = previous {
+                    notifications.push(SessionNotification::Changed(SessionChanged { previous, current: session }));
+                }
+                notifications.push(self.start(session));
+            },
+        }
+
+        let ended = self.ended;
+        let current = match self.current.as_mut() {
+            Some(current) => current,
+            None => return notifications,
+        };
+        current.last_seen = current.last_seen.max(header.session_time);
+        if let Some((session_type, track_id, links)) = session_details(packet) {
+            current.session_type = Some(session_type);
+            current.track_id = Some(track_id);
+            current.links = Some(links);
+        }
+
+        if !ended {
+            self.scopes.iter().for_each(|scope| scope.ingest(packet));
+        }
+        if let (Some(EventDetails::SessionEnded), false) = (event, ended) {
+            let current = *current;
+            notifications.push(self.end(current, EndReason::EndEvent));
+        }
+
+        notifications
+    }
+
+    fn start(&mut self, session: SessionInfo) -> SessionNotification {
+        self.current = Some(session);
+        self.ended = false;
+        self.scopes.iter().for_each(|scope| scope.start(&session));
+        SessionNotification::Started(SessionStarted { session })
+    }
+
+    fn end(&mut self, session: SessionInfo, reason: EndReason) -> SessionNotification {
+        self.ended = true;
+        self.scopes.iter().for_each(|scope| scope.finish(&session));
+        SessionNotification::Ended(SessionEnded { session, reason })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketEventData, PacketHeader, SessionDataPacket};
+    use crate::models::traits::Packet;
+    use crate::testing;
+
+    fn packet<P: Packet>(session_uid: u64, session_time: f32) -> P {
+        testing::packet_with(PacketHeader { session_uid, ..testing::header(P::PACKET_ID, session_time) })
+    }
+
+    fn event(session_uid: u64, code: &[u8; 4]) -> Packets {
+        let mut event = packet::<PacketEventData>(session_uid, 0.0);
+        event.event_string_code = *code;
+        testing::received(&event)
+    }
+
+    #[derive(Default)]
+    struct PacketCount {
+        packets: usize,
+        finished: bool,
+    }
+
+    impl SessionScoped for PacketCount {
+        fn ingest(&mut self, _packet: &Packets) {
+            self.packets += 1;
+        }
+
+        fn finish(&mut self, _session: &SessionInfo) {
+            self.finished = true;
+        }
+    }
+
+    #[test]
+    fn follows_events_and_session_uids() {
+        let mut manager = SessionManager::new();
+        let count = manager.scoped(|_| PacketCount::default());
+
+        let notifications = manager.ingest(&event(1, b"SSTA"));
+        assert!(matches!(notifications[..], [SessionNotification::Started(_)]));
+        assert!(manager.ingest(&Packets::LapData(packet(1, 2.0))).is_empty());
+
+        let mut session = packet::<SessionDataPacket>(1, 3.0);
+        (session.season_link_identifier, session.weekend_link_identifier, session.session_link_identifier) = (7, 8, 9);
+        manager.ingest(&Packets::Session(session));
+        let session = *manager.current().unwrap();
+        assert_eq!(session.links, Some(SessionLinks { season: 7, weekend: 8, session: 9 }));
+        assert_eq!((session.started_at, session.last_seen), (0.0, 3.0));
+
+        let notifications = manager.ingest(&event(1, b"SEND"));
+        assert!(matches!(notifications[..], [SessionNotification::Ended(SessionEnded { reason: EndReason::EndEvent, .. })]));
+        assert!(manager.ingest(&Packets::LapData(packet(1, 4.0))).is_empty());
+        assert_eq!(count.read().unwrap().as_ref().unwrap().packets, 4);
+        assert!(count.read().unwrap().as_ref().unwrap().finished);
+
+        let notifications = manager.ingest(&Packets::LapData(packet(2, 0.0)));
+        assert!(matches!(notifications[..], [SessionNotification::Changed(_), SessionNotification::Started(_)]));
+        assert_eq!(count.read().unwrap().as_ref().unwrap().packets, 1);
+
+        let notifications = manager.ingest(&Packets::LapData(packet(3, 0.0)));
+        assert!(matches!(notifications[..], [
+            SessionNotification::Ended(SessionEnded { reason: EndReason::Replaced, .. }),
+            SessionNotification::Changed(SessionChanged { previous: SessionInfo { session_uid: 2, .. }, .. }),
+            SessionNotification::Started(SessionStarted { session: SessionInfo { session_uid: 3, .. } }),
+        ]));
+    }
+
+    #[test]
+    fn attach_emits_notifications() {
+        let dispatcher = Dispatcher::new();
+        let (_manager, _subscription) = SessionManager::attach(&dispatcher);
+        let started = Arc::new(RwLock::new(vec![]));
+
+        let shared = started.clone();
+        let _started = dispatcher.subscribe(move |started: &SessionStarted| {
+            shared.write().unwrap().push(started.session.session_uid)
+        });
+
+        dispatcher.dispatch(&Packets::LapData(packet(5, 0.0)));
+        dispatcher.dispatch(&Packets::LapData(packet(5, 1.0)));
+        dispatcher.dispatch(&Packets::LapData(packet(6, 0.0)));
+        assert_eq!(*started.read().unwrap(), [5, 6]);
+    }
+}
@@ -0,0 +1,469 @@
+//! A model of the car data that doesn't depend on the game year that sent it.
+//!
+//! Every year's car telemetry, lap data, car status and car damage converts into the types of
+//! this module. Values a year doesn't send are `None`, so analytics code only needs to handle
+//! [`Frame`] and [`CarSnapshot`].
+
+use serde::Serialize;
+use crate::models::{f1_2021, f1_2023, CarDamage, CarMotionData, CarStatusData, CarTelemetryData, LapData, PacketHeader, WheelsVector};
+use crate::models::enums::{ActualTyreCompound, DriverStatus, ErsMode, FuelMix, PitStatus, ResultStatus, Sector, TractionControlStatus, VehicleFiaFlags, VisualTyreCompound};
+use crate::packets::Packets;
+use crate::session_state::MAX_CARS;
+
+/// Driver inputs and car sensors.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Telemetry {
+    /// Speed in kilometres per hour.
+    pub speed: u16,
+    pub throttle: f32,
+    pub steer: f32,
+    pub brake: f32,
+    pub clutch: u8,
+    pub gear: i8,
+    pub engine_rpm: u16,
+    pub drs: bool,
+    pub brakes_temperature: WheelsVector<u16>,
+    pub tyres_surface_temperature: WheelsVector<u8>,
+    pub tyres_inner_temperature: WheelsVector<u8>,
+    pub engine_temperature: u16,
+    pub tyres_pressure: WheelsVector<f32>,
+}
+
+impl From<&CarTelemetryData> for Telemetry {
+    fn from(data: &CarTelemetryData) -> Telemetry {
+        Telemetry {
+            speed: data.speed,
+            throttle: data.throttle,
+            steer: data.steer,
+            brake: data.brake,
+            clutch: data.clutch,
+            gear: data.gear,
+            engine_rpm: data.engine_rpm,
+            drs: data.drs,
+            brakes_temperature: data.brakes_temperature,
+            tyres_surface_temperature: data.tyres_surface_temperature,
+            tyres_inner_temperature: data.tyres_inner_temperature,
+            engine_temperature: data.engine_temperature,
+            tyres_pressure: data.tyres_pressure,
+        }
+    }
+}
+
+/// Progress of the current lap and the race, all times are in milliseconds.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Lap {
+    pub last_lap_time: u32,
+    pub current_lap_time: u32,
+    pub sector1_time: u32,
+    pub sector2_time: u32,
+    /// Only sent since F1 23.
+    pub delta_to_car_in_front: Option<u16>,
+    /// Only sent since F1 23.
+    pub delta_to_race_leader: Option<u16>,
+    pub lap_distance: f32,
+    pub total_distance: f32,
+    pub safety_car_delta: f32,
+    pub car_position: u8,
+    pub current_lap_num: u8,
+    pub pit_status: PitStatus,
+    pub num_pit_stops: u8,
+    pub sector: Sector,
+    pub current_lap_invalid: bool,
+    pub penalties: u8,
+    pub warnings: u8,
+    /// Only sent since F1 23.
+    pub corner_cutting_warnings: Option<u8>,
+    pub num_unserved_drive_through_penalties: u8,
+    pub num_unserved_stop_go_penalties: u8,
+    pub grid_position: u8,
+    pub driver_status: DriverStatus,
+    pub result_status: ResultStatus,
+    pub pit_lane_timer_active: bool,
+    pub pit_lane_time_in_lane: u16,
+    pub pit_stop_timer: u16,
+    pub pit_stop_should_serve_penalty: bool,
+}
+
+/// F1 2021 and F1 2022 share the lap data layout.
+impl From<&LapData> for Lap {
+    fn from(data: &LapData) -> Lap {
+        Lap {
+            last_lap_time: data.last_lap_time,
+            current_lap_time: data.current_lap_time,
+            sector1_time: data.sector1_time as u32,
+            sector2_time: data.sector2_time as u32,
+            delta_to_car_in_front: None,
+            delta_to_race_leader: None,
+            lap_distance: data.lap_distance,
+            total_distance: data.total_distance,
+            safety_car_delta: data.safety_car_delta,
+            car_position: data.car_position,
+            current_lap_num: data.current_lap_num,
+            pit_status: data.pit_status,
+            num_pit_stops: data.num_pit_stops,
+            sector: data.sector,
+            current_lap_invalid: data.current_lap_invalid,
+            penalties: data.penalties,
+            warnings: data.warnings,
+            corner_cutting_warnings: None,
+            num_unserved_drive_through_penalties: data.num_unserved_drive_through_penalties,
+            num_unserved_stop_go_penalties: data.num_unserved_stop_go_penalties,
+            grid_position: data.grid_position,
+            driver_status: data.driver_status,
+            result_status: data.result_status,
+            pit_lane_timer_active: data.pit_lane_timer_active,
+            pit_lane_time_in_lane: data.pit_lane_time_in_lane,
+            pit_stop_timer: data.pit_stop_timer,
+            pit_stop_should_serve_penalty: data.pit_stop_should_serve_penalty,
+        }
+    }
+}
+
+impl From<&f1_2023::LapData> for Lap {
+    fn from(data: &f1_2023::LapData) -> Lap {
+        Lap {
+            last_lap_time: data.last_lap_time,
+            current_lap_time: data.current_lap_time,
+            sector1_time: data.sector1_time(),
+            sector2_time: data.sector2_time(),
+            delta_to_car_in_front: Some(data.delta_to_car_in_front),
+            delta_to_race_leader: Some(data.delta_to_race_leader),
+            lap_distance: data.lap_distance,
+            total_distance: data.total_distance,
+            safety_car_delta: data.safety_car_delta,
+            car_position: data.car_position,
+            current_lap_num: data.current_lap_num,
+            pit_status: data.pit_status,
+            num_pit_stops: data.num_pit_stops,
+            sector: data.sector,
+            current_lap_invalid: data.current_lap_invalid,
+            penalties: data.penalties,
+            warnings: data.warnings,
+            corner_cutting_warnings: Some(data.corner_cutting_warnings),
+            num_unserved_drive_through_penalties: data.num_unserved_drive_through_penalties,
+            num_unserved_stop_go_penalties: data.num_unserved_stop_go_penalties,
+            grid_position: data.grid_position,
+            driver_status: data.driver_status,
+            result_status: data.result_status,
+            pit_lane_timer_active: data.pit_lane_timer_active,
+            pit_lane_time_in_lane: data.pit_lane_time_in_lane,
+            pit_stop_timer: data.pit_stop_timer,
+            pit_stop_should_serve_penalty: data.pit_stop_should_serve_penalty,
+        }
+    }
+}
+
+/// Car settings, fuel, tyres and energy store.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Status {
+    pub traction_control: TractionControlStatus,
+    pub anti_lock_brakes: bool,
+    pub fuel_mix: FuelMix,
+    pub front_brake_bias: u8,
+    pub pit_limiter_status: bool,
+    pub fuel_in_tank: f32,
+    pub fuel_capacity: f32,
+    pub fuel_remaining_laps: f32,
+    pub max_rpm: u16,
+    pub idle_rpm: u16,
+    pub max_gears: u8,
+    pub drs_allowed: bool,
+    pub drs_activation_distance: u16,
+    pub actual_tyre_compound: ActualTyreCompound,
+    pub visual_tyre_compound: VisualTyreCompound,
+    pub tyres_age_laps: u8,
+    pub vehicle_fia_flags: VehicleFiaFlags,
+    /// Power of the combustion engine in watts, only sent since F1 23.
+    pub engine_power_ice: Option<f32>,
+    /// Power of the MGU-K in watts, only sent since F1 23.
+    pub engine_power_mguk: Option<f32>,
+    pub ers_store_energy: f32,
+    pub ers_deploy_mode: ErsMode,
+    pub ers_harvested_this_lap_mguk: f32,
+    pub ers_harvested_this_lap_mguh: f32,
+    pub ers_deployed_this_lap: f32,
+    pub network_paused: bool,
+}
+
+/// F1 2021 and F1 2022 share the car status layout.
+impl From<&CarStatusData> for Status {
+    fn from(data: &CarStatusData) -> Status {
+        Status {
+            traction_control: data.traction_control,
+            anti_lock_brakes: data.anti_lock_brakes,
+            fuel_mix: data.fuel_mix,
+            front_brake_bias: data.front_brake_bias,
+            pit_limiter_status: data.pit_limiter_status,
+            fuel_in_tank: data.fuel_in_tank,
+            fuel_capacity: data.fuel_capacity,
+            fuel_remaining_laps: data.fuel_remaining_laps,
+            max_rpm: data.max_rpm,
+            idle_rpm: data.idle_rpm,
+            max_gears: data.max_gears,
+            drs_allowed: data.drs_allowed,
+            drs_activation_distance: data.drs_activation_distance,
+            actual_tyre_compound: data.actual_tyre_compound,
+            visual_tyre_compound: data.visual_tyre_compound,
+            tyres_age_laps: data.tyres_age_laps,
+            vehicle_fia_flags: data.vehicle_fia_flags,
+            engine_power_ice: None,
+            engine_power_mguk: None,
+            ers_store_energy: data.ers_store_energy,
+            ers_deploy_mode: data.ers_deploy_mode,
+            ers_harvested_this_lap_mguk: data.ers_harvested_this_lap_mguk,
+            ers_harvested_this_lap_mguh: data.ers_harvested_this_lap_mguh,
+            ers_deployed_this_lap: data.ers_deployed_this_lap,
+            network_paused: data.network_paused,
+        }
+    }
+}
+
+impl From<&f1_2023::CarStatusData> for Status {
+    fn from(data: &f1_2023::CarStatusData) -> Status {
+        Status {
+            traction_control: data.traction_control,
+            anti_lock_brakes: data.anti_lock_brakes,
+            fuel_mix: data.fuel_mix,
+            front_brake_bias: data.front_brake_bias,
+            pit_limiter_status: data.pit_limiter_status,
+            fuel_in_tank: data.fuel_in_tank,
+            fuel_capacity: data.fuel_capacity,
+            fuel_remaining_laps: data.fuel_remaining_laps,
+            max_rpm: data.max_rpm,
+            idle_rpm: data.idle_rpm,
+            max_gears: data.max_gears,
+            drs_allowed: data.drs_allowed,
+            drs_activation_distance: data.drs_activation_distance,
+            actual_tyre_compound: data.actual_tyre_compound,
+            visual_tyre_compound: data.visual_tyre_compound,
+            tyres_age_laps: data.tyres_age_laps,
+            vehicle_fia_flags: data.vehicle_fia_flags,
+            engine_power_ice: Some(data.engine_power_ice),
+            engine_power_mguk: Some(data.engine_power_mguk),
+            ers_store_energy: data.ers_store_energy,
+            ers_deploy_mode: data.ers_deploy_mode,
+            ers_harvested_this_lap_mguk: data.ers_harvested_this_lap_mguk,
+            ers_harvested_this_lap_mguh: data.ers_harvested_this_lap_mguh,
+            ers_deployed_this_lap: data.ers_deployed_this_lap,
+            network_paused: data.network_paused,
+        }
+    }
+}
+
+/// Wear and damage in percent.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Damage {
+    pub tyre_wear: WheelsVector<f32>,
+    pub tyre_damage: WheelsVector<u8>,
+    pub brakes_damage: WheelsVector<u8>,
+    pub front_left_wing_damage: u8,
+    pub front_right_wing_damage: u8,
+    pub rear_wing_damage: u8,
+    pub floor_damage: u8,
+    pub diffuser_damage: u8,
+    pub sidepod_damage: u8,
+    pub drs_fault: bool,
+    /// Only sent since F1 2022.
+    pub ers_fault: Option<bool>,
+    pub gear_box_damage: u8,
+    pub engine_damage: u8,
+    pub engine_mguh_wear: u8,
+    pub engine_es_wear: u8,
+    pub engine_ce_wear: u8,
+    pub engine_ice_wear: u8,
+    pub engine_mguk_wear: u8,
+    pub engine_tc_wear: u8,
+    /// Only sent since F1 2022.
+    pub engine_blown: Option<bool>,
+    /// Only sent since F1 2022.
+    pub engine_seized: Option<bool>,
+}
+
+/// F1 2022 and F1 23 share the car damage layout.
+impl From<&CarDamage> for Damage {
+    fn from(data: &CarDamage) -> Damage {
+        Damage {
+            tyre_wear: data.tyre_wear,
+            tyre_damage: data.tyre_damage,
+            brakes_damage: data.brakes_damage,
+            front_left_wing_damage: data.front_left_wing_damage,
+            front_right_wing_damage: data.front_right_wing_damage,
+            rear_wing_damage: data.rear_wing_damage,
+            floor_damage: data.floor_damage,
+            diffuser_damage: data.diffuser_damage,
+            sidepod_damage: data.sidepod_damage,
+            drs_fault: data.drs_fault,
+            ers_fault: Some(data.ers_fault),
+            gear_box_damage: data.gear_box_damage,
+            engine_damage: data.engine_damage,
+            engine_mguh_wear: data.engine_mguh_wear,
+            engine_es_wear: data.engine_es_wear,
+            engine_ce_wear: data.engine_ce_wear,
+            engine_ice_wear: data.engine_ice_wear,
+            engine_mguk_wear: data.engine_mguk_wear,
+            engine_tc_wear: data.engine_tc_wear,
+            engine_blown: Some(data.engine_blown),
+            engine_seized: Some(data.engine_seized),
+        }
+    }
+}
+
+impl From<&f1_2021::CarDamage> for Damage {
+    fn from(data: &f1_2021::CarDamage) -> Damage {
+        Damage {
+            tyre_wear: data.tyre_wear,
+            tyre_damage: data.tyre_damage,
+            brakes_damage: data.brakes_damage,
+            front_left_wing_damage: data.front_left_wing_damage,
+            front_right_wing_damage: data.front_right_wing_damage,
+            rear_wing_damage: data.rear_wing_damage,
+            floor_damage: data.floor_damage,
+            diffuser_damage: data.diffuser_damage,
+            sidepod_damage: data.sidepod_damage,
+            drs_fault: data.drs_fault,
+            ers_fault: None,
+            gear_box_damage: data.gear_box_damage,
+            engine_damage: data.engine_damage,
+            engine_mguh_wear: data.engine_mguh_wear,
+            engine_es_wear: data.engine_es_wear,
+            engine_ce_wear: data.engine_ce_wear,
+            engine_ice_wear: data.engine_ice_wear,
+            engine_mguk_wear: data.engine_mguk_wear,
+            engine_tc_wear: data.engine_tc_wear,
+            engine_blown: None,
+            engine_seized: None,
+        }
+    }
+}
+
+/// Latest known data of a single car, fields stay `None` until the matching packet arrived.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize)]
+pub struct CarSnapshot {
+    pub motion: Option<CarMotionData>,
+    pub telemetry: Option<Telemetry>,
+    pub lap: Option<Lap>,
+    pub status: Option<Status>,
+    pub damage: Option<Damage>,
+}
+
+/// The car data of every car at one point of a session.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Frame {
+    pub packet_format: u16,
+    pub session_uid: u64,
+    pub session_time: f32,
+    pub frame_identifier: u32,
+    pub player_car_index: u8,
+    pub cars: Vec<CarSnapshot>,
+}
+
+fn update<'a, T: 'a, C>(cars: &mut [CarSnapshot], data: impl IntoIterator<Item = &'a T>, field: C)
+    where C: Fn(&mut CarSnapshot, &'a T) {
+    for (car, data) in cars.iter_mut().zip(data) {
+        field(car, data);
+    }
+}
+
+impl Frame {
+    pub fn new(header: &PacketHeader) -> Frame {
+        Frame {
+            packet_format: header.packet_format,
+            session_uid: header.session_uid,
+            session_time: header.session_time,
+            frame_identifier: header.frame_identifier,
+            player_car_index: header.player_car_index,
+            cars: vec![CarSnapshot::default(); MAX_CARS],
+        }
+    }
+
+    /// Builds a frame holding the car data of a single packet.
+    pub fn from_packet(packet: &Packets) -> Option<Frame> {
+        let mut frame = Frame::new(packet.header()?);
+        frame.update(packet).then_some(frame)
+    }
+
+    pub fn car(&self, car_idx: usize) -> Option<&CarSnapshot> {
+        self.cars.get(car_idx)
+    }
+
+    pub fn player(&self) -> Option<&CarSnapshot> {
+        self.car(self.player_car_index as usize)
+    }
+
+    /// Takes over the car data of `packet`, returns `false` for packets without data the
+    /// facade covers.
+    pub fn update(&mut self, packet: &Packets) -> bool {
+        let cars = &mut self.cars;
+        match packet {
+            Packets::Motion(packet) => update(cars, &packet.car_motion_data, |car, data| car.motion = Some(*data)),
+            Packets::Motion2023(packet) => update(cars, &packet.car_motion_data, |car, data| car.motion = Some(*data)),
+            Packets::CarTelemetry(packet) => update(cars, &packet.car_telemetry_data, |car, data| car.telemetry = Some(data.into())),
+            Packets::LapData(packet) => update(cars, &packet.lap_data, |car, data| car.lap = Some(data.into())),
+            Packets::LapData2021(packet) => update(cars, &packet.lap_data, |car, data| car.lap = Some(data.into())),
+            Packets::LapData2023(packet) => update(cars, &packet.lap_data, |car, data| car.lap = Some(data.into())),
+            Packets::CarStatus(packet) => update(cars, &packet.car_status_data, |car, data| car.status = Some(data.into())),
+            Packets::CarStatus2023(packet) => update(cars, &packet.car_status_data, |car, data| car.status = Some(data.into())),
+            Packets::CarDamage(packet) => update(cars, &packet.car_damage_data, |car, data| car.damage = Some(data.into())),
+            Packets::CarDamage2021(packet) => update(cars, &packet.car_damage_data, |car, data| car.damage = Some(data.into())),
+            _ => return false,
+        }
+
+        if let Some(header) = packet.header() {
+            self.packet_format = header.packet_format;
+            self.session_uid = header.session_uid;
+            self.session_time = header.session_time;
+            self.frame_identifier = header.frame_identifier;
+            self.player_car_index = header.player_car_index;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DecodeMode, PacketReader};
+    use crate::models::traits::Packet;
+    use crate::packets::decode_with_mode;
+
+    fn datagram(packet_format: u16, packet_id: u8, size: usize) -> Vec<u8> {
+        let mut datagram = vec![0; size];
+        datagram[0..2].copy_from_slice(&packet_format.to_le_bytes());
+        datagram[if packet_format >= 2023 { 6 } else { 5 }] = packet_id;
+        datagram
+    }
+
+    #[test]
+    fn converts_every_year() {
+        let mut bytes = [0; 50];
+        bytes[8..10].copy_from_slice(&500u16.to_le_bytes());
+        bytes[10] = 1;
+        bytes[16..18].copy_from_slice(&2500u16.to_le_bytes());
+        let lap = f1_2023::LapData::new(&mut PacketReader::with_mode(&bytes[..], DecodeMode::Lenient)).unwrap();
+        let lap = Lap::from(&lap);
+        assert_eq!(lap.sector1_time, 60_500);
+        assert_eq!(lap.delta_to_race_leader, Some(2500));
+
+        let lap = LapData::new(&mut PacketReader::with_mode(&[0; 43][..], DecodeMode::Lenient)).unwrap();
+        assert_eq!(Lap::from(&lap).delta_to_race_leader, None);
+
+        let damage = f1_2021::CarDamage::new(&mut PacketReader::new(&[0; 39][..])).unwrap();
+        let damage = Damage::from(&damage);
+        assert_eq!((damage.ers_fault, damage.engine_blown), (None, None));
+    }
+
+    #[test]
+    fn merges_packets_into_a_frame() {
+        let lap_data = datagram(2021, f1_2021::PacketLapData::PACKET_ID, f1_2021::PacketLapData::PACKET_SIZE);
+        let damage = datagram(2021, f1_2021::PacketCarDamage::PACKET_ID, f1_2021::PacketCarDamage::PACKET_SIZE);
+
+        let mut frame = Frame::from_packet(&decode_with_mode(&lap_data, DecodeMode::Lenient).unwrap()).unwrap();
+        assert!(frame.update(&decode_with_mode(&damage, DecodeMode::Lenient).unwrap()));
+        assert!(!frame.update(&Packets::Unknown));
+
+        let player = frame.player().unwrap();
+        assert!(player.lap.is_some() && player.damage.is_some());
+        assert!(player.telemetry.is_none());
+        assert_eq!(frame.packet_format, 2021);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::models::{DecodeMode, PacketHeader};
+use crate::models::traits::Packet;
+use crate::packets::{decode_with_mode, Packets};
+
+/// Header of an F1 2022 packet with `packet_id` sent at `session_time`.
+pub(crate) fn header(packet_id: u8, session_time: f32) -> PacketHeader {
+    PacketHeader {
+        packet_format: 2022,
+        game_year: None,
+        game_major_version: 0,
+        game_minor_version: 0,
+        packet_version: 0,
+        packet_id,
+        session_uid: 0,
+        session_time,
+        frame_identifier: 0,
+        overall_frame_identifier: None,
+        player_car_index: 0,
+        secondary_player_car_index: 0,
+    }
+}
+
+/// Header of an F1 23 packet with `packet_id` sent at `session_time`.
+pub(crate) fn header_2023(packet_id: u8, session_time: f32) -> PacketHeader {
+    PacketHeader {
+        packet_format: 2023,
+        game_year: Some(23),
+        overall_frame_identifier: Some(0),
+        ..header(packet_id, session_time)
+    }
+}
+
+/// Decodes a zeroed packet sent with `header`, tests set the fields they need on it.
+pub(crate) fn packet_with<P: Packet>(header: PacketHeader) -> P {
+    let mut datagram = Vec::with_capacity(P::PACKET_SIZE);
+    header.write_to(&mut datagram).unwrap();
+    datagram.resize(P::PACKET_SIZE, 0);
+    P::new_lenient(&mut &datagram[..]).unwrap()
+}
+
+/// Decodes a zeroed F1 2022 packet sent at `session_time`.
+pub(crate) fn packet<P: Packet>(session_time: f32) -> P {
+    packet_with(header(P::PACKET_ID, session_time))
+}
+
+/// Encodes `packet` and decodes it again the way the server receives it.
+pub(crate) fn received<P: Packet>(packet: &P) -> Packets {
+    decode_with_mode(&packet.to_bytes(), DecodeMode::Lenient).unwrap()
+}
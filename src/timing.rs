@@ -0,0 +1,330 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::frames::PacketKind;
+use crate::models::enums::{PitStatus, ResultStatus};
+use crate::packets::Packets;
+use crate::session_state::MAX_CARS;
+use crate::telemetry::{Frame, Lap};
+
+/// Distance in metres between two checkpoints.
+pub const DEFAULT_CHECKPOINT_SPACING: f32 = 25.0;
+
+/// The session times a car passed the checkpoints of its total distance.
+#[derive(Debug, Clone, Default)]
+struct Checkpoints {
+    /// Index of the first recorded checkpoint, cars can be first seen anywhere on track.
+    first: usize,
+    times: Vec<f32>,
+    /// The latest sample as total distance and session time.
+    last: Option<(f32, f32)>,
+}
+
+impl Checkpoints {
+    fn record(&mut self, spacing: f32, distance: f32, session_time: f32) {
+        let (last_distance, last_time) = match self.last {
+            Some((last_distance, _)) if distance < last_distance => {
+                // A flashback, the checkpoints after the car are driven again.
+                let keep = ((distance / spacing).floor().max(0.0) as usize + 1).saturating_sub(self.first);
+                self.times.truncate(keep);
+                self.last = Some((distance, session_time));
+                return;
+            },
+            Some(last) => last,
+            None => {
+                self.first = (distance / spacing).ceil().max(0.0) as usize;
+                self.last = Some((distance, session_time));
+                if distance >= 0.0 && distance == self.first as f32 * spacing {
+                    self.times.push(session_time);
+                }
+                return;
+            },
+        };
+
+        let mut index = self.first + self.times.len();
+        while index as f32 * spacing <= distance {
+            let checkpoint = index as f32 * spacing;
+            if checkpoint > last_distance {
+                let share = (checkpoint - last_distance) / (distance - last_distance);
+                self.times.push(last_time + (session_time - last_time) * share);
+            } else {
+                // The car was sitting on the checkpoint at the previous sample.
+                self.times.push(last_time);
+            }
+            index += 1;
+        }
+        self.last = Some((distance, session_time));
+    }
+
+    fn checkpoint(&self, spacing: f32, index: usize) -> Option<(f32, f32)> {
+        let time = *self.times.get(index.checked_sub(self.first)?)?;
+        Some((index as f32 * spacing, time))
+    }
+
+    /// The session time the car passed `distance`, `None` when it didn't get there yet or was
+    /// first seen after it.
+    fn time_at(&self, spacing: f32, distance: f32) -> Option<f32> {
+        let index = (distance / spacing).floor();
+        if index < 0.0 {
+            return None;
+        }
+
+        let (lower_distance, lower_time) = self.checkpoint(spacing, index as usize)?;
+        let (upper_distance, upper_time) = self.checkpoint(spacing, index as usize + 1)
+            .or(self.last.filter(|(last_distance, _)| *last_distance >= distance))?;
+        if upper_distance <= lower_distance {
+            return Some(lower_time);
+        }
+
+        let share = (distance - lower_distance) / (upper_distance - lower_distance);
+        Some(lower_time + (upper_time - lower_time) * share)
+    }
+}
+
+/// A car on the leaderboard, gaps are in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimingEntry {
+    pub position: u8,
+    pub car_idx: u8,
+    /// Empty until the participants packet arrived.
+    pub name: String,
+    pub lap: u8,
+    pub last_lap_time: u32,
+    /// `None` for the leader and cars whose checkpoints aren't known yet.
+    pub gap_to_leader: Option<f32>,
+    pub gap_to_car_ahead: Option<f32>,
+    /// Full laps the car is behind the leader.
+    pub laps_behind_leader: u8,
+    pub laps_behind_car_ahead: u8,
+    pub in_pits: bool,
+    pub result_status: ResultStatus,
+}
+
+/// The running order of a session.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Leaderboard {
+    pub session_uid: u64,
+    pub session_time: f32,
+    /// Sorted by position.
+    pub entries: Vec<TimingEntry>,
+}
+
+impl Leaderboard {
+    pub fn leader(&self) -> Option<&TimingEntry> {
+        self.entries.first()
+    }
+
+    pub fn car(&self, car_idx: u8) -> Option<&TimingEntry> {
+        self.entries.iter().find(|entry| entry.car_idx == car_idx)
+    }
+}
+
+fn laps_behind(ahead: &Lap, behind: &Lap) -> u8 {
+    let laps = ahead.current_lap_num as i32 - behind.current_lap_num as i32;
+    let laps = if ahead.lap_distance < behind.lap_distance { laps - 1 } else { laps };
+    laps.clamp(0, u8::MAX as i32) as u8
+}
+
+/// Computes the gaps between cars from the time they passed the same distance.
+///
+/// Every lap data packet records when each car passed the checkpoints along its total
+/// distance. The gap of a car to a car ahead is the time since the car ahead passed the spot
+/// the car is at now, which also holds for lapped cars.
+#[derive(Debug, Clone)]
+pub struct TimingTower {
+    spacing: f32,
+    frame: Option<Frame>,
+    names: Vec<String>,
+    checkpoints: Vec<Checkpoints>,
+}
+
+impl Default for TimingTower {
+    fn default() -> TimingTower {
+        TimingTower::with_checkpoint_spacing(DEFAULT_CHECKPOINT_SPACING)
+    }
+}
+
+impl TimingTower {
+    pub fn new() -> TimingTower {
+        TimingTower::default()
+    }
+
+    /// Records a checkpoint every `spacing` metres, smaller spacings give more precise gaps.
+    pub fn with_checkpoint_spacing(spacing: f32) -> TimingTower {
+        TimingTower {
+            spacing: spacing.max(1.0),
+            frame: None,
+            names: vec![String::new(); MAX_CARS],
+            checkpoints: vec![Checkpoints::default(); MAX_CARS],
+        }
+    }
+
+    /// Creates a tower fed by every packet dispatched by `dispatcher`, the leaderboard is emitted
+    /// back to the same dispatcher after every lap data packet.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<TimingTower>>, Subscription) {
+        TimingTower::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<TimingTower>>, Subscription) {
+        event_system::attach(self, dispatcher, TimingTower::ingest, |leaderboard, dispatcher| dispatcher.emit(leaderboard))
+    }
+
+    pub fn reset(&mut self) {
+        *self = TimingTower::with_checkpoint_spacing(self.spacing);
+    }
+
+    /// Updates the checkpoints and returns the new leaderboard for lap data packets.
+    pub fn ingest(&mut self, packet: &Packets) -> Option<Leaderboard> {
+        let header = packet.header()?;
+        if self.frame.as_ref().is_some_and(|frame| frame.session_uid != header.session_uid) {
+            self.reset();
+        }
+
+        match packet {
+            Packets::Participants(packet) => self.update_names(packet.participants.iter().map(|participant| &participant.name)),
+            Packets::Participants2023(packet) => self.update_names(packet.participants.iter().map(|participant| &participant.name)),
+            _ => {},
+        }
+
+        let frame = self.frame.get_or_insert_with(|| Frame::new(header));
+        if !frame.update(packet) || PacketKind::of(packet) != Some(PacketKind::LapData) {
+            return None;
+        }
+
+        for (checkpoints, car) in self.checkpoints.iter_mut().zip(&frame.cars) {
+            if let Some(lap) = &car.lap {
+                checkpoints.record(self.spacing, lap.total_distance, header.session_time);
+            }
+        }
+
+        Some(self.leaderboard())
+    }
+
+    fn update_names<'a>(&mut self, names: impl Iterator<Item = &'a String>) {
+        self.names.iter_mut().zip(names).for_each(|(name, new)| name.clone_from(new));
+    }
+
+    /// Time in seconds `behind` is behind `ahead`, `None` when it isn't known.
+    pub fn gap(&self, ahead: usize, behind: usize) -> Option<f32> {
+        let lap = self.frame.as_ref()?.car(behind)?.lap?;
+        let (_, now) = self.checkpoints.get(behind)?.last?;
+        let passed = self.checkpoints.get(ahead)?.time_at(self.spacing, lap.total_distance)?;
+        Some((now - passed).max(0.0))
+    }
+
+    /// The cars that take part, sorted by position.
+    pub fn leaderboard(&self) -> Leaderboard {
+        let frame = match &self.frame {
+            Some(frame) => frame,
+            None => return Leaderboard { session_uid: 0, session_time: 0.0, entries: vec![] },
+        };
+
+        let mut cars: Vec<(usize, Lap)> = frame.cars.iter().enumerate()
+            .filter_map(|(car_idx, car)| car.lap.map(|lap| (car_idx, lap)))
+            .filter(|(_, lap)| lap.car_position > 0 && !matches!(lap.result_status, ResultStatus::Invalid | ResultStatus::Inactive))
+            .collect();
+        cars.sort_by_key(|(_, lap)| lap.car_position);
+
+        let entries = cars.iter().enumerate().map(|(i, (car_idx, lap))| {
+            let leader = cars.first().filter(|_| i > 0);
+            let ahead = i.checked_sub(1).map(|ahead| &cars[ahead]);
+
+            TimingEntry {
+                position: lap.car_position,
+                car_idx: *car_idx as u8,
+                name: self.names[*car_idx].clone(),
+                lap: lap.current_lap_num,
+                last_lap_time: lap.last_lap_time,
+                gap_to_leader: leader.and_then(|(leader, _)| self.gap(*leader, *car_idx)),
+                gap_to_car_ahead: ahead.and_then(|(ahead, _)| self.gap(*ahead, *car_idx)),
+                laps_behind_leader: leader.map_or(0, |(_, leader)| laps_behind(leader, lap)),
+                laps_behind_car_ahead: ahead.map_or(0, |(_, ahead)| laps_behind(ahead, lap)),
+                in_pits: lap.pit_status != PitStatus::None,
+                result_status: lap.result_status,
+            }
+        }).collect();
+
+        Leaderboard {
+            session_uid: frame.session_uid,
+            session_time: frame.session_time,
+            entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketLapData, ParticipantPacket};
+    use crate::testing::packet;
+
+    const TRACK_LENGTH: f32 = 5000.0;
+
+    /// Cars as position and total distance.
+    fn lap_data(session_time: f32, cars: &[(u8, f32)]) -> Packets {
+        let mut packet = packet::<PacketLapData>(session_time);
+        for (lap_data, (position, distance)) in packet.lap_data.iter_mut().zip(cars) {
+            lap_data.lap_distance = distance % TRACK_LENGTH;
+            lap_data.total_distance = *distance;
+            lap_data.car_position = *position;
+            lap_data.current_lap_num = (distance / TRACK_LENGTH) as u8 + 1;
+            lap_data.result_status = ResultStatus::Active;
+        }
+        Packets::LapData(packet)
+    }
+
+    #[test]
+    fn computes_gaps_from_checkpoints() {
+        let mut tower = TimingTower::new();
+        let mut participants = packet::<ParticipantPacket>(0.0);
+        participants.num_active_cars = 3;
+        for (participant, name) in participants.participants.iter_mut().zip(["ALO", "HAM", "SAI"]) {
+            participant.name = name.to_string();
+        }
+        tower.ingest(&Packets::Participants(participants));
+
+        // Car 0 drives 50 m/s, car 1 follows 100 m behind, car 2 is a lap down.
+        let mut leaderboard = None;
+        for step in 0..=20 {
+            let time = step as f32;
+            let leader = 10_000.0 + 50.0 * time;
+            leaderboard = tower.ingest(&lap_data(time, &[(1, leader), (2, leader - 100.0), (3, leader - 5050.0)]));
+        }
+
+        let leaderboard = leaderboard.unwrap();
+        let order: Vec<&str> = leaderboard.entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(order, ["ALO", "HAM", "SAI"]);
+        assert_eq!(leaderboard.leader().unwrap().gap_to_leader, None);
+
+        let second = leaderboard.car(1).unwrap();
+        assert!((second.gap_to_leader.unwrap() - 2.0).abs() < 0.01);
+        assert!((second.gap_to_car_ahead.unwrap() - 2.0).abs() < 0.01);
+        assert_eq!(second.laps_behind_leader, 0);
+
+        // The lapped car is only known behind the car ahead, the leader passed its spot before
+        // the first sample.
+        let third = leaderboard.car(2).unwrap();
+        assert_eq!(third.gap_to_leader, None);
+        assert_eq!(third.gap_to_car_ahead, None);
+        assert_eq!((third.laps_behind_leader, third.laps_behind_car_ahead), (1, 0));
+    }
+
+    #[test]
+    fn lapped_cars_are_timed_once_the_leader_passed_them() {
+        let mut tower = TimingTower::with_checkpoint_spacing(10.0);
+        for step in 0..=120 {
+            let time = step as f32;
+            tower.ingest(&lap_data(time, &[(1, 1000.0 + 80.0 * time), (2, 1000.0 + 40.0 * time)]));
+        }
+
+        // The leader gained 4800 m at 80 m/s.
+        let leaderboard = tower.leaderboard();
+        let second = leaderboard.car(1).unwrap();
+        assert!((second.gap_to_leader.unwrap() - 60.0).abs() < 0.01);
+
+        // A flashback drops the checkpoints the cars pass again, the old times would give 9.4 s.
+        tower.ingest(&lap_data(50.0, &[(1, 5000.0), (2, 3000.0)]));
+        tower.ingest(&lap_data(60.0, &[(1, 5100.0), (2, 5050.0)]));
+        assert!((tower.gap(0, 1).unwrap() - 5.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,344 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::event_system::{self, Dispatcher, Subscription};
+use crate::frames::PacketKind;
+use crate::models::WheelsVector;
+use crate::models::enums::{ActualTyreCompound, PitStatus, VisualTyreCompound};
+use crate::packets::Packets;
+use crate::session_state::MAX_CARS;
+use crate::telemetry::{CarSnapshot, Frame};
+
+/// Wear in percent at which a tyre is taken as worn out.
+pub const DEFAULT_WEAR_THRESHOLD: f32 = 70.0;
+
+/// The last lap a threshold lap is forecast for, lap numbers of the game don't go beyond.
+const FORECAST_HORIZON: f32 = u8::MAX as f32;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum Wheel {
+    RearLeft,
+    RearRight,
+    FrontLeft,
+    FrontRight,
+}
+
+impl Wheel {
+    pub fn of<T: Copy>(self, vector: &WheelsVector<T>) -> T {
+        match self {
+            Wheel::RearLeft => vector.rear_left,
+            Wheel::RearRight => vector.rear_right,
+            Wheel::FrontLeft => vector.front_left,
+            Wheel::FrontRight => vector.front_right,
+        }
+    }
+}
+
+const WHEELS: [Wheel; 4] = [Wheel::RearLeft, Wheel::RearRight, Wheel::FrontLeft, Wheel::FrontRight];
+
+/// Slopes of the least squares lines through the wear of each wheel, `None` until two laps were
+/// sampled.
+fn fit(samples: &[(u8, WheelsVector<f32>)]) -> Option<WheelsVector<f32>> {
+    let count = samples.len() as f32;
+    let mean_lap = samples.iter().map(|(lap, _)| *lap as f32).sum::<f32>() / count;
+    let variance: f32 = samples.iter().map(|(lap, _)| (*lap as f32 - mean_lap).powi(2)).sum();
+    if variance <= 0.0 {
+        return None;
+    }
+
+    let slope = |wheel: Wheel| {
+        let mean_wear = samples.iter().map(|(_, wear)| wheel.of(wear)).sum::<f32>() / count;
+        let covariance: f32 = samples.iter()
+            .map(|(lap, wear)| (*lap as f32 - mean_lap) * (wheel.of(wear) - mean_wear))
+            .sum();
+        covariance / variance
+    };
+
+    Some(WheelsVector {
+        rear_left: slope(Wheel::RearLeft),
+        rear_right: slope(Wheel::RearRight),
+        front_left: slope(Wheel::FrontLeft),
+        front_right: slope(Wheel::FrontRight),
+    })
+}
+
+/// The wear forecast of the tyres a car is running, wear is in percent.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct TyreForecast {
+    pub car_idx: u8,
+    pub actual_compound: ActualTyreCompound,
+    pub visual_compound: VisualTyreCompound,
+    /// The lap the stint started on.
+    pub stint_start_lap: u8,
+    /// Completed laps the wear rates are fitted to.
+    pub laps_fitted: u8,
+    pub wear: WheelsVector<f32>,
+    /// Wear per lap, `None` until two laps of the stint were completed.
+    pub wear_rate: Option<WheelsVector<f32>>,
+    /// The lap the first tyre crosses the threshold on, `None` while no tyre wears or it
+    /// wouldn't be reached within the lap numbers of the game.
+    pub threshold_lap: Option<u16>,
+    pub critical_wheel: Option<Wheel>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Stint {
+    compound: Option<(ActualTyreCompound, VisualTyreCompound)>,
+    start_lap: u8,
+    /// Lap number and wear at the start of each completed lap.
+    samples: Vec<(u8, WheelsVector<f32>)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarTyres {
+    stint: Stint,
+    pit_stops: u8,
+    tyres_age_laps: u8,
+    forecast: Option<TyreForecast>,
+}
+
+/// Forecasts the tyre wear of every car from the wear at the start of each lap.
+///
+/// The wear rate of every wheel is fitted to the laps of the current stint, a stint ends when
+/// the car pits, changes compound or its tyres get younger. Laps started in the pit lane aren't
+/// sampled.
+#[derive(Debug, Clone)]
+pub struct TyreAnalytics {
+    threshold: f32,
+    frame: Option<Frame>,
+    cars: Vec<CarTyres>,
+}
+
+impl Default for TyreAnalytics {
+    fn default() -> TyreAnalytics {
+        TyreAnalytics::with_threshold(DEFAULT_WEAR_THRESHOLD)
+    }
+}
+
+impl TyreAnalytics {
+    pub fn new() -> TyreAnalytics {
+        TyreAnalytics::default()
+    }
+
+    /// Forecasts the lap a tyre reaches `threshold` percent of wear.
+    pub fn with_threshold(threshold: f32) -> TyreAnalytics {
+        TyreAnalytics {
+            threshold,
+            frame: None,
+            cars: vec![CarTyres::default(); MAX_CARS],
+        }
+    }
+
+    /// Creates analytics fed by every packet dispatched by `dispatcher`, the updated forecasts
+    /// are emitted back to the same dispatcher.
+    pub fn attach(dispatcher: &Dispatcher) -> (Arc<RwLock<TyreAnalytics>>, Subscription) {
+        TyreAnalytics::new().attach_to(dispatcher)
+    }
+
+    pub fn attach_to(self, dispatcher: &Dispatcher) -> (Arc<RwLock<TyreAnalytics>>, Subscription) {
+        event_system::attach(self, dispatcher, TyreAnalytics::ingest, |forecast, dispatcher| dispatcher.emit(forecast))
+    }
+
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// The forecast as of the last lap `car_idx` started.
+    pub fn forecast(&self, car_idx: usize) -> Option<&TyreForecast> {
+        self.cars.get(car_idx)?.forecast.as_ref()
+    }
+
+    pub fn forecasts(&self) -> impl Iterator<Item = &TyreForecast> {
+        self.cars.iter().filter_map(|car| car.forecast.as_ref())
+    }
+
+    pub fn reset(&mut self) {
+        *self = TyreAnalytics::with_threshold(self.threshold);
+    }
+
+    /// Updates the stints and returns the forecasts of the cars that started a lap.
+    pub fn ingest(&mut self, packet: &Packets) -> Vec<TyreForecast> {
+        let header = match packet.header() {
+            Some(header) => header,
+            None => return vec![],
+        };
+        if self.frame.as_ref().is_some_and(|frame| frame.session_uid != header.session_uid) {
+            self.reset();
+        }
+
+        let frame = self.frame.get_or_insert_with(|| Frame::new(header));
+        let previous_laps: Vec<Option<u8>> = frame.cars.iter().map(|car| car.lap.map(|lap| lap.current_lap_num)).collect();
+        if !frame.update(packet) {
+            return vec![];
+        }
+
+        let mut forecasts = vec![];
+        for (car_idx, (car, snapshot)) in self.cars.iter_mut().zip(&frame.cars).enumerate() {
+            car.follow_stint(snapshot);
+
+            let started_lap = match (previous_laps[car_idx], snapshot.lap) {
+                (Some(previous), Some(lap)) if PacketKind::of(packet) == Some(PacketKind::LapData) => lap.current_lap_num > previous,
+                _ => false,
+            };
+            if started_lap {
+                if let Some(forecast) = car.sample(car_idx as u8, snapshot, self.threshold) {
+                    forecasts.push(forecast);
+                }
+            }
+        }
+
+        forecasts
+    }
+}
+
+impl CarTyres {
+    /// Starts a new stint when the tyres were changed.
+    fn follow_stint(&mut self, snapshot: &CarSnapshot) {
+        let (lap, status) = match (snapshot.lap, snapshot.status) {
+            (Some(lap), Some(status)) => (lap, status),
+            _ => return,
+        };
+
+        let compound = (status.actual_tyre_compound, status.visual_tyre_compound);
+        let changed = self.stint.compound.is_some_and(|current| current != compound)
+            || lap.num_pit_stops > self.pit_stops
+            || status.tyres_age_laps < self.tyres_age_laps;
+        if changed || self.stint.compound.is_none() {
+            self.stint = Stint {
+                compound: Some(compound),
+                start_lap: lap.current_lap_num,
+                samples: vec![],
+            };
+        }
+
+        self.pit_stops = lap.num_pit_stops;
+        self.tyres_age_laps = status.tyres_age_laps;
+    }
+
+    fn sample(&mut self, car_idx: u8, snapshot: &CarSnapshot, threshold: f32) -> Option<TyreForecast> {
+        let lap = snapshot.lap?;
+        let wear = snapshot.damage?.tyre_wear;
+        let (actual_compound, visual_compound) = self.stint.compound?;
+        if lap.pit_status != PitStatus::None {
+            return None;
+        }
+        self.stint.samples.push((lap.current_lap_num, wear));
+
+        let wear_rate = fit(&self.stint.samples);
+
+        // The wheel that reaches the threshold first and the laps it takes, wheels that don't
+        // wear never do.
+        let remaining = wear_rate.and_then(|rate| WHEELS.into_iter()
+            .filter(|wheel| wheel.of(&rate) > 0.0)
+            .map(|wheel| (wheel, ((threshold - wheel.of(&wear)) / wheel.of(&rate)).max(0.0)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b)));
+        let threshold_lap = remaining
+            .map(|(_, laps)| lap.current_lap_num as f32 + laps.floor())
+            .filter(|threshold_lap| *threshold_lap <= FORECAST_HORIZON);
+
+        let forecast = TyreForecast {
+            car_idx,
+            actual_compound,
+            visual_compound,
+            stint_start_lap: self.stint.start_lap,
+            laps_fitted: self.stint.samples.len().saturating_sub(1) as u8,
+            wear,
+            wear_rate,
+            threshold_lap: threshold_lap.map(|threshold_lap| threshold_lap as u16),
+            critical_wheel: threshold_lap.and(remaining).map(|(wheel, _)| wheel),
+        };
+        self.forecast = Some(forecast);
+        Some(forecast)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PacketCarDamage, PacketCarStatus, PacketLapData};
+    use crate::testing::packet;
+
+    const SOFT: (ActualTyreCompound, VisualTyreCompound) = (ActualTyreCompound::C3, VisualTyreCompound::Soft);
+    const MEDIUM: (ActualTyreCompound, VisualTyreCompound) = (ActualTyreCompound::C2, VisualTyreCompound::Medium);
+
+    fn lap(lap_num: u8, pit_stops: u8) -> Packets {
+        let mut packet = packet::<PacketLapData>(0.0);
+        packet.lap_data[0].current_lap_num = lap_num;
+        packet.lap_data[0].num_pit_stops = pit_stops;
+        Packets::LapData(packet)
+    }
+
+    fn status((actual, visual): (ActualTyreCompound, VisualTyreCompound), age: u8) -> Packets {
+        let mut packet = packet::<PacketCarStatus>(0.0);
+        let status = &mut packet.car_status_data[0];
+        status.actual_tyre_compound = actual;
+        status.visual_tyre_compound = visual;
+        status.tyres_age_laps = age;
+        Packets::CarStatus(packet)
+    }
+
+    fn wear(rear: f32, front: f32) -> Packets {
+        let mut packet = packet::<PacketCarDamage>(0.0);
+        packet.car_damage_data[0].tyre_wear = WheelsVector { rear_left: rear, rear_right: rear, front_left: front, front_right: front };
+        Packets::CarDamage(packet)
+    }
+
+    #[test]
+    fn forecasts_the_threshold_lap() {
+        let mut analytics = TyreAnalytics::new();
+        analytics.ingest(&status(SOFT, 0));
+        analytics.ingest(&lap(1, 0));
+
+        // The fronts wear 4% and the rears 3% per lap.
+        for lap_num in 2..=5u8 {
+            let laps = (lap_num - 1) as f32;
+            analytics.ingest(&wear(3.0 * laps, 4.0 * laps));
+            analytics.ingest(&status(SOFT, lap_num - 1));
+            let forecasts = analytics.ingest(&lap(lap_num, 0));
+            assert_eq!(forecasts.len(), 1, "one forecast per started lap");
+        }
+
+        let forecast = analytics.forecast(0).unwrap();
+        assert_eq!(forecast.laps_fitted, 3);
+        assert!((forecast.wear_rate.unwrap().front_left - 4.0).abs() < 0.001);
+        // 16% after 4 laps, 54% to go at 4% per lap is 13.5 laps from lap 5.
+        assert_eq!(forecast.threshold_lap, Some(18));
+        assert_eq!(forecast.critical_wheel, Some(Wheel::FrontLeft));
+    }
+
+    #[test]
+    fn near_zero_wear_has_no_threshold_lap() {
+        let mut analytics = TyreAnalytics::new();
+        analytics.ingest(&status(SOFT, 0));
+        analytics.ingest(&lap(1, 0));
+        analytics.ingest(&wear(0.0, 0.0));
+        analytics.ingest(&lap(2, 0));
+        analytics.ingest(&wear(0.0001, 0.0001));
+        let forecasts = analytics.ingest(&lap(3, 0));
+
+        assert!(forecasts[0].wear_rate.unwrap().front_left > 0.0);
+        assert_eq!(forecasts[0].threshold_lap, None);
+        assert_eq!(forecasts[0].critical_wheel, None);
+    }
+
+    #[test]
+    fn pit_stops_and_compound_changes_start_a_new_stint() {
+        let mut analytics = TyreAnalytics::new();
+        analytics.ingest(&status(SOFT, 0));
+        analytics.ingest(&lap(1, 0));
+        analytics.ingest(&wear(10.0, 10.0));
+        analytics.ingest(&lap(2, 0));
+        analytics.ingest(&lap(3, 0));
+        assert!(analytics.forecast(0).unwrap().wear_rate.is_some());
+
+        analytics.ingest(&lap(3, 1));
+        analytics.ingest(&wear(0.0, 0.0));
+        let forecasts = analytics.ingest(&lap(4, 1));
+        assert_eq!((forecasts[0].stint_start_lap, forecasts[0].laps_fitted), (3, 0));
+        assert_eq!(forecasts[0].wear_rate, None);
+
+        analytics.ingest(&status(MEDIUM, 1));
+        let forecasts = analytics.ingest(&lap(5, 1));
+        assert_eq!(forecasts[0].visual_compound, VisualTyreCompound::Medium);
+        assert_eq!(forecasts[0].stint_start_lap, 4);
+    }
+}
@@ -0,0 +1,331 @@
+//! Pushes decoded packets as JSON to WebSocket clients, for example browser overlays.
+//!
+//! Every client receives all packets until it sends a subscription message:
+//!
+//! ```json
+//! { "packets": ["lap_data", "car_telemetry"], "car": 0, "max_rate": 10 }
+//! ```
+//!
+//! All fields are optional and fields left out keep their previous value. `packets` takes the
+//! names of [`crate::packets::PACKET_NAMES`] and `car` limits per car packets to the entry of that
+//! car. `max_rate` lowers the number of messages per second and packet type below the limit of
+//! the server, events are never held back. Each packet is sent as
+//! `{ "packet": "<name>", "header": { .. }, "car": <index>, "data": { .. } }`.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::{Error, Message};
+use tokio_util::sync::CancellationToken;
+use crate::event_system::{Dispatcher, Subscription};
+use crate::models::{PacketEventData, PacketHeader};
+use crate::models::traits::Packet;
+use crate::packets::{packet_id_by_name, PacketFilter, Packets, Selection};
+
+/// Packets buffered per client before a slow client starts skipping packets.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A message a client sends to choose what it receives.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct SubscribeMessage {
+    pub packets: Option<Vec<String>>,
+    pub car: Option<usize>,
+    pub max_rate: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct OutgoingMessage<'a> {
+    packet: &'static str,
+    header: Option<&'a PacketHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    car: Option<usize>,
+    data: Selection<'a>,
+}
+
+/// What a single client subscribed to and when it was last sent each packet type.
+#[derive(Debug, Clone)]
+pub struct ClientFilter {
+    filter: PacketFilter,
+    min_interval: Option<Duration>,
+    server_min_interval: Option<Duration>,
+    last_sent: HashMap<u8, Instant>,
+}
+
+/// The time between two messages at `max_rate`, fails for rates too small to wait for.
+fn interval(max_rate: Option<f32>) -> Result<Option<Duration>, String> {
+    max_rate.filter(|rate| *rate > 0.0)
+        .map(|rate| Duration::try_from_secs_f64(1.0 / rate as f64).map_err(|_| format!("max_rate {} is too small", rate)))
+        .transpose()
+}
+
+impl ClientFilter {
+    /// Creates a filter passing every packet, a `max_rate` too small to wait for sends each
+    /// packet type once.
+    pub fn new(max_rate: Option<f32>) -> ClientFilter {
+        let min_interval = interval(max_rate).unwrap_or(Some(Duration::MAX));
+        ClientFilter {
+            filter: PacketFilter::default(),
+            min_interval,
+            server_min_interval: min_interval,
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// Applies the fields of a subscription message, fails on unknown packet names and rates too
+    /// small to wait for without changing the filter.
+    pub fn update(&mut self, message: SubscribeMessage) -> Result<(), String> {
+        let packets = match message.packets {
+            Some(names) => Some(names.iter()
+                .map(|name| packet_id_by_name(name).ok_or_else(|| format!("unknown packet type {}", name)))
+                .collect::<Result<Vec<u8>, String>>()?),
+            None => None,
+        };
+        let client_min_interval = interval(message.max_rate)?;
+
+        if packets.is_some() {
+            self.filter.packets = packets;
+        }
+        if message.car.is_some() {
+            self.filter.car = message.car;
+        }
+        if message.max_rate.is_some() {
+            self.min_interval = match (client_min_interval, self.server_min_interval) {
+                (Some(client), Some(server)) => Some(client.max(server)),
+                (client, server) => client.or(server),
+            };
+        }
+        Ok(())
+    }
+
+    /// Serializes `packet` if the client subscribed to it and its rate limit allows it.
+    pub fn message(&mut self, packet: &Packets, now: Instant) -> Option<String> {
+        let packet_id = packet.packet_id()?;
+        let data = self.filter.select(packet)?;
+
+        // Events happen once, dropping them would lose them.
+        if packet_id != PacketEventData::PACKET_ID {
+            if let (Some(min_interval), Some(last_sent)) = (self.min_interval, self.last_sent.get(&packet_id)) {
+                if now.duration_since(*last_sent) < min_interval {
+                    return None;
+                }
+            }
+            self.last_sent.insert(packet_id, now);
+        }
+
+        serde_json::to_string(&OutgoingMessage {
+            packet: packet.name(),
+            header: packet.header(),
+            car: self.filter.car,
+            data,
+        }).ok()
+    }
+}
+
+/// Accepts WebSocket clients and pushes every published packet to them.
+pub struct WebSocketServer {
+    listener: TcpListener,
+    sender: broadcast::Sender<Arc<Packets>>,
+    max_rate: Option<f32>,
+    shutdown: CancellationToken,
+}
+
+impl WebSocketServer {
+    pub async fn bind(address: &str) -> io::Result<WebSocketServer> {
+        Ok(WebSocketServer {
+            listener: TcpListener::bind(address).await?,
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            max_rate: None,
+            shutdown: CancellationToken::new(),
+        })
+    }
+
+    /// Limits the messages per second and packet type sent to each client.
+    pub fn with_max_rate(mut self, max_rate: f32) -> WebSocketServer {
+        self.max_rate = Some(max_rate);
+        self
+    }
+
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> WebSocketServer {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Returns a handle to push packets to all connected clients.
+    pub fn publisher(&self) -> Publisher {
+        Publisher {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Publishes every packet dispatched by `dispatcher` until the subscription is dropped.
+    pub fn attach(&self, dispatcher: &Dispatcher) -> Subscription {
+        let publisher = self.publisher();
+        dispatcher.subscribe(move |packet: &Packets| publisher.publish(packet.clone()))
+    }
+
+    /// Accepts clients until the shutdown token is cancelled.
+    pub async fn run(self) {
+        loop {
+            let stream = tokio::select! {
+                _ = self.shutdown.cancelled() => return,
+                stream = self.listener.accept() => stream,
+            };
+
+            match stream {
+                Ok((stream, _)) => {
+                    let packets = self.sender.subscribe();
+                    let filter = ClientFilter::new(self.max_rate);
+                    let shutdown = self.shutdown.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve_client(stream, packets, filter, shutdown).await {
+                            println!("WebSocket error: {}", e);
+                        }
+                    });
+                },
+                Err(e) => println!("Error: {}", e),
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Publisher {
+    sender: broadcast::Sender<Arc<Packets>>,
+}
+
+impl Publisher {
+    pub fn publish(&self, packet: Packets) {
+        // Sending only fails without connected clients, the packet is dropped then.
+        let _ = self.sender.send(Arc::new(packet));
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+async fn serve_client(
+    stream: TcpStream,
+    mut packets: broadcast::Receiver<Arc<Packets>>,
+    mut filter: ClientFilter,
+    shutdown: CancellationToken,
+) -> Result<(), Error> {
+    let (mut sink, mut source) = tokio_tungstenite::accept_async(stream).await?.split();
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => return sink.close().await,
+            message = source.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let result = serde_json::from_str::<SubscribeMessage>(&text)
+                        .map_err(|e| e.to_string())
+                        .and_then(|message| filter.update(message));
+                    if let Err(error) = result {
+                        let reply = serde_json::json!({ "error": error }).to_string();
+                        sink.send(Message::Text(reply.into())).await?;
+                    }
+                },
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => {},
+                Some(Err(e)) => return Err(e),
+            },
+            packet = packets.recv() => match packet {
+                Ok(packet) => {
+                    if let Some(message) = filter.message(&packet, Instant::now()) {
+                        sink.send(Message::Text(message.into())).await?;
+                    }
+                },
+                Err(RecvError::Lagged(_)) => {},
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PacketLapData;
+    use crate::testing;
+
+    fn lap_data() -> Packets {
+        let mut packet = testing::packet::<PacketLapData>(0.0);
+        packet.lap_data[3].car_position = 7;
+        Packets::LapData(packet)
+    }
+
+    #[test]
+    fn filters_by_subscription() {
+        let mut filter = ClientFilter::new(None);
+        let now = Instant::now();
+        assert!(filter.message(&lap_data(), now).unwrap().contains("\"lap_data\":["));
+
+        filter.update(SubscribeMessage {
+            packets: Some(vec!["car_telemetry".to_string()]),
+            ..SubscribeMessage::default()
+        }).unwrap();
+        assert!(filter.message(&lap_data(), now).is_none());
+
+        filter.update(SubscribeMessage {
+            packets: Some(vec!["lap_data".to_string()]),
+            car: Some(3),
+            max_rate: None,
+        }).unwrap();
+        let message: serde_json::Value = serde_json::from_str(&filter.message(&lap_data(), now).unwrap()).unwrap();
+        assert_eq!(message["packet"], "lap_data");
+        assert_eq!(message["car"], 3);
+        assert_eq!(message["data"]["car_position"], 7);
+
+        assert!(filter.update(SubscribeMessage {
+            packets: Some(vec!["nope".to_string()]),
+            ..SubscribeMessage::default()
+        }).is_err());
+
+        // Fields left out keep the subscription.
+        filter.update(SubscribeMessage { max_rate: Some(5.0), ..SubscribeMessage::default() }).unwrap();
+        let message: serde_json::Value = serde_json::from_str(&filter.message(&lap_data(), now + Duration::from_secs(1)).unwrap()).unwrap();
+        assert_eq!(message["car"], 3);
+    }
+
+    #[test]
+    fn limits_the_send_rate() {
+        let mut filter = ClientFilter::new(Some(10.0));
+        let now = Instant::now();
+
+        assert!(filter.message(&lap_data(), now).is_some());
+        assert!(filter.message(&lap_data(), now + Duration::from_millis(50)).is_none());
+        assert!(filter.message(&lap_data(), now + Duration::from_millis(100)).is_some());
+
+        // Clients can only lower the rate below the limit of the server.
+        filter.update(SubscribeMessage { max_rate: Some(100.0), ..SubscribeMessage::default() }).unwrap();
+        assert!(filter.message(&lap_data(), now + Duration::from_millis(150)).is_none());
+
+        assert!(filter.update(SubscribeMessage { max_rate: Some(f32::MIN_POSITIVE / 1e6), ..SubscribeMessage::default() }).is_err());
+        assert!(filter.message(&lap_data(), now + Duration::from_millis(200)).is_some());
+    }
+
+    #[test]
+    fn events_are_not_rate_limited() {
+        let mut filter = ClientFilter::new(Some(1.0));
+        let now = Instant::now();
+        let event = testing::received(&testing::packet::<PacketEventData>(0.0));
+
+        assert!(filter.message(&event, now).is_some());
+        assert!(filter.message(&event, now).is_some());
+    }
+}